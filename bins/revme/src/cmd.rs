@@ -1,4 +1,5 @@
 pub mod bench;
+pub mod blocktest;
 pub mod bytecode;
 pub mod eofvalidation;
 pub mod evmrunner;
@@ -12,6 +13,8 @@ use clap::Parser;
 pub enum MainCmd {
     /// Execute Ethereum state tests.
     Statetest(statetest::Cmd),
+    /// Execute Ethereum blockchain tests.
+    Blocktest(blocktest::Cmd),
     /// Execute eof validation tests.
     EofValidation(eofvalidation::Cmd),
     /// Run arbitrary EVM bytecode.
@@ -27,6 +30,8 @@ pub enum Error {
     #[error(transparent)]
     Statetest(#[from] statetest::Error),
     #[error(transparent)]
+    Blocktest(#[from] blocktest::Error),
+    #[error(transparent)]
     EvmRunnerErrors(#[from] evmrunner::Errors),
     #[error("Eof validation failed: {:?}/{total_tests}", total_tests-failed_test)]
     EofValidation {
@@ -41,6 +46,7 @@ impl MainCmd {
     pub fn run(&self) -> Result<(), Error> {
         match self {
             Self::Statetest(cmd) => cmd.run().map_err(Into::into),
+            Self::Blocktest(cmd) => cmd.run().map_err(Into::into),
             Self::EofValidation(cmd) => cmd.run().map_err(Into::into),
             Self::Evm(cmd) => cmd.run().map_err(Into::into),
             Self::Bytecode(cmd) => {
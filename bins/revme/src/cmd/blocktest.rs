@@ -0,0 +1,34 @@
+mod runner;
+
+pub use runner::TestError as Error;
+
+use crate::dir_utils::find_all_json_tests;
+use clap::Parser;
+use runner::{run, TestError};
+use std::path::PathBuf;
+
+/// `blocktest` subcommand.
+#[derive(Parser, Debug)]
+pub struct Cmd {
+    /// Path to folder or file containing the tests. If multiple paths are specified
+    /// they will be run in sequence.
+    ///
+    /// Folders will be searched recursively for files with the extension `.json`.
+    #[clap(required = true, num_args = 1..)]
+    paths: Vec<PathBuf>,
+    /// Keep going after a test failure.
+    #[clap(long, alias = "no-fail-fast")]
+    keep_going: bool,
+}
+
+impl Cmd {
+    /// Run blocktest command.
+    pub fn run(&self) -> Result<(), TestError> {
+        for path in &self.paths {
+            println!("\nRunning tests in {}...", path.display());
+            let test_files = find_all_json_tests(path);
+            run(test_files, self.keep_going)?
+        }
+        Ok(())
+    }
+}
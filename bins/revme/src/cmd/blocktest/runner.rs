@@ -0,0 +1,708 @@
+use alloy_rlp::RlpEncodable;
+use database::{
+    trie::{ordered_trie_root, sec_trie_root, TrieAccount},
+    CacheState, State,
+};
+use indicatif::{ProgressBar, ProgressDrawTarget};
+use revm::{
+    bytecode::Bytecode,
+    database_interface::EmptyDB,
+    executor::{Block, BlockExecutor, Ommer, Withdrawal},
+    primitives::{alloy_primitives::Bloom, keccak256, Address, TxKind, B256, U256},
+    specification::{eip7702::AuthorizationList, hardfork::SpecId},
+    wiring::default::{block::BlockEnv, TxEnv},
+};
+use statetest_types::{
+    BlockchainTestSuite, BlockchainTestUnit, SpecName, TestBlock, TestBlockHeader, TestTransaction,
+};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+#[error("Test {name} failed: {kind}")]
+pub struct TestError {
+    pub name: String,
+    pub kind: TestErrorKind,
+}
+
+#[derive(Debug, Error)]
+pub enum TestErrorKind {
+    #[error("genesis state root mismatch: got {got}, expected {expected}")]
+    GenesisStateRootMismatch { got: B256, expected: B256 },
+    #[error("block {index} has no header")]
+    MissingBlockHeader { index: usize },
+    #[error("block {number}: transaction {index} has an unknown type")]
+    UnknownTransactionType { number: u64, index: usize },
+    #[error("block {number}: transaction {index} has no sender")]
+    MissingSender { number: u64, index: usize },
+    #[error("block {number}: invalid header: {reason}")]
+    InvalidHeader { number: u64, reason: &'static str },
+    #[error("block {number}: invalid RLP: {error}")]
+    InvalidRlp {
+        number: u64,
+        error: alloy_rlp::Error,
+    },
+    #[error("block {number}: expected exception {exception} but the block was accepted")]
+    BlockNotRejected { number: u64, exception: String },
+    #[error("block {number}: execution failed: {error}")]
+    BlockExecution { number: u64, error: String },
+    #[error("block {number}: gas used mismatch: got {got}, expected {expected}")]
+    GasUsedMismatch {
+        number: u64,
+        got: u64,
+        expected: u64,
+    },
+    #[error("block {number}: {root} mismatch: got {got}, expected {expected}")]
+    RootMismatch {
+        number: u64,
+        root: &'static str,
+        got: B256,
+        expected: B256,
+    },
+    #[error("block {number}: withdrawals root mismatch: got {got:?}, expected {expected:?}")]
+    WithdrawalsRootMismatch {
+        number: u64,
+        got: Option<B256>,
+        expected: Option<B256>,
+    },
+    #[error("block {number}: requests hash mismatch: got {got:?}, expected {expected:?}")]
+    RequestsHashMismatch {
+        number: u64,
+        got: Option<B256>,
+        expected: Option<B256>,
+    },
+    #[error("block {number}: logs bloom mismatch: got {got}, expected {expected}")]
+    LogsBloomMismatch {
+        number: u64,
+        got: Box<Bloom>,
+        expected: Box<Bloom>,
+    },
+    #[error("last block hash mismatch: got {got}, expected {expected}")]
+    LastBlockHashMismatch { got: B256, expected: B256 },
+    #[error("post state mismatch for {address}: {reason}")]
+    PostStateMismatch { address: Address, reason: String },
+    #[error(transparent)]
+    SerdeDeserialize(#[from] serde_json::Error),
+}
+
+fn skip_test(path: &Path) -> bool {
+    let name = path.file_name().unwrap().to_str().unwrap();
+
+    matches!(
+        name,
+        // funky test with `bigint 0x00` value in json :) not possible to happen on mainnet and require
+        // custom json parser. https://github.com/ethereum/tests/issues/971
+        "ValueOverflow.json" | "ValueOverflowParis.json"
+    )
+}
+
+/// Returns why the tests of the network are not run, `None` if they are.
+fn skip_network(network: &SpecName) -> Option<&'static str> {
+    match network {
+        // Constantinople was immediately extended by Petersburg.
+        SpecName::Constantinople | SpecName::ByzantiumToConstantinopleAt5 => {
+            Some("Constantinople is not supported")
+        }
+        SpecName::HomesteadToDaoAt5 => {
+            Some("the irregular state change of the DAO fork is not applied")
+        }
+        SpecName::Osaka => Some("Osaka is not implemented"),
+        SpecName::Unknown => Some("unknown network"),
+        _ => None,
+    }
+}
+
+/// Tests and blocks that were not run, reported after the run.
+#[derive(Debug, Default)]
+pub struct Skipped {
+    /// Tests of networks that are not supported.
+    pub tests: usize,
+    /// Blocks expected to be rejected that have no decoded content.
+    pub invalid_blocks: usize,
+}
+
+pub fn execute_test_suite(
+    path: &Path,
+    elapsed: &mut Duration,
+    skipped: &mut Skipped,
+) -> Result<(), TestError> {
+    if skip_test(path) {
+        return Ok(());
+    }
+
+    let s = std::fs::read_to_string(path).unwrap();
+    let suite: BlockchainTestSuite = serde_json::from_str(&s).map_err(|e| TestError {
+        name: path.to_string_lossy().into_owned(),
+        kind: e.into(),
+    })?;
+
+    for (name, unit) in suite.0 {
+        if let Some(reason) = skip_network(&unit.network) {
+            println!("Skipping {name}: {reason}");
+            skipped.tests += 1;
+            continue;
+        }
+        let timer = Instant::now();
+        let result = execute_test_unit(&unit, skipped);
+        *elapsed += timer.elapsed();
+        result.map_err(|kind| TestError { name, kind })?;
+    }
+    Ok(())
+}
+
+/// Executes the blocks of the test and checks their headers and the post state.
+///
+/// Blocks expected to be rejected are executed on a copy of the state, and must fail
+/// or mismatch their header. Those without decoded content are counted as skipped.
+fn execute_test_unit(
+    unit: &BlockchainTestUnit,
+    skipped: &mut Skipped,
+) -> Result<(), TestErrorKind> {
+    let mut cache_state = CacheState::new(false);
+    for (address, info) in &unit.pre {
+        let acc_info = revm::state::AccountInfo {
+            balance: info.balance,
+            code_hash: keccak256(&info.code),
+            code: Some(Bytecode::new_raw(info.code.clone()).into_analyzed()),
+            nonce: info.nonce,
+        };
+        cache_state.insert_account_with_storage(*address, acc_info, info.storage.clone());
+    }
+
+    let genesis = &unit.genesis_block_header;
+    let state_root = cache_state_root(&cache_state);
+    if state_root != genesis.state_root {
+        return Err(TestErrorKind::GenesisStateRootMismatch {
+            got: state_root,
+            expected: genesis.state_root,
+        });
+    }
+
+    let state = State::builder()
+        .with_cached_prestate(cache_state)
+        .with_bundle_update()
+        .with_block_hashes(BTreeMap::from([(
+            genesis.number.saturating_to(),
+            genesis.hash,
+        )]))
+        .build();
+    let mut executor = new_executor(state);
+
+    let mut parent = genesis;
+    for (index, block) in unit.blocks.iter().enumerate() {
+        let Some((decoded, header)) = block.decoded() else {
+            if block.expect_exception.is_some() {
+                skipped.invalid_blocks += 1;
+                continue;
+            }
+            return Err(TestErrorKind::MissingBlockHeader { index });
+        };
+        let number: u64 = header.number.saturating_to();
+
+        if let Some(exception) = &block.expect_exception {
+            // The block must not change the state, so it is executed on a copy.
+            let state = State::builder()
+                .with_cached_prestate(executor.state().cache.clone())
+                .with_bundle_update()
+                .with_block_hashes(executor.state().block_hashes.clone())
+                .build();
+            let result = execute_block(
+                &mut new_executor(state),
+                &unit.network,
+                &block.rlp,
+                decoded,
+                header,
+                parent,
+            );
+            if result.is_ok() {
+                return Err(TestErrorKind::BlockNotRejected {
+                    number,
+                    exception: exception.clone(),
+                });
+            }
+            continue;
+        }
+
+        execute_block(
+            &mut executor,
+            &unit.network,
+            &block.rlp,
+            decoded,
+            header,
+            parent,
+        )?;
+        executor
+            .state_mut()
+            .block_hashes
+            .insert(number, header.hash);
+        parent = header;
+    }
+
+    if parent.hash != unit.last_block_hash {
+        return Err(TestErrorKind::LastBlockHashMismatch {
+            got: parent.hash,
+            expected: unit.last_block_hash,
+        });
+    }
+
+    if let Some(post_state) = &unit.post_state {
+        check_post_state(executor.state(), post_state)?;
+    }
+    Ok(())
+}
+
+/// Returns an executor computing the state roots of the receipts before Byzantium.
+fn new_executor(state: State<EmptyDB>) -> BlockExecutor<EmptyDB> {
+    BlockExecutor::from_state(state)
+        .with_intermediate_state_root(|state| cache_state_root(&state.cache))
+}
+
+/// Checks the header against its parent, executes the block and checks the header against
+/// the execution.
+fn execute_block(
+    executor: &mut BlockExecutor<EmptyDB>,
+    network: &SpecName,
+    rlp: &[u8],
+    block: &TestBlock,
+    header: &TestBlockHeader,
+    parent: &TestBlockHeader,
+) -> Result<(), TestErrorKind> {
+    let number: u64 = header.number.saturating_to();
+    check_header(header, parent)
+        .map_err(|reason| TestErrorKind::InvalidHeader { number, reason })?;
+
+    let transactions_root =
+        transactions_root(rlp).map_err(|error| TestErrorKind::InvalidRlp { number, error })?;
+    check_root(
+        number,
+        "transactions root",
+        transactions_root,
+        header.transactions_trie,
+    )?;
+
+    let withdrawals_root = block.withdrawals.as_ref().map(|withdrawals| {
+        ordered_trie_root(withdrawals.iter().map(|withdrawal| {
+            alloy_rlp::encode(WithdrawalRlp {
+                index: withdrawal.index.saturating_to(),
+                validator_index: withdrawal.validator_index.saturating_to(),
+                address: withdrawal.address,
+                amount: withdrawal.amount.saturating_to(),
+            })
+        }))
+    });
+    if withdrawals_root != header.withdrawals_root {
+        return Err(TestErrorKind::WithdrawalsRootMismatch {
+            number,
+            got: withdrawals_root,
+            expected: header.withdrawals_root,
+        });
+    }
+
+    let spec_id = network.to_spec_id_at(number, header.timestamp.saturating_to());
+    let output = executor
+        .execute_block(spec_id, &block_from_test(block, header, spec_id)?)
+        .map_err(|error| TestErrorKind::BlockExecution {
+            number,
+            error: error.to_string(),
+        })?;
+
+    let expected = header.gas_used.saturating_to();
+    if output.gas_used != expected {
+        return Err(TestErrorKind::GasUsedMismatch {
+            number,
+            got: output.gas_used,
+            expected,
+        });
+    }
+
+    check_root(
+        number,
+        "receipts root",
+        output.receipts_root(),
+        header.receipt_trie,
+    )?;
+
+    let logs_bloom = output.logs_bloom();
+    if logs_bloom != header.bloom {
+        return Err(TestErrorKind::LogsBloomMismatch {
+            number,
+            got: Box::new(logs_bloom),
+            expected: Box::new(header.bloom),
+        });
+    }
+
+    let requests_hash = output.requests_hash();
+    if requests_hash != header.requests_hash {
+        return Err(TestErrorKind::RequestsHashMismatch {
+            number,
+            got: requests_hash,
+            expected: header.requests_hash,
+        });
+    }
+
+    check_root(
+        number,
+        "state root",
+        cache_state_root(&executor.state().cache),
+        header.state_root,
+    )
+}
+
+/// Checks the fields of the header that don't depend on the execution of the block.
+fn check_header(header: &TestBlockHeader, parent: &TestBlockHeader) -> Result<(), &'static str> {
+    if header.number != parent.number + U256::from(1) {
+        return Err("number is not the parent number plus one");
+    }
+    if header.parent_hash != parent.hash {
+        return Err("parent hash is not the hash of the parent");
+    }
+    if header.timestamp <= parent.timestamp {
+        return Err("timestamp is not after the parent timestamp");
+    }
+    if header.gas_used > header.gas_limit {
+        return Err("gas used is above the gas limit");
+    }
+    if header.extra_data.len() > 32 {
+        return Err("extra data is longer than 32 bytes");
+    }
+    Ok(())
+}
+
+fn check_root(
+    number: u64,
+    root: &'static str,
+    got: B256,
+    expected: B256,
+) -> Result<(), TestErrorKind> {
+    if got != expected {
+        return Err(TestErrorKind::RootMismatch {
+            number,
+            root,
+            got,
+            expected,
+        });
+    }
+    Ok(())
+}
+
+/// Computes the state root of the accounts of the cache.
+fn cache_state_root(cache: &CacheState) -> B256 {
+    sec_trie_root(cache.trie_account().into_iter().map(|(address, account)| {
+        let storage_root = sec_trie_root(
+            account
+                .storage
+                .iter()
+                .filter(|(_, value)| !value.is_zero())
+                .map(|(slot, value)| (slot.to_be_bytes::<32>(), alloy_rlp::encode(value))),
+        );
+        let account = TrieAccount {
+            nonce: account.info.nonce,
+            balance: account.info.balance,
+            storage_root,
+            code_hash: account.info.code_hash,
+        };
+        (address, account.encode())
+    }))
+}
+
+/// Computes the transactions root from the transactions of the block RLP.
+///
+/// Legacy transactions are RLP lists, typed transactions RLP strings holding their EIP-2718
+/// encoding.
+fn transactions_root(rlp: &[u8]) -> Result<B256, alloy_rlp::Error> {
+    let mut block = list_payload(rlp)?;
+    let header = alloy_rlp::Header::decode(&mut block)?;
+    let mut transactions = list_payload(block.get(header.payload_length..).unwrap_or_default())?;
+    let mut encoded = Vec::new();
+    while !transactions.is_empty() {
+        let item = transactions;
+        let header = alloy_rlp::Header::decode(&mut transactions)?;
+        let header_length = item.len() - transactions.len();
+        let end = header_length + header.payload_length;
+        let item = item.get(..end).ok_or(alloy_rlp::Error::InputTooShort)?;
+        encoded.push(if header.list {
+            item
+        } else {
+            &item[header_length..]
+        });
+        transactions = &transactions[header.payload_length..];
+    }
+    Ok(ordered_trie_root(encoded))
+}
+
+/// Returns the payload of the RLP list at the start of `buf`.
+fn list_payload(mut buf: &[u8]) -> Result<&[u8], alloy_rlp::Error> {
+    let header = alloy_rlp::Header::decode(&mut buf)?;
+    if !header.list {
+        return Err(alloy_rlp::Error::UnexpectedString);
+    }
+    buf.get(..header.payload_length)
+        .ok_or(alloy_rlp::Error::InputTooShort)
+}
+
+/// Withdrawal as it is stored in the withdrawals trie.
+#[derive(RlpEncodable)]
+struct WithdrawalRlp {
+    index: u64,
+    validator_index: u64,
+    address: Address,
+    amount: u64,
+}
+
+/// Builds the block to execute from its test representation.
+fn block_from_test(
+    block: &TestBlock,
+    header: &TestBlockHeader,
+    spec_id: SpecId,
+) -> Result<Block, TestErrorKind> {
+    let number = header.number.saturating_to();
+    let mut env = BlockEnv {
+        number: header.number,
+        coinbase: header.coinbase,
+        timestamp: header.timestamp,
+        gas_limit: header.gas_limit,
+        basefee: header.base_fee_per_gas.unwrap_or_default(),
+        difficulty: header.difficulty,
+        // after the Merge prevrandao replaces mix_hash field in block and replaced difficulty opcode in EVM.
+        prevrandao: spec_id
+            .is_enabled_in(SpecId::MERGE)
+            .then_some(header.mix_hash),
+        blob_excess_gas_and_price: None,
+    };
+    if let Some(excess_blob_gas) = header.excess_blob_gas {
+        env.set_blob_excess_gas_and_price(excess_blob_gas.saturating_to());
+    }
+
+    let transactions = block
+        .transactions
+        .iter()
+        .enumerate()
+        .map(|(index, tx)| tx_from_test(tx).ok_or_else(|| invalid_tx(tx, number, index)))
+        .collect::<Result<_, _>>()?;
+
+    Ok(Block {
+        env,
+        parent_hash: header.parent_hash,
+        parent_beacon_block_root: header.parent_beacon_block_root,
+        ommers: block
+            .uncle_headers
+            .iter()
+            .map(|ommer| Ommer {
+                number: ommer.number.saturating_to(),
+                beneficiary: ommer.coinbase,
+            })
+            .collect(),
+        withdrawals: block.withdrawals.as_ref().map(|withdrawals| {
+            withdrawals
+                .iter()
+                .map(|withdrawal| Withdrawal {
+                    index: withdrawal.index.saturating_to(),
+                    validator_index: withdrawal.validator_index.saturating_to(),
+                    address: withdrawal.address,
+                    amount: withdrawal.amount.saturating_to(),
+                })
+                .collect()
+        }),
+        transactions,
+    })
+}
+
+/// Returns why the transaction could not be converted.
+fn invalid_tx(tx: &TestTransaction, number: u64, index: usize) -> TestErrorKind {
+    if tx.sender.is_none() {
+        TestErrorKind::MissingSender { number, index }
+    } else {
+        TestErrorKind::UnknownTransactionType { number, index }
+    }
+}
+
+/// Builds the transaction environment, `None` if the type or the sender is missing.
+fn tx_from_test(tx: &TestTransaction) -> Option<TxEnv> {
+    // Legacy transactions carry their EIP-155 chain ID in `v`.
+    let chain_id = match tx.chain_id {
+        Some(chain_id) => Some(chain_id.saturating_to()),
+        None if tx.v >= U256::from(35) => Some(((tx.v - U256::from(35)) / U256::from(2)).to()),
+        None => None,
+    };
+    Some(TxEnv {
+        tx_type: tx.tx_type()?,
+        caller: tx.sender?,
+        gas_limit: tx.gas_limit.saturating_to(),
+        gas_price: tx.gas_price.or(tx.max_fee_per_gas).unwrap_or_default(),
+        transact_to: match tx.to {
+            Some(address) => TxKind::Call(address),
+            None => TxKind::Create,
+        },
+        value: tx.value,
+        data: tx.data.clone(),
+        nonce: tx.nonce.saturating_to(),
+        chain_id,
+        access_list: tx.access_list.clone().unwrap_or_default(),
+        gas_priority_fee: tx.max_priority_fee_per_gas,
+        blob_hashes: tx.blob_versioned_hashes.clone(),
+        max_fee_per_blob_gas: tx.max_fee_per_blob_gas,
+        authorization_list: tx
+            .authorization_list
+            .as_ref()
+            .map(|auth_list| {
+                AuthorizationList::Recovered(
+                    auth_list.iter().map(|auth| auth.into_recovered()).collect(),
+                )
+            })
+            .unwrap_or_default(),
+    })
+}
+
+/// Checks that the accounts of the state are the accounts of the post state.
+fn check_post_state(
+    state: &State<EmptyDB>,
+    post_state: &std::collections::HashMap<Address, statetest_types::AccountInfo>,
+) -> Result<(), TestErrorKind> {
+    let accounts: BTreeMap<_, _> = state.cache.trie_account().into_iter().collect();
+    let mismatch =
+        |address: Address, reason: String| TestErrorKind::PostStateMismatch { address, reason };
+
+    for (address, expected) in post_state {
+        let Some(account) = accounts.get(address) else {
+            return Err(mismatch(*address, "missing account".to_string()));
+        };
+        if account.info.balance != expected.balance {
+            return Err(mismatch(
+                *address,
+                format!(
+                    "balance: got {}, expected {}",
+                    account.info.balance, expected.balance
+                ),
+            ));
+        }
+        if account.info.nonce != expected.nonce {
+            return Err(mismatch(
+                *address,
+                format!(
+                    "nonce: got {}, expected {}",
+                    account.info.nonce, expected.nonce
+                ),
+            ));
+        }
+        if account.info.code_hash != keccak256(&expected.code) {
+            return Err(mismatch(*address, "code mismatch".to_string()));
+        }
+        let storage = account.storage.iter().filter(|(_, value)| !value.is_zero());
+        for (slot, value) in storage.clone() {
+            let expected_value = expected.storage.get(slot).copied().unwrap_or_default();
+            if *value != expected_value {
+                return Err(mismatch(
+                    *address,
+                    format!("storage {slot}: got {value}, expected {expected_value}"),
+                ));
+            }
+        }
+        let expected_slots = expected
+            .storage
+            .values()
+            .filter(|value| !value.is_zero())
+            .count();
+        if storage.count() != expected_slots {
+            return Err(mismatch(*address, "storage slot count".to_string()));
+        }
+    }
+
+    if let Some(address) = accounts.keys().find(|a| !post_state.contains_key(*a)) {
+        return Err(mismatch(*address, "unexpected account".to_string()));
+    }
+    Ok(())
+}
+
+pub fn run(test_files: Vec<PathBuf>, keep_going: bool) -> Result<(), TestError> {
+    let n_files = test_files.len();
+    let console_bar =
+        ProgressBar::with_draw_target(Some(n_files as u64), ProgressDrawTarget::stdout());
+    let mut elapsed = Duration::ZERO;
+    let mut skipped = Skipped::default();
+    let mut errors = Vec::new();
+
+    for test_path in test_files {
+        let result = execute_test_suite(&test_path, &mut elapsed, &mut skipped);
+        console_bar.inc(1);
+
+        if let Err(err) = result {
+            if !keep_going {
+                console_bar.finish();
+                return Err(err);
+            }
+            println!("{err}");
+            errors.push(err);
+        }
+    }
+    console_bar.finish();
+
+    println!(
+        "Finished execution. Total CPU time: {:.6}s",
+        elapsed.as_secs_f64()
+    );
+    if skipped.tests != 0 || skipped.invalid_blocks != 0 {
+        println!(
+            "Skipped {} tests of unsupported networks and {} invalid blocks without decoded content",
+            skipped.tests, skipped.invalid_blocks
+        );
+    }
+
+    let n_errors = errors.len();
+    if n_errors == 0 {
+        println!("All tests passed!");
+        Ok(())
+    } else {
+        println!("Encountered {n_errors} errors out of {n_files} total tests");
+        Err(errors.swap_remove(0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// See `tests/blockchain_suite/ref.txt` for how the fixture was generated.
+    fn fixture() -> BlockchainTestSuite {
+        serde_json::from_str(include_str!(
+            "../../../../../tests/blockchain_suite/valid_and_invalid_blocks.json"
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn valid_and_invalid_blocks() {
+        for (name, unit) in fixture().0 {
+            let mut skipped = Skipped::default();
+            execute_test_unit(&unit, &mut skipped).unwrap_or_else(|e| panic!("{name}: {e}"));
+            assert_eq!(skipped.invalid_blocks, 0);
+        }
+    }
+
+    #[test]
+    fn invalid_block_is_executed() {
+        for (name, mut unit) in fixture().0 {
+            // Accepting the block with the reused nonce fails.
+            let invalid = unit.blocks.pop().unwrap();
+            unit.blocks.push(*invalid.rlp_decoded.unwrap());
+            unit.blocks[1].rlp = invalid.rlp;
+            let result = execute_test_unit(&unit, &mut Skipped::default());
+            assert!(result.is_err(), "{name}");
+
+            // Rejecting the valid block fails.
+            let mut unit = fixture().0.remove(&name).unwrap();
+            unit.blocks[0].expect_exception = Some("invalid".into());
+            let result = execute_test_unit(&unit, &mut Skipped::default());
+            assert!(
+                matches!(
+                    result,
+                    Err(TestErrorKind::BlockNotRejected { number: 1, .. })
+                ),
+                "{name}"
+            );
+        }
+    }
+}
@@ -136,7 +136,9 @@ fn skip_test(path: &Path) -> bool {
         | "initcode_transaction_before_prague.json"
         | "invalid_tx_non_existing_sender.json"
         | "tx_non_existing_sender.json"
+        // Withdrawals are not part of the state test format.
         | "block_apply_withdrawal.json"
+        // Exported with a 5 ether block reward in London and ommers older than the block.
         | "block_apply_ommers_reward.json"
         | "known_block_hash.json"
         | "eip7516_blob_base_fee.json"
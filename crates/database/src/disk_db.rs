@@ -0,0 +1,740 @@
+use crate::states::{PlainStateReverts, PlainStorageChangeset, RevertToSlot, StateChangeset};
+use crate::{BundleState, OriginalValuesKnown};
+use core::fmt;
+use database_interface::{Database, DatabaseCommit, DatabaseRef};
+use primitives::{Address, HashMap, B256, KECCAK_EMPTY, U256};
+use redb::{ReadableTable, Table, TableDefinition, WriteTransaction};
+use state::{Account, AccountInfo, Bytecode};
+use std::{
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet},
+    path::Path,
+    vec::Vec,
+};
+
+/// Accounts by address, see [encode_account].
+const ACCOUNTS: TableDefinition<'_, [u8; 20], [u8; 72]> = TableDefinition::new("accounts");
+/// Storage slots by address and slot.
+const STORAGE: TableDefinition<'_, [u8; 52], [u8; 32]> = TableDefinition::new("storage");
+/// Original contract bytes by code hash.
+const BYTECODES: TableDefinition<'_, [u8; 32], &[u8]> = TableDefinition::new("bytecodes");
+/// Block hashes by block number.
+const BLOCK_HASHES: TableDefinition<'_, u64, [u8; 32]> = TableDefinition::new("block_hashes");
+/// Accounts before the block by block number and address, empty if the account didn't exist.
+const ACCOUNT_REVERTS: TableDefinition<'_, [u8; 28], &[u8]> =
+    TableDefinition::new("account_reverts");
+/// Storage slots before the block by block number, address and slot.
+const STORAGE_REVERTS: TableDefinition<'_, [u8; 60], [u8; 32]> =
+    TableDefinition::new("storage_reverts");
+
+/// A persistent [Database] implementation backed by an embedded [redb] database.
+///
+/// The plain state is kept in flat tables of accounts, storage slots, contracts and block
+/// hashes. Next to the state, the database can keep the reverts of each written block so the
+/// latest blocks can be reverted with [DiskDB::revert_block].
+///
+/// Every write is a single transaction, so the database is never left with a partially applied
+/// change.
+#[derive(Debug)]
+pub struct DiskDB {
+    db: redb::Database,
+}
+
+/// Error of the [DiskDB].
+#[derive(Debug)]
+pub enum DiskDBError {
+    /// Error of the underlying database.
+    Storage(Box<redb::Error>),
+    /// An entry of the database can't be decoded.
+    InvalidEntry(&'static str),
+    /// The storage of the account is wiped in a later block of the written reverts, but the
+    /// value of the slot before that block is neither in the revert nor on disk.
+    UnknownWipedSlot {
+        /// Number of the block wiping the storage.
+        block: u64,
+        /// Address of the account.
+        address: Address,
+        /// Slot changed by an earlier block.
+        slot: U256,
+    },
+}
+
+impl std::error::Error for DiskDBError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Storage(error) => Some(error.as_ref()),
+            Self::InvalidEntry(_) | Self::UnknownWipedSlot { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for DiskDBError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Storage(error) => write!(f, "database error: {error}"),
+            Self::InvalidEntry(table) => write!(f, "invalid entry in table {table}"),
+            Self::UnknownWipedSlot {
+                block,
+                address,
+                slot,
+            } => write!(
+                f,
+                "unknown value of slot {slot} of {address} wiped in block {block}"
+            ),
+        }
+    }
+}
+
+impl<E: Into<redb::Error>> From<E> for DiskDBError {
+    fn from(error: E) -> Self {
+        Self::Storage(Box::new(error.into()))
+    }
+}
+
+impl DiskDB {
+    /// Opens the database at the given path, creating it if it doesn't exist.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, DiskDBError> {
+        let db = redb::Database::create(path)?;
+        // Create the tables so that reads don't fail on a new database.
+        let tx = db.begin_write()?;
+        tx.open_table(ACCOUNTS)?;
+        tx.open_table(STORAGE)?;
+        tx.open_table(BYTECODES)?;
+        tx.open_table(BLOCK_HASHES)?;
+        tx.open_table(ACCOUNT_REVERTS)?;
+        tx.open_table(STORAGE_REVERTS)?;
+        tx.commit()?;
+        Ok(Self { db })
+    }
+
+    /// Inserts a block hash.
+    pub fn insert_block_hash(&self, number: u64, hash: B256) -> Result<(), DiskDBError> {
+        let tx = self.db.begin_write()?;
+        tx.open_table(BLOCK_HASHES)?.insert(number, hash.0)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Applies the changeset without keeping its reverts.
+    pub fn apply_changeset(&self, changeset: &StateChangeset) -> Result<(), DiskDBError> {
+        let tx = self.db.begin_write()?;
+        write_changeset(&tx, changeset)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Applies the changeset and keeps the reverts of its blocks, starting with `first_block`.
+    ///
+    /// Both are written in a single transaction. Fails with [DiskDBError::UnknownWipedSlot] if
+    /// a block wipes a storage slot changed by an earlier block without listing its value.
+    pub fn write_state(
+        &self,
+        changeset: &StateChangeset,
+        reverts: &PlainStateReverts,
+        first_block: u64,
+    ) -> Result<(), DiskDBError> {
+        let tx = self.db.begin_write()?;
+        // Reverts of wiped storage are read from the state before the changeset.
+        write_reverts(&tx, reverts, first_block)?;
+        write_changeset(&tx, changeset)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Applies the bundle and keeps its reverts, the first of which is for `first_block`.
+    pub fn write_bundle(&self, bundle: &BundleState, first_block: u64) -> Result<(), DiskDBError> {
+        let (changeset, reverts) = bundle.to_plain_state_and_reverts(OriginalValuesKnown::Yes);
+        self.write_state(&changeset, &reverts, first_block)
+    }
+
+    /// Reverts the state to before the given block, removing the reverts of the block.
+    ///
+    /// Blocks must be reverted starting with the latest one.
+    pub fn revert_block(&self, number: u64) -> Result<(), DiskDBError> {
+        let tx = self.db.begin_write()?;
+        {
+            let mut accounts = tx.open_table(ACCOUNTS)?;
+            let mut account_reverts = tx.open_table(ACCOUNT_REVERTS)?;
+            let start = account_revert_key(number, Address::ZERO);
+            let end = account_revert_key(number, Address::repeat_byte(0xff));
+            for entry in account_reverts.extract_from_if(start..=end, |_, _| true)? {
+                let (key, info) = entry?;
+                let address = Address::from_slice(&key.value()[8..]);
+                match info.value() {
+                    [] => accounts.remove(address.0 .0)?,
+                    info => accounts.insert(
+                        address.0 .0,
+                        <[u8; 72]>::try_from(info)
+                            .map_err(|_| DiskDBError::InvalidEntry("account_reverts"))?,
+                    )?,
+                };
+            }
+
+            let mut storage = tx.open_table(STORAGE)?;
+            let mut storage_reverts = tx.open_table(STORAGE_REVERTS)?;
+            let start = storage_revert_key(number, Address::ZERO, U256::ZERO);
+            let end = storage_revert_key(number, Address::repeat_byte(0xff), U256::MAX);
+            for entry in storage_reverts.extract_from_if(start..=end, |_, _| true)? {
+                let (key, value) = entry?;
+                let key: [u8; 52] = key.value()[8..].try_into().unwrap();
+                write_slot(&mut storage, key, value.value())?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+impl DatabaseRef for DiskDB {
+    type Error = DiskDBError;
+
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        let tx = self.db.begin_read()?;
+        let Some(account) = tx.open_table(ACCOUNTS)?.get(address.0 .0)? else {
+            return Ok(None);
+        };
+        let mut info = decode_account(&account.value());
+        if info.code_hash != KECCAK_EMPTY {
+            if let Some(code) = tx.open_table(BYTECODES)?.get(info.code_hash.0)? {
+                info.code = Some(decode_bytecode(code.value())?);
+            }
+        }
+        Ok(Some(info))
+    }
+
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        let tx = self.db.begin_read()?;
+        match tx.open_table(BYTECODES)?.get(code_hash.0)? {
+            Some(code) => decode_bytecode(code.value()),
+            None => Ok(Bytecode::default()),
+        }
+    }
+
+    fn storage_ref(&self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        let tx = self.db.begin_read()?;
+        let value = tx
+            .open_table(STORAGE)?
+            .get(storage_key(address, index))?
+            .map(|value| U256::from_be_bytes(value.value()))
+            .unwrap_or_default();
+        Ok(value)
+    }
+
+    fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
+        let tx = self.db.begin_read()?;
+        let hash = tx
+            .open_table(BLOCK_HASHES)?
+            .get(number)?
+            .map(|hash| B256::from(hash.value()))
+            .unwrap_or_default();
+        Ok(hash)
+    }
+}
+
+impl Database for DiskDB {
+    type Error = DiskDBError;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.basic_ref(address)
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.code_by_hash_ref(code_hash)
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        self.storage_ref(address, index)
+    }
+
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        self.block_hash_ref(number)
+    }
+}
+
+impl DatabaseCommit for DiskDB {
+    /// Applies the changes without keeping their reverts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the changes can't be written.
+    fn commit(&mut self, changes: HashMap<Address, Account>) {
+        let mut changeset = StateChangeset::default();
+        for (address, account) in changes {
+            if !account.is_touched() {
+                continue;
+            }
+            if account.is_selfdestructed() {
+                changeset.accounts.push((address, None));
+                changeset.storage.push(PlainStorageChangeset {
+                    address,
+                    wipe_storage: true,
+                    storage: Vec::new(),
+                });
+                continue;
+            }
+            if let Some(code) = account.info.code.as_ref().filter(|code| !code.is_empty()) {
+                changeset
+                    .contracts
+                    .push((account.info.code_hash, code.clone()));
+            }
+            changeset
+                .accounts
+                .push((address, Some(account.info.copy_without_code())));
+            changeset.storage.push(PlainStorageChangeset {
+                address,
+                wipe_storage: account.is_created(),
+                storage: account
+                    .storage
+                    .into_iter()
+                    .map(|(key, value)| (key, value.present_value()))
+                    .collect(),
+            });
+        }
+        self.apply_changeset(&changeset)
+            .expect("failed to commit changes to the disk database");
+    }
+}
+
+fn write_changeset(tx: &WriteTransaction, changeset: &StateChangeset) -> Result<(), DiskDBError> {
+    let mut accounts = tx.open_table(ACCOUNTS)?;
+    for (address, info) in &changeset.accounts {
+        match info {
+            Some(info) => accounts.insert(address.0 .0, encode_account(info))?,
+            None => accounts.remove(address.0 .0)?,
+        };
+    }
+
+    let mut storage = tx.open_table(STORAGE)?;
+    for changes in &changeset.storage {
+        if changes.wipe_storage {
+            let (start, end) = storage_range(changes.address);
+            storage.retain_in(start..=end, |_, _| false)?;
+        }
+        for (slot, value) in &changes.storage {
+            write_slot(
+                &mut storage,
+                storage_key(changes.address, *slot),
+                value.to_be_bytes(),
+            )?;
+        }
+    }
+
+    let mut bytecodes = tx.open_table(BYTECODES)?;
+    for (code_hash, code) in &changeset.contracts {
+        bytecodes.insert(code_hash.0, code.original_byte_slice())?;
+    }
+    Ok(())
+}
+
+fn write_reverts(
+    tx: &WriteTransaction,
+    reverts: &PlainStateReverts,
+    first_block: u64,
+) -> Result<(), DiskDBError> {
+    let mut account_reverts = tx.open_table(ACCOUNT_REVERTS)?;
+    for (number, accounts) in (first_block..).zip(&reverts.accounts) {
+        for (address, info) in accounts {
+            let info = info.as_ref().map(encode_account);
+            account_reverts.insert(
+                account_revert_key(number, *address),
+                info.as_ref().map_or(&[][..], |info| &info[..]),
+            )?;
+        }
+    }
+
+    let storage = tx.open_table(STORAGE)?;
+    let mut storage_reverts = tx.open_table(STORAGE_REVERTS)?;
+    // Storage wiped and slots changed by the blocks before the current one, which the state on
+    // disk doesn't reflect yet.
+    let mut wiped = BTreeSet::new();
+    let mut changed = BTreeSet::new();
+    for (number, reverts) in (first_block..).zip(&reverts.storage) {
+        for revert in reverts {
+            let mut slots: BTreeMap<U256, U256> = revert
+                .storage_revert
+                .iter()
+                .map(|(slot, value)| (*slot, RevertToSlot::to_previous_value(*value)))
+                .collect();
+            // The wiped storage is not part of the revert, so it is taken from the state. Once
+            // an earlier block wiped it, the storage before the block is all in the revert.
+            if revert.wiped && !wiped.contains(&revert.address) {
+                let (start, end) = storage_range(revert.address);
+                for entry in storage.range(start..=end)? {
+                    let (key, value) = entry?;
+                    let slot = U256::from_be_slice(&key.value()[20..]);
+                    if slots.contains_key(&slot) {
+                        continue;
+                    }
+                    if changed.contains(&(revert.address, slot)) {
+                        return Err(DiskDBError::UnknownWipedSlot {
+                            block: number,
+                            address: revert.address,
+                            slot,
+                        });
+                    }
+                    slots.insert(slot, U256::from_be_bytes(value.value()));
+                }
+            }
+            if revert.wiped {
+                wiped.insert(revert.address);
+            }
+            changed.extend(slots.keys().map(|slot| (revert.address, *slot)));
+            for (slot, value) in slots {
+                storage_reverts.insert(
+                    storage_revert_key(number, revert.address, slot),
+                    value.to_be_bytes(),
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Writes the slot value, removing the slot if the value is zero.
+fn write_slot(
+    storage: &mut Table<'_, [u8; 52], [u8; 32]>,
+    key: [u8; 52],
+    value: [u8; 32],
+) -> Result<(), DiskDBError> {
+    if value == [0; 32] {
+        storage.remove(key)?;
+    } else {
+        storage.insert(key, value)?;
+    }
+    Ok(())
+}
+
+/// Encodes the account as the nonce, balance and code hash, without the code.
+fn encode_account(info: &AccountInfo) -> [u8; 72] {
+    let mut encoded = [0; 72];
+    encoded[..8].copy_from_slice(&info.nonce.to_be_bytes());
+    encoded[8..40].copy_from_slice(&info.balance.to_be_bytes::<32>());
+    encoded[40..].copy_from_slice(info.code_hash.as_slice());
+    encoded
+}
+
+fn decode_account(encoded: &[u8; 72]) -> AccountInfo {
+    AccountInfo {
+        nonce: u64::from_be_bytes(encoded[..8].try_into().unwrap()),
+        balance: U256::from_be_slice(&encoded[8..40]),
+        code_hash: B256::from_slice(&encoded[40..]),
+        code: None,
+    }
+}
+
+fn decode_bytecode(encoded: &[u8]) -> Result<Bytecode, DiskDBError> {
+    Bytecode::new_raw_checked(encoded.to_vec().into())
+        .map_err(|_| DiskDBError::InvalidEntry("bytecodes"))
+}
+
+fn storage_key(address: Address, slot: U256) -> [u8; 52] {
+    let mut key = [0; 52];
+    key[..20].copy_from_slice(address.as_slice());
+    key[20..].copy_from_slice(&slot.to_be_bytes::<32>());
+    key
+}
+
+/// Returns the first and last storage keys of the account.
+fn storage_range(address: Address) -> ([u8; 52], [u8; 52]) {
+    (
+        storage_key(address, U256::ZERO),
+        storage_key(address, U256::MAX),
+    )
+}
+
+fn account_revert_key(number: u64, address: Address) -> [u8; 28] {
+    let mut key = [0; 28];
+    key[..8].copy_from_slice(&number.to_be_bytes());
+    key[8..].copy_from_slice(address.as_slice());
+    key
+}
+
+fn storage_revert_key(number: u64, address: Address, slot: U256) -> [u8; 60] {
+    let mut key = [0; 60];
+    key[..8].copy_from_slice(&number.to_be_bytes());
+    key[8..].copy_from_slice(&storage_key(address, slot));
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::states::PlainStorageRevert;
+    use primitives::address;
+    use state::{AccountStatus, EvmStorageSlot};
+
+    const EXISTING: Address = address!("0000000000000000000000000000000000000e01");
+    const CREATED: Address = address!("0000000000000000000000000000000000000e02");
+    const WIPED: Address = address!("0000000000000000000000000000000000000e03");
+
+    fn info(balance: u64) -> AccountInfo {
+        AccountInfo::from_balance(U256::from(balance))
+    }
+
+    fn storage(db: &DiskDB, address: Address) -> Vec<(U256, U256)> {
+        (0..10)
+            .map(U256::from)
+            .map(|slot| (slot, db.storage_ref(address, slot).unwrap()))
+            .filter(|(_, value)| !value.is_zero())
+            .collect()
+    }
+
+    fn genesis() -> StateChangeset {
+        StateChangeset {
+            accounts: vec![(EXISTING, Some(info(1))), (WIPED, Some(info(3)))],
+            storage: vec![
+                PlainStorageChangeset {
+                    address: EXISTING,
+                    wipe_storage: false,
+                    storage: vec![
+                        (U256::from(1), U256::from(1)),
+                        (U256::from(2), U256::from(2)),
+                    ],
+                },
+                PlainStorageChangeset {
+                    address: WIPED,
+                    wipe_storage: false,
+                    storage: vec![(U256::from(7), U256::from(7))],
+                },
+            ],
+            contracts: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn persist() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.redb");
+        let code = Bytecode::new_raw([0x60, 0x01, 0x00].into());
+        let code_info = AccountInfo {
+            code_hash: code.hash_slow(),
+            ..info(2)
+        };
+        {
+            let db = DiskDB::open(&path).unwrap();
+            let mut changeset = genesis();
+            changeset.accounts.push((CREATED, Some(code_info.clone())));
+            changeset
+                .contracts
+                .push((code_info.code_hash, code.clone()));
+            db.apply_changeset(&changeset).unwrap();
+            db.insert_block_hash(1, B256::repeat_byte(1)).unwrap();
+        }
+
+        let mut db = DiskDB::open(&path).unwrap();
+        assert_eq!(db.basic(EXISTING).unwrap(), Some(info(1)));
+        assert_eq!(
+            db.basic(CREATED).unwrap(),
+            Some(AccountInfo {
+                code: Some(code.clone()),
+                ..code_info.clone()
+            })
+        );
+        assert_eq!(db.basic(Address::ZERO).unwrap(), None);
+        assert_eq!(db.code_by_hash(code_info.code_hash).unwrap(), code);
+        assert_eq!(db.storage(EXISTING, U256::from(2)).unwrap(), U256::from(2));
+        assert_eq!(db.storage(EXISTING, U256::from(3)).unwrap(), U256::ZERO);
+        assert_eq!(db.block_hash(1).unwrap(), B256::repeat_byte(1));
+        assert_eq!(db.block_hash(2).unwrap(), B256::ZERO);
+    }
+
+    #[test]
+    fn revert_block() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = DiskDB::open(dir.path().join("state.redb")).unwrap();
+        db.apply_changeset(&genesis()).unwrap();
+
+        // Block 5 changes the existing account, creates an account and recreates the wiped one.
+        let changeset = StateChangeset {
+            accounts: vec![
+                (EXISTING, Some(info(10))),
+                (CREATED, Some(info(20))),
+                (WIPED, Some(info(30))),
+            ],
+            storage: vec![
+                PlainStorageChangeset {
+                    address: EXISTING,
+                    wipe_storage: false,
+                    storage: vec![(U256::from(1), U256::from(5)), (U256::from(2), U256::ZERO)],
+                },
+                PlainStorageChangeset {
+                    address: CREATED,
+                    wipe_storage: false,
+                    storage: vec![(U256::from(4), U256::from(4))],
+                },
+                PlainStorageChangeset {
+                    address: WIPED,
+                    wipe_storage: true,
+                    storage: vec![(U256::from(8), U256::from(8))],
+                },
+            ],
+            contracts: Vec::new(),
+        };
+        let reverts = PlainStateReverts {
+            accounts: vec![vec![
+                (EXISTING, Some(info(1))),
+                (CREATED, None),
+                (WIPED, Some(info(3))),
+            ]],
+            storage: vec![vec![
+                PlainStorageRevert {
+                    address: EXISTING,
+                    wiped: false,
+                    storage_revert: vec![
+                        (U256::from(1), RevertToSlot::Some(U256::from(1))),
+                        (U256::from(2), RevertToSlot::Some(U256::from(2))),
+                    ],
+                },
+                PlainStorageRevert {
+                    address: CREATED,
+                    wiped: false,
+                    storage_revert: vec![(U256::from(4), RevertToSlot::Destroyed)],
+                },
+                PlainStorageRevert {
+                    address: WIPED,
+                    wiped: true,
+                    storage_revert: vec![(U256::from(8), RevertToSlot::Destroyed)],
+                },
+            ]],
+        };
+        db.write_state(&changeset, &reverts, 5).unwrap();
+        assert_eq!(db.basic_ref(CREATED).unwrap(), Some(info(20)));
+        assert_eq!(storage(&db, EXISTING), vec![(U256::from(1), U256::from(5))]);
+        assert_eq!(storage(&db, WIPED), vec![(U256::from(8), U256::from(8))]);
+
+        // Reverts of other blocks change nothing.
+        db.revert_block(4).unwrap();
+        assert_eq!(db.basic_ref(EXISTING).unwrap(), Some(info(10)));
+
+        db.revert_block(5).unwrap();
+        assert_eq!(db.basic_ref(EXISTING).unwrap(), Some(info(1)));
+        assert_eq!(db.basic_ref(CREATED).unwrap(), None);
+        assert_eq!(db.basic_ref(WIPED).unwrap(), Some(info(3)));
+        assert_eq!(
+            storage(&db, EXISTING),
+            vec![
+                (U256::from(1), U256::from(1)),
+                (U256::from(2), U256::from(2))
+            ]
+        );
+        assert_eq!(storage(&db, CREATED), Vec::new());
+        assert_eq!(storage(&db, WIPED), vec![(U256::from(7), U256::from(7))]);
+    }
+
+    #[test]
+    fn revert_blocks_wiping_twice() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = DiskDB::open(dir.path().join("state.redb")).unwrap();
+        db.apply_changeset(&genesis()).unwrap();
+
+        // Block 5 wipes the storage and sets slot 8, block 6 wipes it again and sets slot 9.
+        let changeset = StateChangeset {
+            accounts: vec![(WIPED, Some(info(30)))],
+            storage: vec![PlainStorageChangeset {
+                address: WIPED,
+                wipe_storage: true,
+                storage: vec![(U256::from(9), U256::from(9))],
+            }],
+            contracts: Vec::new(),
+        };
+        let reverts = PlainStateReverts {
+            accounts: vec![vec![(WIPED, Some(info(3)))], vec![(WIPED, Some(info(30)))]],
+            storage: vec![
+                vec![PlainStorageRevert {
+                    address: WIPED,
+                    wiped: true,
+                    storage_revert: vec![(U256::from(8), RevertToSlot::Destroyed)],
+                }],
+                vec![PlainStorageRevert {
+                    address: WIPED,
+                    wiped: true,
+                    storage_revert: vec![
+                        (U256::from(8), RevertToSlot::Some(U256::from(8))),
+                        (U256::from(9), RevertToSlot::Destroyed),
+                    ],
+                }],
+            ],
+        };
+        db.write_state(&changeset, &reverts, 5).unwrap();
+        assert_eq!(storage(&db, WIPED), vec![(U256::from(9), U256::from(9))]);
+
+        // Slot 7 was wiped by block 5, so it is not restored with block 6.
+        db.revert_block(6).unwrap();
+        assert_eq!(storage(&db, WIPED), vec![(U256::from(8), U256::from(8))]);
+
+        db.revert_block(5).unwrap();
+        assert_eq!(storage(&db, WIPED), vec![(U256::from(7), U256::from(7))]);
+    }
+
+    #[test]
+    fn wipe_after_unknown_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = DiskDB::open(dir.path().join("state.redb")).unwrap();
+        db.apply_changeset(&genesis()).unwrap();
+
+        // Block 5 changes slot 7, block 6 wipes the storage without the value of slot 7.
+        let changeset = StateChangeset {
+            accounts: vec![(WIPED, None)],
+            storage: vec![PlainStorageChangeset {
+                address: WIPED,
+                wipe_storage: true,
+                storage: Vec::new(),
+            }],
+            contracts: Vec::new(),
+        };
+        let reverts = PlainStateReverts {
+            accounts: vec![Vec::new(), vec![(WIPED, Some(info(3)))]],
+            storage: vec![
+                vec![PlainStorageRevert {
+                    address: WIPED,
+                    wiped: false,
+                    storage_revert: vec![(U256::from(7), RevertToSlot::Some(U256::from(7)))],
+                }],
+                vec![PlainStorageRevert {
+                    address: WIPED,
+                    wiped: true,
+                    storage_revert: Vec::new(),
+                }],
+            ],
+        };
+        assert!(matches!(
+            db.write_state(&changeset, &reverts, 5),
+            Err(DiskDBError::UnknownWipedSlot { block: 6, address: WIPED, slot })
+                if slot == U256::from(7)
+        ));
+        // Nothing is written.
+        assert_eq!(db.basic_ref(WIPED).unwrap(), Some(info(3)));
+        assert_eq!(storage(&db, WIPED), vec![(U256::from(7), U256::from(7))]);
+    }
+
+    #[test]
+    fn commit() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut db = DiskDB::open(dir.path().join("state.redb")).unwrap();
+        db.apply_changeset(&genesis()).unwrap();
+
+        let mut changes = HashMap::default();
+        let mut account = Account::from(info(10));
+        account.status = AccountStatus::Touched;
+        account.storage.insert(
+            U256::from(1),
+            EvmStorageSlot::new_changed(U256::from(1), U256::from(3)),
+        );
+        changes.insert(EXISTING, account);
+        let mut account = Account::from(info(3));
+        account.status = AccountStatus::Touched | AccountStatus::SelfDestructed;
+        changes.insert(WIPED, account);
+        db.commit(changes);
+
+        assert_eq!(db.basic(EXISTING).unwrap(), Some(info(10)));
+        assert_eq!(
+            storage(&db, EXISTING),
+            vec![
+                (U256::from(1), U256::from(3)),
+                (U256::from(2), U256::from(2))
+            ]
+        );
+        assert_eq!(db.basic(WIPED).unwrap(), None);
+        assert_eq!(storage(&db, WIPED), Vec::new());
+    }
+}
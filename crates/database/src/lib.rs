@@ -2,16 +2,25 @@
 
 #[cfg(feature = "alloydb")]
 mod alloydb;
+#[cfg(feature = "diskdb")]
+mod disk_db;
 
 pub mod in_memory_db;
+pub mod recording_db;
 pub mod states;
+pub mod trie;
+pub mod witness_db;
 
 #[cfg(feature = "alloydb")]
 pub use alloydb::{AlloyDB, BlockId};
+#[cfg(feature = "diskdb")]
+pub use disk_db::{DiskDB, DiskDBError};
 
 pub use in_memory_db::*;
+pub use recording_db::{ExecutionWitness, RecordingDB, ReplayDB};
 pub use states::{
     AccountRevert, AccountStatus, BundleAccount, BundleState, CacheState, DBBox,
     OriginalValuesKnown, PlainAccount, RevertToSlot, State, StateBuilder, StateDBBox,
     StorageWithOriginalValues, TransitionAccount, TransitionState,
 };
+pub use witness_db::{WitnessDB, WitnessDBError};
@@ -0,0 +1,311 @@
+use crate::WitnessDBError;
+use database_interface::{Database, DatabaseRef};
+use primitives::{Address, B256, KECCAK_EMPTY, U256};
+use state::{AccountInfo, Bytecode};
+use std::collections::BTreeMap;
+
+/// State read by an execution, with the values that were read.
+///
+/// The maps are ordered so the serialized witness is the same for the same execution.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExecutionWitness {
+    /// Accounts, `None` if the account doesn't exist.
+    ///
+    /// `code` is always `None`, the bytecode can be found in `contracts`.
+    pub accounts: BTreeMap<Address, Option<AccountInfo>>,
+    /// Storage slots by account.
+    pub storage: BTreeMap<Address, BTreeMap<U256, U256>>,
+    /// Contracts by their code hash.
+    pub contracts: BTreeMap<B256, Bytecode>,
+    /// Block hashes by their number.
+    pub block_hashes: BTreeMap<u64, B256>,
+}
+
+impl ExecutionWitness {
+    /// Returns the number of accounts, storage slots, contracts and block hashes in the witness.
+    pub fn len(&self) -> usize {
+        self.accounts.len()
+            + self.storage.values().map(BTreeMap::len).sum::<usize>()
+            + self.contracts.len()
+            + self.block_hashes.len()
+    }
+
+    /// Returns `true` if nothing was recorded.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// A [Database] wrapper that records the state read from the wrapped database into an
+/// [ExecutionWitness].
+///
+/// Only the first read of each item is recorded, as the wrapped database is not expected to
+/// change during the execution.
+#[derive(Clone, Debug, Default)]
+pub struct RecordingDB<DB> {
+    /// The wrapped database.
+    pub db: DB,
+    /// The state read so far.
+    pub witness: ExecutionWitness,
+}
+
+impl<DB> RecordingDB<DB> {
+    /// Wraps the database with an empty witness.
+    pub fn new(db: DB) -> Self {
+        Self {
+            db,
+            witness: ExecutionWitness::default(),
+        }
+    }
+
+    /// Returns the state read so far.
+    pub fn witness(&self) -> &ExecutionWitness {
+        &self.witness
+    }
+
+    /// Takes the state read so far, leaving an empty witness.
+    pub fn take_witness(&mut self) -> ExecutionWitness {
+        core::mem::take(&mut self.witness)
+    }
+
+    /// Consumes the wrapper, returning the database and the recorded witness.
+    pub fn into_parts(self) -> (DB, ExecutionWitness) {
+        (self.db, self.witness)
+    }
+}
+
+impl<DB: Database> Database for RecordingDB<DB> {
+    type Error = DB::Error;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        let info = self.db.basic(address)?;
+        self.witness.accounts.entry(address).or_insert_with(|| {
+            info.as_ref().map(|info| {
+                if let Some(code) = info.code.as_ref().filter(|code| !code.is_empty()) {
+                    self.witness
+                        .contracts
+                        .entry(info.code_hash)
+                        .or_insert_with(|| code.clone());
+                }
+                info.copy_without_code()
+            })
+        });
+        Ok(info)
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        let code = self.db.code_by_hash(code_hash)?;
+        self.witness
+            .contracts
+            .entry(code_hash)
+            .or_insert_with(|| code.clone());
+        Ok(code)
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        let value = self.db.storage(address, index)?;
+        self.witness
+            .storage
+            .entry(address)
+            .or_default()
+            .entry(index)
+            .or_insert(value);
+        Ok(value)
+    }
+
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        let hash = self.db.block_hash(number)?;
+        self.witness.block_hashes.entry(number).or_insert(hash);
+        Ok(hash)
+    }
+}
+
+/// A [Database] that serves only the state recorded in an [ExecutionWitness].
+///
+/// Reading anything that is not in the witness is an error, so replaying an execution on top of
+/// this database checks that the witness is complete.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReplayDB {
+    /// The recorded state.
+    pub witness: ExecutionWitness,
+}
+
+impl ReplayDB {
+    /// Creates a database serving the witness.
+    pub fn new(witness: ExecutionWitness) -> Self {
+        Self { witness }
+    }
+}
+
+impl From<ExecutionWitness> for ReplayDB {
+    fn from(witness: ExecutionWitness) -> Self {
+        Self::new(witness)
+    }
+}
+
+impl DatabaseRef for ReplayDB {
+    type Error = WitnessDBError;
+
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        let info = self
+            .witness
+            .accounts
+            .get(&address)
+            .ok_or(WitnessDBError::MissingAccount(address))?;
+        Ok(info.clone().map(|mut info| {
+            info.code = self.witness.contracts.get(&info.code_hash).cloned();
+            info
+        }))
+    }
+
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        if code_hash == KECCAK_EMPTY {
+            return Ok(Bytecode::default());
+        }
+        self.witness
+            .contracts
+            .get(&code_hash)
+            .cloned()
+            .ok_or(WitnessDBError::MissingCode(code_hash))
+    }
+
+    fn storage_ref(&self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        self.witness
+            .storage
+            .get(&address)
+            .and_then(|storage| storage.get(&index))
+            .copied()
+            .ok_or(WitnessDBError::MissingStorage {
+                address,
+                slot: index,
+            })
+    }
+
+    fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
+        self.witness
+            .block_hashes
+            .get(&number)
+            .copied()
+            .ok_or(WitnessDBError::MissingBlockHash(number))
+    }
+}
+
+impl Database for ReplayDB {
+    type Error = WitnessDBError;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.basic_ref(address)
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.code_by_hash_ref(code_hash)
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        self.storage_ref(address, index)
+    }
+
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        self.block_hash_ref(number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InMemoryDB;
+    use primitives::address;
+
+    const ACCOUNT: Address = address!("0000000000000000000000000000000000000a11");
+    const MISSING: Address = address!("0000000000000000000000000000000000000e0e");
+
+    fn recorded() -> (RecordingDB<InMemoryDB>, Bytecode) {
+        let code = Bytecode::new_raw([0x60, 0x01, 0x00].into());
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(
+            ACCOUNT,
+            AccountInfo {
+                balance: U256::from(10),
+                code_hash: code.hash_slow(),
+                code: Some(code.clone()),
+                nonce: 1,
+            },
+        );
+        db.insert_account_storage(ACCOUNT, U256::from(1), U256::from(2))
+            .unwrap();
+        db.block_hashes.insert(U256::from(7), B256::repeat_byte(7));
+
+        let mut db = RecordingDB::new(db);
+        db.basic(ACCOUNT).unwrap();
+        db.basic(MISSING).unwrap();
+        db.storage(ACCOUNT, U256::from(1)).unwrap();
+        db.storage(ACCOUNT, U256::from(3)).unwrap();
+        db.storage(MISSING, U256::ZERO).unwrap();
+        db.block_hash(7).unwrap();
+        (db, code)
+    }
+
+    #[test]
+    fn record() {
+        let (db, code) = recorded();
+        let witness = db.witness();
+        assert_eq!(witness.len(), 7);
+        assert_eq!(witness.accounts[&MISSING], None);
+        let info = witness.accounts[&ACCOUNT].as_ref().unwrap();
+        assert_eq!(info.code, None);
+        assert_eq!(witness.contracts[&info.code_hash], code);
+        assert_eq!(
+            witness.storage[&ACCOUNT],
+            BTreeMap::from([(U256::from(1), U256::from(2)), (U256::from(3), U256::ZERO)])
+        );
+        assert_eq!(
+            witness.storage[&MISSING],
+            BTreeMap::from([(U256::ZERO, U256::ZERO)])
+        );
+        assert_eq!(witness.block_hashes[&7], B256::repeat_byte(7));
+    }
+
+    #[test]
+    fn replay() {
+        let (mut recording, code) = recorded();
+        let mut replay = ReplayDB::new(recording.take_witness());
+        assert!(recording.witness().is_empty());
+
+        assert_eq!(replay.basic(ACCOUNT).ok(), recording.basic(ACCOUNT).ok());
+        assert_eq!(replay.basic(MISSING), Ok(None));
+        assert_eq!(replay.code_by_hash(code.hash_slow()), Ok(code));
+        assert_eq!(replay.storage(ACCOUNT, U256::from(1)), Ok(U256::from(2)));
+        assert_eq!(replay.storage(ACCOUNT, U256::from(3)), Ok(U256::ZERO));
+        assert_eq!(replay.block_hash(7), Ok(B256::repeat_byte(7)));
+
+        assert_eq!(
+            replay.basic(Address::ZERO),
+            Err(WitnessDBError::MissingAccount(Address::ZERO))
+        );
+        assert_eq!(
+            replay.storage(ACCOUNT, U256::from(2)),
+            Err(WitnessDBError::MissingStorage {
+                address: ACCOUNT,
+                slot: U256::from(2)
+            })
+        );
+        assert_eq!(
+            replay.code_by_hash(B256::ZERO),
+            Err(WitnessDBError::MissingCode(B256::ZERO))
+        );
+        assert_eq!(
+            replay.block_hash(8),
+            Err(WitnessDBError::MissingBlockHash(8))
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_roundtrip() {
+        let (db, _) = recorded();
+        let json = serde_json::to_string(db.witness()).unwrap();
+        let witness: ExecutionWitness = serde_json::from_str(&json).unwrap();
+        assert_eq!(&witness, db.witness());
+    }
+}
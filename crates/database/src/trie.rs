@@ -0,0 +1,265 @@
+//! Merkle Patricia trie root computation.
+//!
+//! The root functions compute the roots in one pass over the sorted entries, without storing the
+//! nodes. [MerkleTrie] and [StateRoot] keep the nodes in a [TrieStore] to update the roots
+//! incrementally and to generate the proofs of the values.
+pub mod merkle_trie;
+mod node;
+pub mod proof;
+pub mod state_root;
+pub mod store;
+
+pub use merkle_trie::{MerkleTrie, TrieError};
+pub use proof::{
+    proof, verify_account_proof, verify_proof, verify_storage_proof, AccountProof, ProofError,
+    StorageProof,
+};
+pub use state_root::{StateRoot, TrieAccount};
+pub use store::{InMemoryTrieStore, TrieStore};
+
+use alloy_rlp::{Encodable, Header, EMPTY_STRING_CODE};
+use primitives::{b256, keccak256, B256};
+use std::vec::Vec;
+
+/// Root of an empty trie, `keccak256(rlp(""))`.
+pub const EMPTY_ROOT_HASH: B256 =
+    b256!("56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421");
+
+/// Computes the root of a trie keyed by the RLP encoded index of each value.
+///
+/// This is how the transactions, receipts and withdrawals roots of a block header are built.
+pub fn ordered_trie_root<I, V>(values: I) -> B256
+where
+    I: IntoIterator<Item = V>,
+    V: AsRef<[u8]>,
+{
+    trie_root(
+        values
+            .into_iter()
+            .enumerate()
+            .map(|(index, value)| (alloy_rlp::encode(index), value)),
+    )
+}
+
+/// Computes the root of a trie keyed by the keccak256 hash of each key.
+///
+/// This is how the state and storage roots are built.
+pub fn sec_trie_root<I, K, V>(entries: I) -> B256
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<[u8]>,
+    V: AsRef<[u8]>,
+{
+    trie_root(
+        entries
+            .into_iter()
+            .map(|(key, value)| (keccak256(key), value)),
+    )
+}
+
+/// Computes the root of a trie from its entries.
+///
+/// Keys must be unique, if a key is repeated only one of the values is kept.
+pub fn trie_root<I, K, V>(entries: I) -> B256
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<[u8]>,
+    V: AsRef<[u8]>,
+{
+    let entries: Vec<(K, V)> = entries.into_iter().collect();
+    let mut nibbles: Vec<(Vec<u8>, &[u8])> = entries
+        .iter()
+        .map(|(key, value)| (unpack_nibbles(key.as_ref()), value.as_ref()))
+        .collect();
+    nibbles.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+    nibbles.dedup_by(|(a, _), (b, _)| a == b);
+
+    if nibbles.is_empty() {
+        return EMPTY_ROOT_HASH;
+    }
+    keccak256(encode_node(&nibbles, 0))
+}
+
+/// Returns the nibbles of the key, high nibble first.
+fn unpack_nibbles(key: &[u8]) -> Vec<u8> {
+    key.iter()
+        .flat_map(|byte| [byte >> 4, byte & 0x0f])
+        .collect()
+}
+
+/// Encodes the node holding the entries, which share the first `depth` nibbles of their keys.
+fn encode_node(entries: &[(Vec<u8>, &[u8])], depth: usize) -> Vec<u8> {
+    if let [(key, value)] = entries {
+        return encode_leaf(&key[depth..], value);
+    }
+
+    // Entries are sorted, so the first and the last keys have the shortest common prefix.
+    let first = &entries[0].0[depth..];
+    let last = &entries[entries.len() - 1].0[depth..];
+    let shared = first.iter().zip(last).take_while(|(a, b)| a == b).count();
+    if shared > 0 {
+        let child = encode_node(entries, depth + shared);
+        return encode_extension(&first[..shared], node_reference(&child));
+    }
+
+    // The key that ends here is the first one, as it sorts before the keys it prefixes.
+    let (value, mut rest) = match entries.split_first() {
+        Some(((key, value), rest)) if key.len() == depth => (Some(*value), rest),
+        _ => (None, entries),
+    };
+    let mut children: [Vec<u8>; 16] = Default::default();
+    for (nibble, child) in children.iter_mut().enumerate() {
+        let end = rest
+            .iter()
+            .position(|(key, _)| key[depth] as usize != nibble)
+            .unwrap_or(rest.len());
+        let (group, tail) = rest.split_at(end);
+        *child = if group.is_empty() {
+            Vec::from([EMPTY_STRING_CODE])
+        } else {
+            node_reference(&encode_node(group, depth + 1))
+        };
+        rest = tail;
+    }
+    encode_branch(children, value)
+}
+
+/// Encodes a leaf node.
+fn encode_leaf(path: &[u8], value: &[u8]) -> Vec<u8> {
+    let path = compact_path(path, true);
+    encode_list(&[encode_bytes(&path), encode_bytes(value)])
+}
+
+/// Encodes an extension node from the reference to its child.
+fn encode_extension(path: &[u8], child: Vec<u8>) -> Vec<u8> {
+    let path = compact_path(path, false);
+    encode_list(&[encode_bytes(&path), child])
+}
+
+/// Encodes a branch node from the references to its children.
+fn encode_branch(children: [Vec<u8>; 16], value: Option<&[u8]>) -> Vec<u8> {
+    let mut items = Vec::from(children);
+    items.push(encode_bytes(value.unwrap_or_default()));
+    encode_list(&items)
+}
+
+/// Returns how a node is referenced by its parent: inlined when its encoding is shorter than
+/// a hash, by hash otherwise.
+fn node_reference(node: &[u8]) -> Vec<u8> {
+    if node.len() < 32 {
+        node.to_vec()
+    } else {
+        encode_bytes(keccak256(node).as_slice())
+    }
+}
+
+/// Hex prefix encoding of a path, with the leaf flag.
+fn compact_path(path: &[u8], is_leaf: bool) -> Vec<u8> {
+    let flag = if is_leaf { 0x20 } else { 0x00 };
+    let mut compact = Vec::with_capacity(path.len() / 2 + 1);
+    let pairs = if path.len() % 2 == 1 {
+        compact.push(flag | 0x10 | path[0]);
+        &path[1..]
+    } else {
+        compact.push(flag);
+        path
+    };
+    compact.extend(pairs.chunks(2).map(|pair| (pair[0] << 4) | pair[1]));
+    compact
+}
+
+fn encode_bytes(bytes: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(bytes.length());
+    bytes.encode(&mut out);
+    out
+}
+
+fn encode_list(items: &[Vec<u8>]) -> Vec<u8> {
+    let payload_length = items.iter().map(Vec::len).sum();
+    let mut out = Vec::with_capacity(payload_length + 3);
+    Header {
+        list: true,
+        payload_length,
+    }
+    .encode(&mut out);
+    for item in items {
+        out.extend_from_slice(item);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hash_db::Hasher;
+    use plain_hasher::PlainHasher;
+
+    struct KeccakHasher;
+
+    impl Hasher for KeccakHasher {
+        type Out = B256;
+        type StdHasher = PlainHasher;
+        const LENGTH: usize = 32;
+
+        fn hash(x: &[u8]) -> Self::Out {
+            keccak256(x)
+        }
+    }
+
+    fn values(count: usize) -> Vec<Vec<u8>> {
+        // Mix of values inlined in their parent and values referenced by hash.
+        (0..count)
+            .map(|i| (0..(i * 7) % 50).map(|j| (i + j) as u8).collect())
+            .collect()
+    }
+
+    #[test]
+    fn empty_root() {
+        assert_eq!(trie_root::<_, &[u8], &[u8]>([]), EMPTY_ROOT_HASH);
+        assert_eq!(ordered_trie_root::<_, &[u8]>([]), EMPTY_ROOT_HASH);
+    }
+
+    #[test]
+    fn ordered_root_matches_reference() {
+        for count in [1, 2, 3, 15, 16, 17, 127, 128, 129, 300] {
+            let values = values(count);
+            assert_eq!(
+                ordered_trie_root(&values),
+                triehash::ordered_trie_root::<KeccakHasher, _>(&values),
+                "{count} values"
+            );
+        }
+    }
+
+    #[test]
+    fn sec_root_matches_reference() {
+        for count in [1, 2, 17, 200] {
+            let entries: Vec<_> = values(count)
+                .into_iter()
+                .enumerate()
+                .map(|(i, value)| ((i as u64 * 31).to_be_bytes(), [value, vec![1]].concat()))
+                .collect();
+            assert_eq!(
+                sec_trie_root(entries.clone()),
+                triehash::sec_trie_root::<KeccakHasher, _, _, _>(entries),
+                "{count} entries"
+            );
+        }
+    }
+
+    #[test]
+    fn prefixed_keys_match_reference() {
+        // Keys that are prefixes of other keys end in a branch value.
+        let entries = [
+            (vec![0x12], vec![1]),
+            (vec![0x12, 0x34], vec![2; 40]),
+            (vec![0x12, 0x35], vec![3]),
+            (vec![0x13], vec![4; 33]),
+            (vec![0x13, 0x00, 0x01], vec![5]),
+        ];
+        assert_eq!(
+            trie_root(entries.clone()),
+            triehash::trie_root::<KeccakHasher, _, _, _>(entries)
+        );
+    }
+}
@@ -0,0 +1,501 @@
+//! Merkle Patricia trie updated in place.
+use super::{
+    node::{Child, Node},
+    unpack_nibbles, TrieStore, EMPTY_ROOT_HASH,
+};
+use core::fmt;
+use primitives::{keccak256, B256};
+use std::{boxed::Box, vec::Vec};
+
+/// Errors of the trie operations.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TrieError<StoreError> {
+    /// Node referenced by the trie is not in the store.
+    MissingNode(B256),
+    /// Node in the store can't be decoded.
+    Decode(alloy_rlp::Error),
+    /// Error of the store.
+    Store(StoreError),
+}
+
+impl<StoreError: std::error::Error + 'static> std::error::Error for TrieError<StoreError> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Store(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl<StoreError: fmt::Display> fmt::Display for TrieError<StoreError> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingNode(hash) => write!(f, "missing trie node {hash}"),
+            Self::Decode(error) => write!(f, "invalid trie node: {error}"),
+            Self::Store(error) => write!(f, "trie store error: {error}"),
+        }
+    }
+}
+
+/// Merkle Patricia trie whose nodes are loaded from a [TrieStore] when they are needed.
+///
+/// The changed nodes are kept in memory until [MerkleTrie::commit] writes them to the store
+/// and returns the new root, the untouched nodes are never loaded nor rehashed.
+///
+/// If an operation fails the trie should be discarded, the store still holds the trie of the
+/// last committed root.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MerkleTrie {
+    root: Child,
+}
+
+impl MerkleTrie {
+    /// Creates an empty trie.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Opens the trie with the given root, its nodes are read from the store.
+    pub fn from_root(root: B256) -> Self {
+        Self {
+            root: if root == EMPTY_ROOT_HASH {
+                Child::Empty
+            } else {
+                Child::Hash(root)
+            },
+        }
+    }
+
+    /// Returns the value of the key.
+    pub fn get<S: TrieStore>(
+        &self,
+        store: &S,
+        key: &[u8],
+    ) -> Result<Option<Vec<u8>>, TrieError<S::Error>> {
+        get(store, &self.root, &unpack_nibbles(key))
+    }
+
+    /// Inserts the value of the key, an empty value removes the key.
+    pub fn insert<S: TrieStore>(
+        &mut self,
+        store: &S,
+        key: &[u8],
+        value: Vec<u8>,
+    ) -> Result<(), TrieError<S::Error>> {
+        if value.is_empty() {
+            return self.remove(store, key);
+        }
+        let root = core::mem::take(&mut self.root);
+        self.root = insert(store, root, &unpack_nibbles(key), value)?;
+        Ok(())
+    }
+
+    /// Removes the key.
+    pub fn remove<S: TrieStore>(
+        &mut self,
+        store: &S,
+        key: &[u8],
+    ) -> Result<(), TrieError<S::Error>> {
+        let root = core::mem::take(&mut self.root);
+        self.root = remove(store, root, &unpack_nibbles(key))?;
+        Ok(())
+    }
+
+    /// Writes the changed nodes to the store and returns the root.
+    pub fn commit<S: TrieStore>(&mut self, store: &mut S) -> Result<B256, TrieError<S::Error>> {
+        let root = match &mut self.root {
+            Child::Empty => return Ok(EMPTY_ROOT_HASH),
+            Child::Hash(hash) => return Ok(*hash),
+            Child::Node(node) => node,
+        };
+        let mut nodes = Vec::new();
+        // The root is always referenced by hash, even when its encoding is shorter.
+        let encoded = root.commit(&mut nodes);
+        let hash = keccak256(&encoded);
+        nodes.push((hash, encoded));
+        for (hash, node) in nodes {
+            store.insert_node(hash, node).map_err(TrieError::Store)?;
+        }
+        self.root = Child::Hash(hash);
+        Ok(hash)
+    }
+}
+
+/// Loads the node with the given hash from the store.
+fn load<S: TrieStore>(store: &S, hash: B256) -> Result<Node, TrieError<S::Error>> {
+    let encoded = store
+        .node(&hash)
+        .map_err(TrieError::Store)?
+        .ok_or(TrieError::MissingNode(hash))?;
+    Node::decode(&encoded).map_err(TrieError::Decode)
+}
+
+/// Returns the node of the child, `None` if there is no child.
+fn resolve<S: TrieStore>(store: &S, child: Child) -> Result<Option<Node>, TrieError<S::Error>> {
+    match child {
+        Child::Empty => Ok(None),
+        Child::Hash(hash) => load(store, hash).map(Some),
+        Child::Node(node) => Ok(Some(*node)),
+    }
+}
+
+fn get<S: TrieStore>(
+    store: &S,
+    child: &Child,
+    path: &[u8],
+) -> Result<Option<Vec<u8>>, TrieError<S::Error>> {
+    match child {
+        Child::Empty => Ok(None),
+        Child::Hash(hash) => get_in_node(store, &load(store, *hash)?, path),
+        Child::Node(node) => get_in_node(store, node, path),
+    }
+}
+
+fn get_in_node<S: TrieStore>(
+    store: &S,
+    node: &Node,
+    path: &[u8],
+) -> Result<Option<Vec<u8>>, TrieError<S::Error>> {
+    match node {
+        Node::Leaf { path: leaf, value } => Ok((leaf.as_slice() == path).then(|| value.clone())),
+        Node::Extension {
+            path: extension,
+            child,
+        } => match path.strip_prefix(extension.as_slice()) {
+            Some(rest) => get(store, child, rest),
+            None => Ok(None),
+        },
+        Node::Branch { children, value } => match path.split_first() {
+            Some((&nibble, rest)) => get(store, &children[nibble as usize], rest),
+            None => Ok(value.clone()),
+        },
+    }
+}
+
+fn insert<S: TrieStore>(
+    store: &S,
+    child: Child,
+    path: &[u8],
+    value: Vec<u8>,
+) -> Result<Child, TrieError<S::Error>> {
+    let node = match resolve(store, child)? {
+        None => Node::Leaf {
+            path: path.to_vec(),
+            value,
+        },
+        Some(Node::Leaf {
+            path: leaf,
+            value: leaf_value,
+        }) => {
+            if leaf == path {
+                Node::Leaf { path: leaf, value }
+            } else {
+                let shared = shared_prefix(&leaf, path);
+                let mut branch = Branch::default();
+                branch.insert_leaf(&leaf[shared..], leaf_value);
+                branch.insert_leaf(&path[shared..], value);
+                extension(&path[..shared], branch.into_node())
+            }
+        }
+        Some(Node::Extension {
+            path: extension_path,
+            child,
+        }) => {
+            let shared = shared_prefix(&extension_path, path);
+            if shared == extension_path.len() {
+                Node::Extension {
+                    child: insert(store, child, &path[shared..], value)?,
+                    path: extension_path,
+                }
+            } else {
+                // The extension is split by a branch, the nibble taken by the branch is
+                // removed from the remaining extension.
+                let mut branch = Branch::default();
+                let rest = &extension_path[shared + 1..];
+                branch.children[extension_path[shared] as usize] = if rest.is_empty() {
+                    child
+                } else {
+                    Child::Node(Box::new(Node::Extension {
+                        path: rest.to_vec(),
+                        child,
+                    }))
+                };
+                branch.insert_leaf(&path[shared..], value);
+                extension(&path[..shared], branch.into_node())
+            }
+        }
+        Some(Node::Branch {
+            mut children,
+            value: branch_value,
+        }) => match path.split_first() {
+            Some((&nibble, rest)) => {
+                let child = core::mem::take(&mut children[nibble as usize]);
+                children[nibble as usize] = insert(store, child, rest, value)?;
+                Node::Branch {
+                    children,
+                    value: branch_value,
+                }
+            }
+            None => Node::Branch {
+                children,
+                value: Some(value),
+            },
+        },
+    };
+    Ok(Child::Node(Box::new(node)))
+}
+
+fn remove<S: TrieStore>(
+    store: &S,
+    child: Child,
+    path: &[u8],
+) -> Result<Child, TrieError<S::Error>> {
+    let node = match resolve(store, child)? {
+        None => return Ok(Child::Empty),
+        Some(Node::Leaf { path: leaf, .. }) if leaf == path => return Ok(Child::Empty),
+        Some(Node::Extension {
+            path: extension_path,
+            child,
+        }) if path.starts_with(&extension_path) => {
+            let child = remove(store, child, &path[extension_path.len()..])?;
+            return normalize_extension(store, extension_path, child);
+        }
+        Some(Node::Branch {
+            mut children,
+            mut value,
+        }) => {
+            match path.split_first() {
+                Some((&nibble, rest)) => {
+                    let child = core::mem::take(&mut children[nibble as usize]);
+                    children[nibble as usize] = remove(store, child, rest)?;
+                }
+                None => value = None,
+            }
+            return normalize_branch(store, children, value);
+        }
+        // The key is not in the trie.
+        Some(node) => node,
+    };
+    Ok(Child::Node(Box::new(node)))
+}
+
+/// Merges the extension with its child after a removal.
+fn normalize_extension<S: TrieStore>(
+    store: &S,
+    mut path: Vec<u8>,
+    child: Child,
+) -> Result<Child, TrieError<S::Error>> {
+    let node = match resolve(store, child)? {
+        None => return Ok(Child::Empty),
+        Some(Node::Leaf { path: rest, value }) => {
+            path.extend(rest);
+            Node::Leaf { path, value }
+        }
+        Some(Node::Extension { path: rest, child }) => {
+            path.extend(rest);
+            Node::Extension { path, child }
+        }
+        Some(branch) => Node::Extension {
+            path,
+            child: Child::Node(Box::new(branch)),
+        },
+    };
+    Ok(Child::Node(Box::new(node)))
+}
+
+/// Collapses the branch if it is left with a single child or only a value after a removal.
+fn normalize_branch<S: TrieStore>(
+    store: &S,
+    mut children: Box<[Child; 16]>,
+    value: Option<Vec<u8>>,
+) -> Result<Child, TrieError<S::Error>> {
+    let mut remaining = children
+        .iter()
+        .enumerate()
+        .filter(|(_, child)| **child != Child::Empty)
+        .map(|(nibble, _)| nibble);
+    let node = match (remaining.next(), remaining.next(), value) {
+        (None, _, None) => return Ok(Child::Empty),
+        (None, _, Some(value)) => Node::Leaf {
+            path: Vec::new(),
+            value,
+        },
+        (Some(nibble), None, None) => {
+            let child = core::mem::take(&mut children[nibble]);
+            return normalize_extension(store, Vec::from([nibble as u8]), child);
+        }
+        (_, _, value) => Node::Branch { children, value },
+    };
+    Ok(Child::Node(Box::new(node)))
+}
+
+/// Wraps the node in an extension if the path is not empty.
+fn extension(path: &[u8], node: Node) -> Node {
+    if path.is_empty() {
+        node
+    } else {
+        Node::Extension {
+            path: path.to_vec(),
+            child: Child::Node(Box::new(node)),
+        }
+    }
+}
+
+fn shared_prefix(a: &[u8], b: &[u8]) -> usize {
+    a.iter().zip(b).take_while(|(a, b)| a == b).count()
+}
+
+/// Branch being built from the two sides of a split.
+#[derive(Default)]
+struct Branch {
+    children: Box<[Child; 16]>,
+    value: Option<Vec<u8>>,
+}
+
+impl Branch {
+    /// Inserts a leaf at the path, relative to the branch.
+    fn insert_leaf(&mut self, path: &[u8], value: Vec<u8>) {
+        match path.split_first() {
+            Some((&nibble, rest)) => {
+                self.children[nibble as usize] = Child::Node(Box::new(Node::Leaf {
+                    path: rest.to_vec(),
+                    value,
+                }))
+            }
+            None => self.value = Some(value),
+        }
+    }
+
+    fn into_node(self) -> Node {
+        Node::Branch {
+            children: self.children,
+            value: self.value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trie::{trie_root, InMemoryTrieStore};
+
+    fn entries(count: usize) -> Vec<(Vec<u8>, Vec<u8>)> {
+        (0..count)
+            .map(|i| {
+                let key = keccak256((i as u64).to_be_bytes()).to_vec();
+                // Mix of values inlined in their parent and values referenced by hash.
+                (key, vec![(i % 200) as u8 + 1; 1 + i % 40])
+            })
+            .collect()
+    }
+
+    #[test]
+    fn insert_matches_root() {
+        let mut store = InMemoryTrieStore::default();
+        let mut trie = MerkleTrie::new();
+        assert_eq!(trie.commit(&mut store), Ok(EMPTY_ROOT_HASH));
+
+        let entries = entries(300);
+        for (count, (key, value)) in entries.iter().enumerate() {
+            trie.insert(&store, key, value.clone()).unwrap();
+            // Commit in between to update tries that are partly in the store.
+            if count % 50 == 0 {
+                let root = trie.commit(&mut store).unwrap();
+                assert_eq!(root, trie_root(entries[..=count].iter().cloned()));
+            }
+        }
+        assert_eq!(trie.commit(&mut store), Ok(trie_root(entries)));
+    }
+
+    #[test]
+    fn remove_matches_root() {
+        let mut store = InMemoryTrieStore::default();
+        let mut trie = MerkleTrie::new();
+        let mut entries = entries(200);
+        for (key, value) in &entries {
+            trie.insert(&store, key, value.clone()).unwrap();
+        }
+        let root = trie.commit(&mut store).unwrap();
+
+        let mut trie = MerkleTrie::from_root(root);
+        while !entries.is_empty() {
+            for (key, _) in entries.drain(..entries.len().min(30)) {
+                trie.remove(&store, &key).unwrap();
+            }
+            assert_eq!(
+                trie.commit(&mut store),
+                Ok(trie_root(entries.iter().cloned()))
+            );
+        }
+        assert_eq!(trie.commit(&mut store), Ok(EMPTY_ROOT_HASH));
+    }
+
+    #[test]
+    fn get_and_update() {
+        let mut store = InMemoryTrieStore::default();
+        let mut trie = MerkleTrie::new();
+        let entries = entries(50);
+        for (key, value) in &entries {
+            trie.insert(&store, key, value.clone()).unwrap();
+        }
+        let old_root = trie.commit(&mut store).unwrap();
+
+        let mut trie = MerkleTrie::from_root(old_root);
+        trie.insert(&store, &entries[3].0, vec![9; 50]).unwrap();
+        trie.insert(&store, &entries[4].0, Vec::new()).unwrap();
+        assert_eq!(trie.get(&store, &entries[3].0), Ok(Some(vec![9; 50])));
+        assert_eq!(trie.get(&store, &entries[4].0), Ok(None));
+        assert_eq!(
+            trie.get(&store, &entries[5].0),
+            Ok(Some(entries[5].1.clone()))
+        );
+        trie.commit(&mut store).unwrap();
+
+        // The trie of the old root is still in the store.
+        let old = MerkleTrie::from_root(old_root);
+        assert_eq!(
+            old.get(&store, &entries[3].0),
+            Ok(Some(entries[3].1.clone()))
+        );
+        assert_eq!(
+            old.get(&store, &entries[4].0),
+            Ok(Some(entries[4].1.clone()))
+        );
+    }
+
+    #[test]
+    fn prefixed_keys() {
+        // Keys that are prefixes of other keys end in a branch value.
+        let entries = [
+            (vec![0x12], vec![1]),
+            (vec![0x12, 0x34], vec![2; 40]),
+            (vec![0x12, 0x35], vec![3]),
+            (vec![0x13], vec![4; 33]),
+            (vec![0x13, 0x00, 0x01], vec![5]),
+        ];
+        let mut store = InMemoryTrieStore::default();
+        let mut trie = MerkleTrie::new();
+        for (key, value) in entries.iter().rev() {
+            trie.insert(&store, key, value.clone()).unwrap();
+        }
+        assert_eq!(trie.commit(&mut store), Ok(trie_root(entries.clone())));
+
+        for (index, (key, _)) in entries.iter().enumerate() {
+            trie.remove(&store, key).unwrap();
+            assert_eq!(
+                trie.commit(&mut store),
+                Ok(trie_root(entries[index + 1..].iter().cloned()))
+            );
+        }
+    }
+
+    #[test]
+    fn missing_node() {
+        let store = InMemoryTrieStore::default();
+        let root = B256::repeat_byte(1);
+        assert_eq!(
+            MerkleTrie::from_root(root).get(&store, &[1]),
+            Err(TrieError::MissingNode(root))
+        );
+    }
+}
@@ -0,0 +1,214 @@
+//! Nodes of a Merkle Patricia trie, as kept in memory between commits.
+use super::{encode_branch, encode_bytes, encode_extension, encode_leaf};
+use alloy_rlp::{Error, Header, EMPTY_STRING_CODE};
+use primitives::{keccak256, B256};
+use std::{boxed::Box, vec::Vec};
+
+/// Reference from a node to its child.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(super) enum Child {
+    /// No child.
+    #[default]
+    Empty,
+    /// Child that is only in the store.
+    Hash(B256),
+    /// Child that is in memory, either changed or inlined in its parent.
+    Node(Box<Node>),
+}
+
+/// Trie node, with the paths in nibbles.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) enum Node {
+    Leaf {
+        path: Vec<u8>,
+        value: Vec<u8>,
+    },
+    Extension {
+        path: Vec<u8>,
+        child: Child,
+    },
+    Branch {
+        children: Box<[Child; 16]>,
+        value: Option<Vec<u8>>,
+    },
+}
+
+impl Child {
+    /// Returns the reference of the child in its parent node.
+    ///
+    /// Children whose encoding is at least as long as a hash are pushed to `nodes` and replaced
+    /// by their hash.
+    pub(super) fn commit(&mut self, nodes: &mut Vec<(B256, Vec<u8>)>) -> Vec<u8> {
+        match self {
+            Self::Empty => Vec::from([EMPTY_STRING_CODE]),
+            Self::Hash(hash) => encode_bytes(hash.as_slice()),
+            Self::Node(node) => {
+                let encoded = node.commit(nodes);
+                if encoded.len() < 32 {
+                    return encoded;
+                }
+                let hash = keccak256(&encoded);
+                nodes.push((hash, encoded));
+                *self = Self::Hash(hash);
+                encode_bytes(hash.as_slice())
+            }
+        }
+    }
+}
+
+impl Node {
+    /// Encodes the node, committing its children.
+    pub(super) fn commit(&mut self, nodes: &mut Vec<(B256, Vec<u8>)>) -> Vec<u8> {
+        match self {
+            Self::Leaf { path, value } => encode_leaf(path, value),
+            Self::Extension { path, child } => encode_extension(path, child.commit(nodes)),
+            Self::Branch { children, value } => {
+                let children = children.each_mut().map(|child| child.commit(nodes));
+                encode_branch(children, value.as_deref())
+            }
+        }
+    }
+
+    /// Decodes an encoded node.
+    pub(super) fn decode(mut buf: &[u8]) -> Result<Self, Error> {
+        let header = Header::decode(&mut buf)?;
+        if !header.list {
+            return Err(Error::UnexpectedString);
+        }
+        let mut payload = buf
+            .get(..header.payload_length)
+            .ok_or(Error::InputTooShort)?;
+        let mut items = Vec::with_capacity(17);
+        while !payload.is_empty() {
+            items.push(split_item(&mut payload)?);
+        }
+
+        match items.as_slice() {
+            [path, item] => {
+                let (path, is_leaf) = expand_path(decode_bytes(path)?)?;
+                Ok(if is_leaf {
+                    Self::Leaf {
+                        path,
+                        value: decode_bytes(item)?.to_vec(),
+                    }
+                } else {
+                    Self::Extension {
+                        path,
+                        child: decode_child(item)?,
+                    }
+                })
+            }
+            [children @ .., value] if children.len() == 16 => {
+                let mut decoded: Box<[Child; 16]> = Default::default();
+                for (child, item) in decoded.iter_mut().zip(children) {
+                    *child = decode_child(item)?;
+                }
+                let value = decode_bytes(value)?;
+                Ok(Self::Branch {
+                    children: decoded,
+                    value: (!value.is_empty()).then(|| value.to_vec()),
+                })
+            }
+            _ => Err(Error::Custom("invalid trie node")),
+        }
+    }
+}
+
+/// Splits the next RLP item off the buffer.
+fn split_item<'a>(buf: &mut &'a [u8]) -> Result<&'a [u8], Error> {
+    let mut rest = *buf;
+    let header = Header::decode(&mut rest)?;
+    let length = buf.len() - rest.len() + header.payload_length;
+    if length > buf.len() {
+        return Err(Error::InputTooShort);
+    }
+    let (item, rest) = buf.split_at(length);
+    *buf = rest;
+    Ok(item)
+}
+
+fn decode_bytes(mut item: &[u8]) -> Result<&[u8], Error> {
+    Header::decode_bytes(&mut item, false)
+}
+
+/// Decodes the reference to a child, which is either its hash or its inlined encoding.
+fn decode_child(item: &[u8]) -> Result<Child, Error> {
+    if Header::decode(&mut &item[..])?.list {
+        return Node::decode(item).map(|node| Child::Node(Box::new(node)));
+    }
+    match decode_bytes(item)? {
+        [] => Ok(Child::Empty),
+        hash if hash.len() == 32 => Ok(Child::Hash(B256::from_slice(hash))),
+        _ => Err(Error::Custom("invalid trie node reference")),
+    }
+}
+
+/// Decodes the hex prefix encoding of a path, returning the nibbles and the leaf flag.
+fn expand_path(compact: &[u8]) -> Result<(Vec<u8>, bool), Error> {
+    let (&first, rest) = compact
+        .split_first()
+        .ok_or(Error::Custom("empty trie node path"))?;
+    let flag = first >> 4;
+    if flag > 3 {
+        return Err(Error::Custom("invalid trie node path"));
+    }
+    let mut path = Vec::with_capacity(rest.len() * 2 + 1);
+    if flag & 1 == 1 {
+        path.push(first & 0x0f);
+    }
+    path.extend(rest.iter().flat_map(|byte| [byte >> 4, byte & 0x0f]));
+    Ok((path, flag & 2 == 2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trie::compact_path;
+
+    #[test]
+    fn path_roundtrip() {
+        for path in [&[][..], &[1], &[1, 2], &[0xf, 0, 0xa], &[0, 0, 0, 0]] {
+            for is_leaf in [false, true] {
+                assert_eq!(
+                    expand_path(&compact_path(path, is_leaf)).unwrap(),
+                    (path.to_vec(), is_leaf)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn node_roundtrip() {
+        let mut children: Box<[Child; 16]> = Default::default();
+        children[1] = Child::Hash(B256::repeat_byte(0x11));
+        children[7] = Child::Node(Box::new(Node::Leaf {
+            path: Vec::from([3]),
+            value: Vec::from([4]),
+        }));
+        let mut node = Node::Extension {
+            path: Vec::from([1, 2, 3]),
+            child: Child::Node(Box::new(Node::Branch {
+                children,
+                value: Some(Vec::from([5; 40])),
+            })),
+        };
+        let expected = node.clone();
+
+        let mut nodes = Vec::new();
+        let encoded = node.commit(&mut nodes);
+        // The branch is stored by hash, its leaf child is inlined.
+        assert_eq!(nodes.len(), 1);
+        let Node::Extension { child, .. } = Node::decode(&encoded).unwrap() else {
+            panic!("expected an extension");
+        };
+        assert_eq!(child, Child::Hash(keccak256(&nodes[0].1)));
+
+        let Node::Extension { child, .. } = expected else {
+            unreachable!()
+        };
+        let Child::Node(branch) = child else {
+            unreachable!()
+        };
+        assert_eq!(Node::decode(&nodes[0].1).unwrap(), *branch);
+    }
+}
@@ -0,0 +1,370 @@
+//! Merkle proofs of the trie values, as returned by `eth_getProof`.
+use super::{
+    node::{Child, Node},
+    unpack_nibbles, TrieAccount, TrieError, TrieStore, EMPTY_ROOT_HASH,
+};
+use alloy_rlp::Decodable;
+use core::fmt;
+use primitives::{keccak256, Address, Bytes, B256, U256};
+use std::vec::Vec;
+
+/// Errors of the proof verification.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProofError {
+    /// The proof ends before the node with the given hash.
+    MissingNode(B256),
+    /// A node of the proof doesn't have the hash referenced by its parent.
+    HashMismatch {
+        /// Hash referenced by the parent.
+        expected: B256,
+        /// Hash of the node in the proof.
+        got: B256,
+    },
+    /// A node of the proof or the proven value can't be decoded.
+    Decode(alloy_rlp::Error),
+    /// The proof has more nodes than the path of the key.
+    UnusedNodes,
+    /// The proven value doesn't match the claimed value.
+    ValueMismatch,
+}
+
+impl std::error::Error for ProofError {}
+
+impl fmt::Display for ProofError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingNode(hash) => write!(f, "proof is missing node {hash}"),
+            Self::HashMismatch { expected, got } => {
+                write!(
+                    f,
+                    "proof node hash mismatch: got {got}, expected {expected}"
+                )
+            }
+            Self::Decode(error) => write!(f, "invalid proof node: {error}"),
+            Self::UnusedNodes => f.write_str("proof has unused nodes"),
+            Self::ValueMismatch => f.write_str("proven value mismatch"),
+        }
+    }
+}
+
+/// Proof of an account and of some of its storage slots.
+///
+/// Accounts that don't exist are proven with default values: zero nonce and balance, empty
+/// code hash and empty storage root.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct AccountProof {
+    /// Address of the account.
+    pub address: Address,
+    /// Account balance.
+    pub balance: U256,
+    /// Hash of the account code.
+    pub code_hash: B256,
+    /// Account nonce.
+    #[cfg_attr(feature = "serde", serde(with = "quantity"))]
+    pub nonce: u64,
+    /// Root of the account storage trie.
+    pub storage_hash: B256,
+    /// Nodes of the state trie on the path of the account, starting with the root.
+    pub account_proof: Vec<Bytes>,
+    /// Proofs of the storage slots.
+    pub storage_proof: Vec<StorageProof>,
+}
+
+/// Proof of a storage slot.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StorageProof {
+    /// Storage slot.
+    pub key: U256,
+    /// Value of the slot, zero if the slot is not set.
+    pub value: U256,
+    /// Nodes of the storage trie on the path of the slot, starting with the root.
+    pub proof: Vec<Bytes>,
+}
+
+impl AccountProof {
+    /// Returns the proven account.
+    pub fn account(&self) -> TrieAccount {
+        TrieAccount {
+            nonce: self.nonce,
+            balance: self.balance,
+            storage_root: self.storage_hash,
+            code_hash: self.code_hash,
+        }
+    }
+
+    /// Verifies the account proof against the state root, and the storage proofs against the
+    /// storage root of the account.
+    ///
+    /// Returns the proven account, `None` if the account doesn't exist.
+    pub fn verify(&self, state_root: B256) -> Result<Option<TrieAccount>, ProofError> {
+        let account = verify_account_proof(state_root, self.address, &self.account_proof)?;
+        if account.unwrap_or_default() != self.account() {
+            return Err(ProofError::ValueMismatch);
+        }
+        for storage in &self.storage_proof {
+            storage.verify(self.storage_hash)?;
+        }
+        Ok(account)
+    }
+}
+
+impl StorageProof {
+    /// Verifies the proof against the storage root.
+    pub fn verify(&self, storage_root: B256) -> Result<(), ProofError> {
+        if verify_storage_proof(storage_root, self.key, &self.proof)? != self.value {
+            return Err(ProofError::ValueMismatch);
+        }
+        Ok(())
+    }
+}
+
+/// Returns the nodes on the path of the key in the trie with the given root.
+///
+/// The proof of a key that is not in the trie proves its absence.
+pub fn proof<S: TrieStore>(
+    store: &S,
+    root: B256,
+    key: &[u8],
+) -> Result<Vec<Bytes>, TrieError<S::Error>> {
+    let mut nodes = Vec::new();
+    if root == EMPTY_ROOT_HASH {
+        return Ok(nodes);
+    }
+    let path = unpack_nibbles(key);
+    let mut path = path.as_slice();
+    let mut child = Child::Hash(root);
+    loop {
+        let node = match child {
+            Child::Empty => return Ok(nodes),
+            Child::Hash(hash) => {
+                let encoded = store
+                    .node(&hash)
+                    .map_err(TrieError::Store)?
+                    .ok_or(TrieError::MissingNode(hash))?;
+                let node = Node::decode(&encoded).map_err(TrieError::Decode)?;
+                nodes.push(encoded.into());
+                node
+            }
+            // Inlined nodes are part of their parent.
+            Child::Node(node) => *node,
+        };
+        match next(node, &mut path) {
+            Some(next) => child = next,
+            None => return Ok(nodes),
+        }
+    }
+}
+
+/// Verifies the proof of the key against the root, and returns the proven value.
+///
+/// Returns `None` if the proof shows that the key is not in the trie.
+pub fn verify_proof(
+    root: B256,
+    key: &[u8],
+    proof: &[impl AsRef<[u8]>],
+) -> Result<Option<Vec<u8>>, ProofError> {
+    let mut proof = proof.iter();
+    let path = unpack_nibbles(key);
+    let mut path = path.as_slice();
+    let mut child = if root == EMPTY_ROOT_HASH {
+        Child::Empty
+    } else {
+        Child::Hash(root)
+    };
+    let value = loop {
+        let node = match child {
+            Child::Empty => break None,
+            Child::Hash(expected) => {
+                let encoded = proof.next().ok_or(ProofError::MissingNode(expected))?;
+                let got = keccak256(encoded);
+                if got != expected {
+                    return Err(ProofError::HashMismatch { expected, got });
+                }
+                Node::decode(encoded.as_ref()).map_err(ProofError::Decode)?
+            }
+            Child::Node(node) => *node,
+        };
+        match node {
+            Node::Leaf {
+                path: leaf,
+                value: leaf_value,
+            } => break (leaf == path).then_some(leaf_value),
+            Node::Branch {
+                value: branch_value,
+                ..
+            } if path.is_empty() => break branch_value,
+            node => match next(node, &mut path) {
+                Some(next) => child = next,
+                None => break None,
+            },
+        }
+    };
+    if proof.next().is_some() {
+        return Err(ProofError::UnusedNodes);
+    }
+    Ok(value)
+}
+
+/// Verifies the proof of the account against the state root.
+pub fn verify_account_proof(
+    state_root: B256,
+    address: Address,
+    proof: &[impl AsRef<[u8]>],
+) -> Result<Option<TrieAccount>, ProofError> {
+    verify_proof(state_root, keccak256(address).as_slice(), proof)?
+        .map(|encoded| TrieAccount::decode(&encoded).map_err(ProofError::Decode))
+        .transpose()
+}
+
+/// Verifies the proof of the storage slot against the storage root, and returns its value.
+pub fn verify_storage_proof(
+    storage_root: B256,
+    slot: U256,
+    proof: &[impl AsRef<[u8]>],
+) -> Result<U256, ProofError> {
+    match verify_proof(
+        storage_root,
+        keccak256(slot.to_be_bytes::<32>()).as_slice(),
+        proof,
+    )? {
+        Some(encoded) => U256::decode(&mut encoded.as_slice()).map_err(ProofError::Decode),
+        None => Ok(U256::ZERO),
+    }
+}
+
+/// Returns the child on the path, advancing the path, or `None` if the path ends at the node.
+fn next(node: Node, path: &mut &[u8]) -> Option<Child> {
+    match node {
+        Node::Leaf { .. } => None,
+        Node::Extension {
+            path: extension,
+            child,
+        } => {
+            *path = path.strip_prefix(extension.as_slice())?;
+            Some(child)
+        }
+        Node::Branch { mut children, .. } => {
+            let (&nibble, rest) = path.split_first()?;
+            *path = rest;
+            Some(core::mem::take(&mut children[nibble as usize]))
+        }
+    }
+}
+
+/// Serializes `u64` as a hex quantity, like the other numbers of `eth_getProof`.
+#[cfg(feature = "serde")]
+mod quantity {
+    use primitives::alloy_primitives::U64;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        U64::from(*value).serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        U64::deserialize(deserializer).map(|value| value.to())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trie::{InMemoryTrieStore, MerkleTrie};
+
+    type Entries = Vec<(Vec<u8>, Vec<u8>)>;
+
+    fn trie(count: usize) -> (InMemoryTrieStore, B256, Entries) {
+        let entries: Vec<_> = (0..count)
+            .map(|i| {
+                let key = keccak256((i as u64).to_be_bytes()).to_vec();
+                // Short values are inlined in their parent.
+                (key, vec![(i % 200) as u8 + 1; 1 + i % 40])
+            })
+            .collect();
+        let mut store = InMemoryTrieStore::default();
+        let mut trie = MerkleTrie::new();
+        for (key, value) in &entries {
+            trie.insert(&store, key, value.clone()).unwrap();
+        }
+        let root = trie.commit(&mut store).unwrap();
+        (store, root, entries)
+    }
+
+    #[test]
+    fn inclusion() {
+        let (store, root, entries) = trie(100);
+        for (key, value) in &entries {
+            let nodes = proof(&store, root, key).unwrap();
+            assert_eq!(verify_proof(root, key, &nodes), Ok(Some(value.clone())));
+        }
+    }
+
+    #[test]
+    fn exclusion() {
+        let (store, root, _) = trie(100);
+        for i in 100..150u64 {
+            let key = keccak256(i.to_be_bytes());
+            let nodes = proof(&store, root, key.as_slice()).unwrap();
+            assert_eq!(verify_proof(root, key.as_slice(), &nodes), Ok(None));
+        }
+
+        let key = keccak256([1]);
+        let nodes = proof(
+            &InMemoryTrieStore::default(),
+            EMPTY_ROOT_HASH,
+            key.as_slice(),
+        );
+        assert_eq!(nodes, Ok(Vec::new()));
+        assert_eq!(
+            verify_proof(EMPTY_ROOT_HASH, key.as_slice(), &[] as &[Bytes]),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn invalid_proofs() {
+        let (store, root, entries) = trie(100);
+        let key = &entries[7].0;
+        let mut nodes = proof(&store, root, key).unwrap();
+
+        let missing = nodes[..nodes.len() - 1].to_vec();
+        assert!(matches!(
+            verify_proof(root, key, &missing),
+            Err(ProofError::MissingNode(_))
+        ));
+
+        let mut extra = nodes.clone();
+        extra.push(nodes[0].clone());
+        assert_eq!(
+            verify_proof(root, key, &extra),
+            Err(ProofError::UnusedNodes)
+        );
+
+        let mut tampered = nodes[1].to_vec();
+        *tampered.last_mut().unwrap() ^= 1;
+        nodes[1] = tampered.into();
+        assert!(matches!(
+            verify_proof(root, key, &nodes),
+            Err(ProofError::HashMismatch { .. })
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialize_account_proof() {
+        let account = AccountProof {
+            balance: U256::from(10),
+            nonce: 42,
+            ..Default::default()
+        };
+        let json = serde_json::to_value(&account).unwrap();
+        assert_eq!(json["balance"], "0xa");
+        assert_eq!(json["nonce"], "0x2a");
+
+        let deserialized: AccountProof = serde_json::from_value(json).unwrap();
+        assert_eq!(deserialized, account);
+    }
+}
@@ -0,0 +1,436 @@
+//! Incremental state root computation.
+use super::{
+    encode_list, proof, AccountProof, MerkleTrie, StorageProof, TrieError, TrieStore,
+    EMPTY_ROOT_HASH,
+};
+use crate::states::{BundleState, OriginalValuesKnown, StateChangeset};
+use alloy_rlp::{Decodable, Header};
+use primitives::{keccak256, Address, B256, KECCAK_EMPTY, U256};
+use state::AccountInfo;
+use std::{collections::BTreeMap, vec::Vec};
+
+/// Account as it is stored in the state trie.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TrieAccount {
+    /// Account nonce.
+    pub nonce: u64,
+    /// Account balance.
+    pub balance: U256,
+    /// Root of the storage trie.
+    pub storage_root: B256,
+    /// Hash of the account code.
+    pub code_hash: B256,
+}
+
+impl Default for TrieAccount {
+    fn default() -> Self {
+        Self {
+            nonce: 0,
+            balance: U256::ZERO,
+            storage_root: EMPTY_ROOT_HASH,
+            code_hash: KECCAK_EMPTY,
+        }
+    }
+}
+
+impl TrieAccount {
+    /// Returns the RLP encoding of the account.
+    pub fn encode(&self) -> Vec<u8> {
+        encode_list(&[
+            alloy_rlp::encode(self.nonce),
+            alloy_rlp::encode(self.balance),
+            alloy_rlp::encode(self.storage_root),
+            alloy_rlp::encode(self.code_hash),
+        ])
+    }
+
+    /// Decodes the RLP encoding of the account.
+    pub fn decode(mut buf: &[u8]) -> Result<Self, alloy_rlp::Error> {
+        let header = Header::decode(&mut buf)?;
+        if !header.list {
+            return Err(alloy_rlp::Error::UnexpectedString);
+        }
+        Ok(Self {
+            nonce: u64::decode(&mut buf)?,
+            balance: U256::decode(&mut buf)?,
+            storage_root: B256::decode(&mut buf)?,
+            code_hash: B256::decode(&mut buf)?,
+        })
+    }
+}
+
+/// Changes of an account to apply to the tries.
+#[derive(Default)]
+struct AccountChange<'a> {
+    /// New account info, `Some(None)` if the account is removed.
+    info: Option<Option<&'a AccountInfo>>,
+    wipe_storage: bool,
+    storage: Vec<(U256, U256)>,
+}
+
+/// Computes the state root incrementally, from the changes of each block.
+///
+/// The account trie and the storage tries are kept in a [TrieStore], only the accounts and the
+/// storage slots that changed are rehashed.
+#[derive(Clone, Debug)]
+pub struct StateRoot<S> {
+    store: S,
+    root: B256,
+}
+
+impl<S: TrieStore> StateRoot<S> {
+    /// Creates the calculator for an empty state.
+    pub fn new(store: S) -> Self {
+        Self::with_root(store, EMPTY_ROOT_HASH)
+    }
+
+    /// Creates the calculator for a state whose tries are in the store.
+    pub fn with_root(store: S, root: B256) -> Self {
+        Self { store, root }
+    }
+
+    /// Returns the state root.
+    pub fn root(&self) -> B256 {
+        self.root
+    }
+
+    /// Returns the trie store.
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Returns the trie store.
+    pub fn store_mut(&mut self) -> &mut S {
+        &mut self.store
+    }
+
+    /// Consumes the calculator and returns the trie store.
+    pub fn into_store(self) -> S {
+        self.store
+    }
+
+    /// Returns the account as stored in the state trie.
+    pub fn account(&self, address: Address) -> Result<Option<TrieAccount>, TrieError<S::Error>> {
+        account(&self.store, &MerkleTrie::from_root(self.root), address)
+    }
+
+    /// Returns the value of the storage slot.
+    pub fn storage(&self, address: Address, slot: U256) -> Result<U256, TrieError<S::Error>> {
+        let Some(account) = self.account(address)? else {
+            return Ok(U256::ZERO);
+        };
+        let value = MerkleTrie::from_root(account.storage_root)
+            .get(&self.store, keccak256(slot.to_be_bytes::<32>()).as_slice())?;
+        match value {
+            Some(value) => U256::decode(&mut value.as_slice()).map_err(TrieError::Decode),
+            None => Ok(U256::ZERO),
+        }
+    }
+
+    /// Returns the proof of the account and of its storage slots, as served by `eth_getProof`.
+    pub fn account_proof(
+        &self,
+        address: Address,
+        slots: &[U256],
+    ) -> Result<AccountProof, TrieError<S::Error>> {
+        let account = self.account(address)?.unwrap_or_default();
+        let mut storage_proof = Vec::with_capacity(slots.len());
+        for slot in slots {
+            storage_proof.push(StorageProof {
+                key: *slot,
+                value: self.storage(address, *slot)?,
+                proof: proof(
+                    &self.store,
+                    account.storage_root,
+                    keccak256(slot.to_be_bytes::<32>()).as_slice(),
+                )?,
+            });
+        }
+        Ok(AccountProof {
+            address,
+            balance: account.balance,
+            code_hash: account.code_hash,
+            nonce: account.nonce,
+            storage_hash: account.storage_root,
+            account_proof: proof(&self.store, self.root, keccak256(address).as_slice())?,
+            storage_proof,
+        })
+    }
+
+    /// Applies the changes of the bundle and returns the new state root.
+    pub fn apply_bundle(&mut self, bundle: &BundleState) -> Result<B256, TrieError<S::Error>> {
+        self.apply_changeset(&bundle.to_plain_state(OriginalValuesKnown::Yes))
+    }
+
+    /// Applies the changeset and returns the new state root.
+    ///
+    /// Accounts with only storage changes keep their info, and a wiped storage is cleared
+    /// before the slots of the changeset are applied.
+    pub fn apply_changeset(
+        &mut self,
+        changeset: &StateChangeset,
+    ) -> Result<B256, TrieError<S::Error>> {
+        let mut changes = BTreeMap::<Address, AccountChange<'_>>::new();
+        for (address, info) in &changeset.accounts {
+            changes.entry(*address).or_default().info = Some(info.as_ref());
+        }
+        for storage in &changeset.storage {
+            let change = changes.entry(storage.address).or_default();
+            change.wipe_storage |= storage.wipe_storage;
+            change.storage.extend_from_slice(&storage.storage);
+        }
+
+        let mut accounts = MerkleTrie::from_root(self.root);
+        for (address, change) in changes {
+            let key = keccak256(address);
+            if change.info == Some(None) {
+                accounts.remove(&self.store, key.as_slice())?;
+                continue;
+            }
+
+            let mut account = account(&self.store, &accounts, address)?.unwrap_or_default();
+            if let Some(Some(info)) = change.info {
+                account.nonce = info.nonce;
+                account.balance = info.balance;
+                account.code_hash = info.code_hash;
+            }
+            if change.wipe_storage {
+                account.storage_root = EMPTY_ROOT_HASH;
+            }
+            if !change.storage.is_empty() {
+                let mut storage = MerkleTrie::from_root(account.storage_root);
+                for (slot, value) in change.storage {
+                    let key = keccak256(slot.to_be_bytes::<32>());
+                    if value.is_zero() {
+                        storage.remove(&self.store, key.as_slice())?;
+                    } else {
+                        storage.insert(&self.store, key.as_slice(), alloy_rlp::encode(value))?;
+                    }
+                }
+                account.storage_root = storage.commit(&mut self.store)?;
+            }
+            accounts.insert(&self.store, key.as_slice(), account.encode())?;
+        }
+
+        self.root = accounts.commit(&mut self.store)?;
+        Ok(self.root)
+    }
+}
+
+fn account<S: TrieStore>(
+    store: &S,
+    accounts: &MerkleTrie,
+    address: Address,
+) -> Result<Option<TrieAccount>, TrieError<S::Error>> {
+    accounts
+        .get(store, keccak256(address).as_slice())?
+        .map(|encoded| TrieAccount::decode(&encoded).map_err(TrieError::Decode))
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        states::{bundle_state::BundleRetention, PlainStorageChangeset},
+        trie::{sec_trie_root, InMemoryTrieStore},
+        InMemoryDB, State,
+    };
+    use database_interface::DatabaseCommit;
+    use primitives::{address, HashMap};
+    use state::{Account, AccountStatus, EvmStorageSlot};
+
+    type Accounts = BTreeMap<Address, (AccountInfo, BTreeMap<U256, U256>)>;
+
+    /// Rebuilds the state root from all the accounts.
+    fn full_root(accounts: &Accounts) -> B256 {
+        sec_trie_root(accounts.iter().map(|(address, (info, storage))| {
+            let storage_root = sec_trie_root(
+                storage
+                    .iter()
+                    .filter(|(_, value)| !value.is_zero())
+                    .map(|(slot, value)| (slot.to_be_bytes::<32>(), alloy_rlp::encode(value))),
+            );
+            let account = TrieAccount {
+                nonce: info.nonce,
+                balance: info.balance,
+                storage_root,
+                code_hash: info.code_hash,
+            };
+            (address, account.encode())
+        }))
+    }
+
+    fn info(nonce: u64) -> AccountInfo {
+        AccountInfo {
+            nonce,
+            balance: U256::from(nonce * 1000),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn account_roundtrip() {
+        let account = TrieAccount {
+            nonce: 7,
+            balance: U256::from(1234),
+            storage_root: B256::repeat_byte(1),
+            code_hash: B256::repeat_byte(2),
+        };
+        assert_eq!(TrieAccount::decode(&account.encode()), Ok(account));
+    }
+
+    #[test]
+    fn apply_changesets() {
+        let mut state_root = StateRoot::new(InMemoryTrieStore::default());
+        let mut accounts = Accounts::new();
+        let addresses: Vec<Address> = (0..40u8).map(|i| Address::repeat_byte(i + 1)).collect();
+
+        // Create the accounts, half of them with storage.
+        let mut changeset = StateChangeset::default();
+        for (i, address) in addresses.iter().enumerate() {
+            let storage: Vec<_> = (0..(i % 2) * 5)
+                .map(|slot| (U256::from(slot), U256::from(i * 10 + slot + 1)))
+                .collect();
+            changeset.accounts.push((*address, Some(info(i as u64))));
+            changeset.storage.push(PlainStorageChangeset {
+                address: *address,
+                wipe_storage: false,
+                storage: storage.clone(),
+            });
+            accounts.insert(*address, (info(i as u64), storage.into_iter().collect()));
+        }
+        assert_eq!(
+            state_root.apply_changeset(&changeset),
+            Ok(full_root(&accounts))
+        );
+
+        // Update infos, update and clear slots, wipe storages and remove accounts.
+        let mut changeset = StateChangeset::default();
+        for (i, address) in addresses.iter().enumerate() {
+            let (account_info, storage) = accounts.get_mut(address).unwrap();
+            match i % 4 {
+                0 => {
+                    *account_info = info(100 + i as u64);
+                    changeset
+                        .accounts
+                        .push((*address, Some(account_info.clone())));
+                }
+                1 => {
+                    let slots = [(U256::from(0), U256::ZERO), (U256::from(9), U256::from(9))];
+                    storage.extend(slots);
+                    changeset.storage.push(PlainStorageChangeset {
+                        address: *address,
+                        wipe_storage: false,
+                        storage: slots.to_vec(),
+                    });
+                }
+                2 => {
+                    accounts.remove(address);
+                    changeset.accounts.push((*address, None));
+                }
+                _ => {
+                    storage.clear();
+                    storage.insert(U256::from(1), U256::from(1));
+                    changeset.storage.push(PlainStorageChangeset {
+                        address: *address,
+                        wipe_storage: true,
+                        storage: vec![(U256::from(1), U256::from(1))],
+                    });
+                }
+            }
+        }
+        assert_eq!(
+            state_root.apply_changeset(&changeset),
+            Ok(full_root(&accounts))
+        );
+
+        assert_eq!(
+            state_root.account(addresses[2]),
+            Ok(None),
+            "removed account"
+        );
+        assert_eq!(
+            state_root.storage(addresses[1], U256::from(9)),
+            Ok(U256::from(9))
+        );
+        assert_eq!(state_root.storage(addresses[1], U256::ZERO), Ok(U256::ZERO));
+
+        // Proofs of existing and removed accounts, with set and unset slots.
+        for address in &addresses[..4] {
+            let proof = state_root
+                .account_proof(*address, &[U256::from(1), U256::from(9)])
+                .unwrap();
+            let account = proof.verify(state_root.root()).unwrap();
+            assert_eq!(account.is_some(), accounts.contains_key(address));
+            let (info, storage) = accounts
+                .get(address)
+                .cloned()
+                .unwrap_or((AccountInfo::default(), BTreeMap::new()));
+            assert_eq!(proof.balance, info.balance);
+            assert_eq!(
+                proof.storage_proof[1].value,
+                storage.get(&U256::from(9)).copied().unwrap_or_default()
+            );
+        }
+    }
+
+    #[test]
+    fn apply_bundle() {
+        let existing = address!("0000000000000000000000000000000000000e01");
+        let created = address!("0000000000000000000000000000000000000e02");
+
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(existing, info(1));
+        db.insert_account_storage(existing, U256::from(1), U256::from(1))
+            .unwrap();
+        let mut accounts = Accounts::from([(
+            existing,
+            (info(1), BTreeMap::from([(U256::from(1), U256::from(1))])),
+        )]);
+        let mut state_root = StateRoot::new(InMemoryTrieStore::default());
+        state_root
+            .apply_changeset(&StateChangeset {
+                accounts: vec![(existing, Some(info(1)))],
+                storage: vec![PlainStorageChangeset {
+                    address: existing,
+                    wipe_storage: false,
+                    storage: vec![(U256::from(1), U256::from(1))],
+                }],
+                contracts: Vec::new(),
+            })
+            .unwrap();
+
+        let mut state = State::builder()
+            .with_database(db)
+            .with_bundle_update()
+            .build();
+        let mut changes = HashMap::default();
+        // Only the storage of the existing account changes.
+        let mut account = Account::from(info(1));
+        account.status = AccountStatus::Touched;
+        account.storage.insert(
+            U256::from(2),
+            EvmStorageSlot::new_changed(U256::ZERO, U256::from(2)),
+        );
+        changes.insert(existing, account);
+        let mut account = Account::from(info(5));
+        account.status = AccountStatus::Touched | AccountStatus::Created;
+        changes.insert(created, account);
+        state.load_cache_account(existing).unwrap();
+        state.load_cache_account(created).unwrap();
+        state.commit(changes);
+        state.merge_transitions(BundleRetention::PlainState);
+
+        accounts
+            .get_mut(&existing)
+            .unwrap()
+            .1
+            .insert(U256::from(2), U256::from(2));
+        accounts.insert(created, (info(5), BTreeMap::new()));
+        assert_eq!(
+            state_root.apply_bundle(&state.take_bundle()),
+            Ok(full_root(&accounts))
+        );
+    }
+}
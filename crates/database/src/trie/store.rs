@@ -0,0 +1,52 @@
+//! Storage of the trie nodes.
+use auto_impl::auto_impl;
+use core::convert::Infallible;
+use primitives::{HashMap, B256};
+use std::vec::Vec;
+
+/// Storage of the encoded trie nodes, keyed by their hash.
+///
+/// Nodes are never removed, so the tries of previous roots stay readable.
+#[auto_impl(&mut, Box)]
+pub trait TrieStore {
+    /// The store error type.
+    type Error;
+
+    /// Returns the encoded node with the given hash.
+    fn node(&self, hash: &B256) -> Result<Option<Vec<u8>>, Self::Error>;
+
+    /// Inserts the encoded node with its hash.
+    fn insert_node(&mut self, hash: B256, node: Vec<u8>) -> Result<(), Self::Error>;
+}
+
+/// Trie store keeping the nodes in memory.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InMemoryTrieStore {
+    /// Encoded nodes by hash.
+    pub nodes: HashMap<B256, Vec<u8>>,
+}
+
+impl InMemoryTrieStore {
+    /// Returns the number of stored nodes.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns `true` if no nodes are stored.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
+
+impl TrieStore for InMemoryTrieStore {
+    type Error = Infallible;
+
+    fn node(&self, hash: &B256) -> Result<Option<Vec<u8>>, Self::Error> {
+        Ok(self.nodes.get(hash).cloned())
+    }
+
+    fn insert_node(&mut self, hash: B256, node: Vec<u8>) -> Result<(), Self::Error> {
+        self.nodes.insert(hash, node);
+        Ok(())
+    }
+}
@@ -0,0 +1,290 @@
+use crate::trie::{AccountProof, ProofError, TrieAccount, EMPTY_ROOT_HASH};
+use core::fmt;
+use database_interface::{Database, DatabaseRef};
+use primitives::{Address, HashMap, B256, KECCAK_EMPTY, U256};
+use state::{AccountInfo, Bytecode};
+
+/// A [Database] implementation backed only by verified proofs.
+///
+/// Accounts and storage slots are added from their proofs against the state root, contracts
+/// and block hashes are added as they are. Reading anything that was not added is an error,
+/// so executing a block on top of this database checks that the witness is complete.
+#[derive(Clone, Debug)]
+pub struct WitnessDB {
+    /// State root the proofs are verified against.
+    state_root: B256,
+    /// Proven accounts, `None` if the account doesn't exist.
+    accounts: HashMap<Address, Option<TrieAccount>>,
+    /// Proven storage slots.
+    storage: HashMap<(Address, U256), U256>,
+    /// Contracts by their code hash.
+    contracts: HashMap<B256, Bytecode>,
+    /// Block hashes by their number.
+    block_hashes: HashMap<u64, B256>,
+}
+
+/// Error of the [WitnessDB] and the [ReplayDB](crate::ReplayDB), the witness misses the data
+/// read by the execution.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WitnessDBError {
+    /// The account has no proof.
+    MissingAccount(Address),
+    /// The storage slot has no proof.
+    MissingStorage {
+        /// Address of the account.
+        address: Address,
+        /// Storage slot.
+        slot: U256,
+    },
+    /// The contract with the code hash was not added.
+    MissingCode(B256),
+    /// The block hash was not added.
+    MissingBlockHash(u64),
+}
+
+impl std::error::Error for WitnessDBError {}
+
+impl fmt::Display for WitnessDBError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingAccount(address) => write!(f, "missing proof of account {address}"),
+            Self::MissingStorage { address, slot } => {
+                write!(
+                    f,
+                    "missing proof of storage slot {slot} of account {address}"
+                )
+            }
+            Self::MissingCode(code_hash) => write!(f, "missing code {code_hash}"),
+            Self::MissingBlockHash(number) => write!(f, "missing hash of block {number}"),
+        }
+    }
+}
+
+impl WitnessDB {
+    /// Creates an empty witness for the state with the given root.
+    pub fn new(state_root: B256) -> Self {
+        let mut contracts = HashMap::default();
+        contracts.insert(KECCAK_EMPTY, Bytecode::default());
+        Self {
+            state_root,
+            accounts: HashMap::default(),
+            storage: HashMap::default(),
+            contracts,
+            block_hashes: HashMap::default(),
+        }
+    }
+
+    /// Returns the state root.
+    pub fn state_root(&self) -> B256 {
+        self.state_root
+    }
+
+    /// Verifies the proof against the state root and adds the account and its storage slots.
+    pub fn insert_proof(&mut self, proof: &AccountProof) -> Result<(), ProofError> {
+        let account = proof.verify(self.state_root)?;
+        self.accounts.insert(proof.address, account);
+        for storage in &proof.storage_proof {
+            self.storage
+                .insert((proof.address, storage.key), storage.value);
+        }
+        Ok(())
+    }
+
+    /// Adds a contract, keyed by its code hash.
+    pub fn insert_contract(&mut self, bytecode: Bytecode) {
+        self.contracts.insert(bytecode.hash_slow(), bytecode);
+    }
+
+    /// Adds a block hash.
+    pub fn insert_block_hash(&mut self, number: u64, hash: B256) {
+        self.block_hashes.insert(number, hash);
+    }
+}
+
+impl DatabaseRef for WitnessDB {
+    type Error = WitnessDBError;
+
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        let account = self
+            .accounts
+            .get(&address)
+            .ok_or(WitnessDBError::MissingAccount(address))?;
+        Ok(account.map(|account| AccountInfo {
+            balance: account.balance,
+            nonce: account.nonce,
+            code_hash: account.code_hash,
+            code: self.contracts.get(&account.code_hash).cloned(),
+        }))
+    }
+
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.contracts
+            .get(&code_hash)
+            .cloned()
+            .ok_or(WitnessDBError::MissingCode(code_hash))
+    }
+
+    fn storage_ref(&self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        match self.accounts.get(&address) {
+            None => Err(WitnessDBError::MissingAccount(address)),
+            // Storage of accounts that don't exist or have no storage needs no proof.
+            Some(None) => Ok(U256::ZERO),
+            Some(Some(account)) if account.storage_root == EMPTY_ROOT_HASH => Ok(U256::ZERO),
+            Some(Some(_)) => {
+                self.storage
+                    .get(&(address, index))
+                    .copied()
+                    .ok_or(WitnessDBError::MissingStorage {
+                        address,
+                        slot: index,
+                    })
+            }
+        }
+    }
+
+    fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
+        self.block_hashes
+            .get(&number)
+            .copied()
+            .ok_or(WitnessDBError::MissingBlockHash(number))
+    }
+}
+
+impl Database for WitnessDB {
+    type Error = WitnessDBError;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.basic_ref(address)
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.code_by_hash_ref(code_hash)
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        self.storage_ref(address, index)
+    }
+
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        self.block_hash_ref(number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        states::{PlainStorageChangeset, StateChangeset},
+        trie::{InMemoryTrieStore, StateRoot},
+    };
+    use primitives::{address, keccak256};
+
+    const CALLER: Address = address!("0000000000000000000000000000000000000a11");
+    const CONTRACT: Address = address!("0000000000000000000000000000000000000c0d");
+    const MISSING: Address = address!("0000000000000000000000000000000000000e0e");
+
+    fn state() -> (StateRoot<InMemoryTrieStore>, Bytecode) {
+        let code = Bytecode::new_raw([0x60, 0x01, 0x00].into());
+        let mut state_root = StateRoot::new(InMemoryTrieStore::default());
+        state_root
+            .apply_changeset(&StateChangeset {
+                accounts: vec![
+                    (CALLER, Some(AccountInfo::from_balance(U256::from(1000)))),
+                    (
+                        CONTRACT,
+                        Some(AccountInfo {
+                            code_hash: code.hash_slow(),
+                            ..Default::default()
+                        }),
+                    ),
+                ],
+                storage: vec![PlainStorageChangeset {
+                    address: CONTRACT,
+                    wipe_storage: false,
+                    storage: vec![(U256::ZERO, U256::from(41))],
+                }],
+                contracts: Vec::new(),
+            })
+            .unwrap();
+        (state_root, code)
+    }
+
+    #[test]
+    fn read_proven_state() {
+        let (state_root, code) = state();
+        let mut witness = WitnessDB::new(state_root.root());
+        for (address, slots) in [
+            (CALLER, &[][..]),
+            (CONTRACT, &[U256::ZERO, U256::from(1)][..]),
+            (MISSING, &[U256::ZERO][..]),
+        ] {
+            witness
+                .insert_proof(&state_root.account_proof(address, slots).unwrap())
+                .unwrap();
+        }
+
+        assert_eq!(
+            witness.basic(CALLER).unwrap().map(|info| info.balance),
+            Some(U256::from(1000))
+        );
+        assert_eq!(witness.basic(MISSING), Ok(None));
+        assert_eq!(witness.storage(CONTRACT, U256::ZERO), Ok(U256::from(41)));
+        assert_eq!(witness.storage(CONTRACT, U256::from(1)), Ok(U256::ZERO));
+        // Accounts without storage need no storage proofs.
+        assert_eq!(witness.storage(CALLER, U256::from(7)), Ok(U256::ZERO));
+        assert_eq!(witness.storage(MISSING, U256::from(7)), Ok(U256::ZERO));
+
+        let code_hash = code.hash_slow();
+        assert_eq!(
+            witness.code_by_hash(code_hash),
+            Err(WitnessDBError::MissingCode(code_hash))
+        );
+        witness.insert_contract(code.clone());
+        assert_eq!(witness.code_by_hash(code_hash), Ok(code.clone()));
+        assert_eq!(witness.basic(CONTRACT).unwrap().unwrap().code, Some(code));
+    }
+
+    #[test]
+    fn missing_witness() {
+        let (state_root, _) = state();
+        let mut witness = WitnessDB::new(state_root.root());
+        assert_eq!(
+            witness.basic(CONTRACT),
+            Err(WitnessDBError::MissingAccount(CONTRACT))
+        );
+        witness
+            .insert_proof(&state_root.account_proof(CONTRACT, &[]).unwrap())
+            .unwrap();
+        assert_eq!(
+            witness.storage(CONTRACT, U256::ZERO),
+            Err(WitnessDBError::MissingStorage {
+                address: CONTRACT,
+                slot: U256::ZERO
+            })
+        );
+        assert_eq!(
+            witness.block_hash(1),
+            Err(WitnessDBError::MissingBlockHash(1))
+        );
+        witness.insert_block_hash(1, B256::repeat_byte(1));
+        assert_eq!(witness.block_hash(1), Ok(B256::repeat_byte(1)));
+    }
+
+    #[test]
+    fn invalid_proofs() {
+        let (state_root, _) = state();
+        let mut proof = state_root.account_proof(CONTRACT, &[U256::ZERO]).unwrap();
+        proof.storage_proof[0].value = U256::from(1);
+        let mut witness = WitnessDB::new(state_root.root());
+        assert_eq!(witness.insert_proof(&proof), Err(ProofError::ValueMismatch));
+
+        let other_root = keccak256([1]);
+        assert_eq!(
+            WitnessDB::new(other_root).insert_proof(&proof),
+            Err(ProofError::HashMismatch {
+                expected: other_root,
+                got: keccak256(&proof.account_proof[0]),
+            })
+        );
+    }
+}
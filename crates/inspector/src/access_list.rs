@@ -0,0 +1,280 @@
+use crate::Inspector;
+use revm::{
+    bytecode::opcode,
+    interpreter::{CallInputs, CallOutcome, CreateInputs, CreateOutcome, Interpreter},
+    primitives::{Address, HashSet, B256},
+    specification::eip2930::{AccessList, AccessListItem},
+    transaction::AccessListTrait,
+    wiring::{
+        default::TxEnv,
+        result::{EVMResultGeneric, ExecutionResult},
+        Block, HaltReasonTrait, TransactionType,
+    },
+    Evm, EvmContext, EvmWiring,
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// [Inspector] that builds the access list of a transaction, like the Geth access list tracer.
+///
+/// Every address and storage slot accessed by the transaction is recorded, except the addresses
+/// that are always warm: the caller, the called or created address, the precompiles and the
+/// coinbase. Their slots are still recorded.
+///
+/// The tracer starts from the access list of the transaction, see [create_access_list] to find
+/// the access list that doesn't change when used.
+#[derive(Clone, Debug, Default)]
+pub struct AccessListTracer {
+    access_list: BTreeMap<Address, BTreeSet<B256>>,
+    /// Addresses that are warm without the access list.
+    excluded: HashSet<Address>,
+}
+
+impl AccessListTracer {
+    /// Creates a new tracer starting from the given access list.
+    pub fn new(access_list: &impl AccessListTrait) -> Self {
+        Self {
+            access_list: access_list
+                .iter()
+                .map(|(address, slots)| (address, slots.collect()))
+                .collect(),
+            excluded: HashSet::default(),
+        }
+    }
+
+    /// Returns the access list, ordered by address and slot.
+    pub fn access_list(&self) -> AccessList {
+        AccessList(
+            self.access_list
+                .iter()
+                .map(|(address, slots)| AccessListItem {
+                    address: *address,
+                    storage_keys: slots.iter().copied().collect(),
+                })
+                .collect(),
+        )
+    }
+
+    /// Excludes the addresses that are warm at the start of the transaction.
+    fn start<EvmWiringT: EvmWiring>(
+        &mut self,
+        context: &EvmContext<EvmWiringT>,
+        caller: Address,
+        target: Option<Address>,
+    ) {
+        if context.journaled_state.depth() != 0 {
+            return;
+        }
+        let coinbase = *context.inner.env.block.coinbase();
+        self.excluded = context.precompiles.addresses().copied().collect();
+        self.excluded.extend([caller, coinbase]);
+        self.excluded.extend(target);
+        self.access_list
+            .retain(|address, slots| !slots.is_empty() || !self.excluded.contains(address));
+    }
+
+    fn insert_address(&mut self, address: Address) {
+        if !self.excluded.contains(&address) {
+            self.access_list.entry(address).or_default();
+        }
+    }
+}
+
+impl<EvmWiringT: EvmWiring> Inspector<EvmWiringT> for AccessListTracer {
+    fn step(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<EvmWiringT>) {
+        let stack = interp.stack.data().as_slice();
+        match (interp.current_opcode(), stack) {
+            (opcode::SLOAD | opcode::SSTORE, [.., slot]) => {
+                self.access_list
+                    .entry(interp.contract.target_address)
+                    .or_default()
+                    .insert((*slot).into());
+            }
+            (
+                opcode::EXTCODECOPY
+                | opcode::EXTCODEHASH
+                | opcode::EXTCODESIZE
+                | opcode::BALANCE
+                | opcode::SELFDESTRUCT,
+                [.., address],
+            ) => self.insert_address(Address::from_word((*address).into())),
+            (
+                opcode::CALL | opcode::CALLCODE | opcode::DELEGATECALL | opcode::STATICCALL,
+                [.., address, _],
+            ) => self.insert_address(Address::from_word((*address).into())),
+            _ => {}
+        }
+    }
+
+    fn call(
+        &mut self,
+        context: &mut EvmContext<EvmWiringT>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        self.start(context, inputs.caller, Some(inputs.target_address));
+        None
+    }
+
+    fn create(
+        &mut self,
+        context: &mut EvmContext<EvmWiringT>,
+        inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        // The nonce of the caller is bumped after this hook.
+        let created = context
+            .journaled_state
+            .state
+            .get(&inputs.caller)
+            .map(|account| inputs.created_address(account.info.nonce));
+        self.start(context, inputs.caller, created);
+        None
+    }
+}
+
+/// Access list found by [create_access_list].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AccessListResult<HaltReasonT: HaltReasonTrait> {
+    /// Access list of the transaction.
+    pub access_list: AccessList,
+    /// Result of the transaction with the access list.
+    pub result: ExecutionResult<HaltReasonT>,
+}
+
+impl<HaltReasonT: HaltReasonTrait> AccessListResult<HaltReasonT> {
+    /// Returns the gas used by the transaction with the access list.
+    pub fn gas_used(&self) -> u64 {
+        self.result.gas_used()
+    }
+}
+
+/// Finds the access list of the transaction of the [Evm], like `eth_createAccessList`.
+///
+/// The transaction is executed with the access list found by the previous execution, starting
+/// from its own access list, until the access list doesn't change. The transaction is left with
+/// the found access list, and a legacy transaction is turned into an EIP-2930 transaction as it
+/// can't have an access list. The state changes are not committed.
+///
+/// The [Evm] must be built with the [inspector_handle_register](crate::inspector_handle_register).
+pub fn create_access_list<EvmWiringT>(
+    evm: &mut Evm<'_, EvmWiringT>,
+) -> EVMResultGeneric<AccessListResult<EvmWiringT::HaltReason>, EvmWiringT>
+where
+    EvmWiringT: EvmWiring<ExternalContext = AccessListTracer, Transaction = TxEnv>,
+{
+    let tx = evm.tx_mut();
+    if tx.tx_type == TransactionType::Legacy {
+        tx.tx_type = TransactionType::Eip2930;
+    }
+    loop {
+        let access_list = evm.tx().access_list.clone();
+        evm.context.external = AccessListTracer::new(&access_list);
+        let result = evm.transact()?.result;
+        let found = evm.context.external.access_list();
+        if found == access_list {
+            return Ok(AccessListResult {
+                access_list,
+                result,
+            });
+        }
+        evm.tx_mut().access_list = found;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inspector_handle_register;
+    use database::InMemoryDB;
+    use revm::{
+        bytecode::opcode::*,
+        primitives::{address, U256},
+        state::AccountInfo,
+        test_utils::{account, CALLER, CALLER_A},
+        wiring::EthereumWiring,
+    };
+
+    const STORER: Address = address!("00000000000000000000000000000000000000bb");
+    const COINBASE: Address = address!("00000000000000000000000000000000000000cc");
+    const ACCOUNT: Address = address!("00000000000000000000000000000000000000ee");
+    const IDENTITY: Address = address!("0000000000000000000000000000000000000004");
+
+    fn balance(code: &mut Vec<u8>, address: Address) {
+        code.push(PUSH20);
+        code.extend_from_slice(address.as_slice());
+        code.extend([BALANCE, POP]);
+    }
+
+    fn call(code: &mut Vec<u8>, target: Address) {
+        code.extend([PUSH1, 0, PUSH1, 0, PUSH1, 0, PUSH1, 0, PUSH1, 0, PUSH20]);
+        code.extend_from_slice(target.as_slice());
+        code.extend([GAS, CALL, POP]);
+    }
+
+    #[test]
+    fn create_access_list() {
+        // Loads slot 1, reads balances and calls the storer and a precompile.
+        let mut caller_a = vec![PUSH1, 1, SLOAD, POP];
+        balance(&mut caller_a, ACCOUNT);
+        balance(&mut caller_a, CALLER);
+        balance(&mut caller_a, COINBASE);
+        call(&mut caller_a, STORER);
+        call(&mut caller_a, IDENTITY);
+        caller_a.push(STOP);
+
+        // Stores in slot 0.
+        let storer = vec![PUSH1, 7, PUSH1, 0, SSTORE, STOP];
+
+        let mut evm = Evm::<EthereumWiring<InMemoryDB, AccessListTracer>>::builder()
+            .with_default_db()
+            .with_external_context(AccessListTracer::default())
+            .modify_db(|db| {
+                db.insert_account_info(
+                    CALLER,
+                    AccountInfo::from_balance(U256::from(10u64.pow(18))),
+                );
+                db.insert_account_info(CALLER_A, account(caller_a));
+                db.insert_account_info(STORER, account(storer));
+            })
+            .modify_block_env(|block| block.coinbase = COINBASE)
+            .modify_tx_env(|tx| {
+                tx.caller = CALLER;
+                tx.transact_to = CALLER_A.into();
+                tx.gas_limit = 1_000_000;
+                // Warm anyway, so dropped.
+                tx.access_list = AccessList(vec![AccessListItem {
+                    address: CALLER,
+                    storage_keys: Vec::new(),
+                }]);
+            })
+            .append_handler_register(inspector_handle_register)
+            .build();
+        let without = evm.transact().unwrap().result.gas_used();
+
+        let found = super::create_access_list(&mut evm).unwrap();
+        assert!(found.result.is_success());
+        assert_eq!(
+            found.access_list,
+            AccessList(vec![
+                AccessListItem {
+                    address: CALLER_A,
+                    storage_keys: vec![B256::with_last_byte(1)],
+                },
+                AccessListItem {
+                    address: STORER,
+                    storage_keys: vec![B256::ZERO],
+                },
+                AccessListItem {
+                    address: ACCOUNT,
+                    storage_keys: Vec::new(),
+                },
+            ])
+        );
+        // The accounts and the loaded slot save 100 gas each and the stored slot 200, but the
+        // called account is warm anyway.
+        assert_eq!(found.gas_used(), without + 2400 - 3 * 100 - 200);
+
+        // The transaction is left with the access list.
+        assert_eq!(evm.tx().tx_type, TransactionType::Eip2930);
+        assert_eq!(evm.tx().access_list, found.access_list);
+        assert_eq!(evm.transact().unwrap().result.gas_used(), found.gas_used());
+    }
+}
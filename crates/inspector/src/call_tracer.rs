@@ -0,0 +1,560 @@
+use crate::{
+    util::{error_message, transaction_gas_used},
+    Inspector,
+};
+use revm::{
+    interpreter::{
+        CallInputs, CallOutcome, CallScheme, CreateInputs, CreateOutcome, EOFCreateInputs,
+        Interpreter, InterpreterResult,
+    },
+    primitives::{decode_revert_reason, Address, Bytes, Log, B256, U256},
+    wiring::{default::CreateScheme, Transaction},
+    EvmContext, EvmWiring,
+};
+use std::{string::String, vec::Vec};
+
+/// Configuration of the [CallTracer], the `tracerConfig` of the Geth `callTracer`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase", default))]
+pub struct CallTracerConfig {
+    /// Trace only the top-level call.
+    pub only_top_call: bool,
+    /// Include the logs emitted by the calls.
+    pub with_log: bool,
+}
+
+/// Call of a [CallTracer] trace, serialized as a Geth `callTracer` frame.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct CallFrame {
+    /// Kind of the call: `CALL`, `STATICCALL`, `DELEGATECALL`, `CALLCODE`, `CREATE`,
+    /// `CREATE2` or `SELFDESTRUCT`.
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
+    pub typ: String,
+    /// Caller of the call.
+    pub from: Address,
+    /// Called or created address, `None` if the creation fails.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub to: Option<Address>,
+    /// Value of the call, `None` for static calls.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub value: Option<U256>,
+    /// Gas available to the call, the gas limit of the transaction for the top-level call.
+    #[cfg_attr(feature = "serde", serde(with = "quantity"))]
+    pub gas: u64,
+    /// Gas used by the call, the gas used by the transaction for the top-level call.
+    #[cfg_attr(feature = "serde", serde(with = "quantity"))]
+    pub gas_used: u64,
+    /// Call data or init code.
+    pub input: Bytes,
+    /// Returned data or created code, empty if the call failed without reverting.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "<[u8]>::is_empty")
+    )]
+    pub output: Bytes,
+    /// Error of a failed call.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub error: Option<String>,
+    /// Decoded `Error(string)` or `Panic(uint256)` revert reason of a reverted call.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub revert_reason: Option<String>,
+    /// Calls made by the call.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub calls: Vec<CallFrame>,
+    /// Logs emitted by the call, see [CallTracerConfig::with_log].
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub logs: Vec<CallLog>,
+}
+
+/// Log of a [CallFrame].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CallLog {
+    /// Address of the contract that emitted the log.
+    pub address: Address,
+    /// Topics of the log.
+    pub topics: Vec<B256>,
+    /// Data of the log.
+    pub data: Bytes,
+    /// Number of calls made by the frame before the log was emitted.
+    #[cfg_attr(feature = "serde", serde(with = "quantity"))]
+    pub position: u64,
+}
+
+/// [Inspector] that builds the call tree of a transaction, like the Geth `callTracer`.
+///
+/// The tracer is meant for a single transaction, the trace is taken with
+/// [CallTracer::into_frame].
+#[derive(Clone, Debug, Default)]
+pub struct CallTracer {
+    config: CallTracerConfig,
+    /// Calls that have not ended, starting with the top-level call.
+    stack: Vec<CallFrame>,
+    /// The ended top-level call.
+    root: Option<CallFrame>,
+}
+
+impl CallTracer {
+    /// Creates a new tracer with the given configuration.
+    pub fn new(config: CallTracerConfig) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the configuration of the tracer.
+    pub fn config(&self) -> &CallTracerConfig {
+        &self.config
+    }
+
+    /// Returns the top-level call if it has ended.
+    pub fn frame(&self) -> Option<&CallFrame> {
+        self.root.as_ref()
+    }
+
+    /// Consumes the tracer, returning the top-level call if it has ended.
+    pub fn into_frame(self) -> Option<CallFrame> {
+        self.root
+    }
+
+    fn start<EvmWiringT: EvmWiring>(
+        &mut self,
+        context: &mut EvmContext<EvmWiringT>,
+        mut frame: CallFrame,
+    ) {
+        if self.stack.is_empty() {
+            self.root = None;
+            frame.gas = context.inner.env().tx.common_fields().gas_limit();
+        }
+        self.stack.push(frame);
+    }
+
+    /// Sets the created address, which is left unset if the creation fails, like Geth.
+    fn set_created(&mut self, outcome: &CreateOutcome) {
+        if let Some(frame) = self.stack.last_mut() {
+            frame.to = outcome.address.filter(|_| outcome.result.is_ok());
+        }
+    }
+
+    fn end<EvmWiringT: EvmWiring>(
+        &mut self,
+        context: &EvmContext<EvmWiringT>,
+        result: &InterpreterResult,
+        gas_limit: u64,
+    ) {
+        let Some(mut frame) = self.stack.pop() else {
+            return;
+        };
+        frame.gas_used = if self.stack.is_empty() {
+            transaction_gas_used(context.spec_id(), frame.gas, result)
+        } else if result.result.is_error() {
+            gas_limit
+        } else {
+            gas_limit - result.gas.remaining()
+        };
+
+        if result.is_ok() {
+            frame.output = result.output.clone();
+        } else {
+            if result.result.is_revert() {
+                frame.output = result.output.clone();
+                frame.revert_reason = decode_revert_reason(&result.output);
+            }
+            frame.error = Some(error_message(result.result));
+            // Logs of failed calls are reverted.
+            clear_logs(&mut frame);
+        }
+
+        match self.stack.last_mut() {
+            Some(parent) => {
+                if !self.config.only_top_call {
+                    parent.calls.push(frame);
+                }
+            }
+            None => self.root = Some(frame),
+        }
+    }
+}
+
+impl<EvmWiringT: EvmWiring> Inspector<EvmWiringT> for CallTracer {
+    fn log(&mut self, _interp: &mut Interpreter, _context: &mut EvmContext<EvmWiringT>, log: &Log) {
+        if !self.config.with_log || (self.config.only_top_call && self.stack.len() > 1) {
+            return;
+        }
+        if let Some(frame) = self.stack.last_mut() {
+            frame.logs.push(CallLog {
+                address: log.address,
+                topics: log.topics().to_vec(),
+                data: log.data.data.clone(),
+                position: frame.calls.len() as u64,
+            });
+        }
+    }
+
+    fn call(
+        &mut self,
+        context: &mut EvmContext<EvmWiringT>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        let typ = match inputs.scheme {
+            CallScheme::Call | CallScheme::ExtCall => "CALL",
+            CallScheme::CallCode => "CALLCODE",
+            CallScheme::DelegateCall | CallScheme::ExtDelegateCall => "DELEGATECALL",
+            CallScheme::StaticCall | CallScheme::ExtStaticCall => "STATICCALL",
+        };
+        let frame = CallFrame {
+            typ: typ.into(),
+            from: inputs.caller,
+            to: Some(inputs.target_address),
+            value: (!inputs.is_static).then(|| inputs.call_value()),
+            gas: inputs.gas_limit,
+            input: inputs.input.clone(),
+            ..Default::default()
+        };
+        self.start(context, frame);
+        None
+    }
+
+    fn call_end(
+        &mut self,
+        context: &mut EvmContext<EvmWiringT>,
+        inputs: &CallInputs,
+        outcome: CallOutcome,
+    ) -> CallOutcome {
+        self.end(context, &outcome.result, inputs.gas_limit);
+        outcome
+    }
+
+    fn create(
+        &mut self,
+        context: &mut EvmContext<EvmWiringT>,
+        inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        let typ = match inputs.scheme {
+            CreateScheme::Create => "CREATE",
+            CreateScheme::Create2 { .. } => "CREATE2",
+        };
+        // The nonce of the caller is bumped after this hook.
+        let to = context
+            .journaled_state
+            .state
+            .get(&inputs.caller)
+            .map(|account| inputs.created_address(account.info.nonce));
+        let frame = CallFrame {
+            typ: typ.into(),
+            from: inputs.caller,
+            to,
+            value: Some(inputs.value),
+            gas: inputs.gas_limit,
+            input: inputs.init_code.clone(),
+            ..Default::default()
+        };
+        self.start(context, frame);
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        context: &mut EvmContext<EvmWiringT>,
+        inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        self.set_created(&outcome);
+        self.end(context, &outcome.result, inputs.gas_limit);
+        outcome
+    }
+
+    fn eofcreate(
+        &mut self,
+        context: &mut EvmContext<EvmWiringT>,
+        inputs: &mut EOFCreateInputs,
+    ) -> Option<CreateOutcome> {
+        let frame = CallFrame {
+            typ: "CREATE".into(),
+            from: inputs.caller,
+            to: inputs.kind.created_address().copied(),
+            value: Some(inputs.value),
+            gas: inputs.gas_limit,
+            ..Default::default()
+        };
+        self.start(context, frame);
+        None
+    }
+
+    fn eofcreate_end(
+        &mut self,
+        context: &mut EvmContext<EvmWiringT>,
+        inputs: &EOFCreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        self.set_created(&outcome);
+        self.end(context, &outcome.result, inputs.gas_limit);
+        outcome
+    }
+
+    fn selfdestruct(&mut self, contract: Address, target: Address, value: U256) {
+        if self.config.only_top_call {
+            return;
+        }
+        if let Some(frame) = self.stack.last_mut() {
+            frame.calls.push(CallFrame {
+                typ: "SELFDESTRUCT".into(),
+                from: contract,
+                to: Some(target),
+                value: Some(value),
+                ..Default::default()
+            });
+        }
+    }
+}
+
+fn clear_logs(frame: &mut CallFrame) {
+    frame.logs.clear();
+    frame.calls.iter_mut().for_each(clear_logs);
+}
+
+/// Serializes `u64` as a hex quantity, as Geth does.
+#[cfg(feature = "serde")]
+mod quantity {
+    use core::fmt;
+    use serde::{de, Deserializer, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&format_args!("{value:#x}"))
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        struct QuantityVisitor;
+
+        impl de::Visitor<'_> for QuantityVisitor {
+            type Value = u64;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a hex quantity")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<u64, E> {
+                let digits = value
+                    .strip_prefix("0x")
+                    .ok_or_else(|| E::custom("missing 0x prefix"))?;
+                u64::from_str_radix(digits, 16).map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(QuantityVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inspector_handle_register;
+    use database::InMemoryDB;
+    use revm::{
+        bytecode::opcode::*,
+        primitives::{address, bytes},
+        state::AccountInfo,
+        test_utils::{self, CALLER, CALLER_A},
+        wiring::{
+            result::{ExecutionResult, HaltReason},
+            EthereumWiring,
+        },
+        Evm,
+    };
+
+    const REVERTER: Address = address!("00000000000000000000000000000000000000bb");
+    const DESTRUCTED: Address = address!("00000000000000000000000000000000000000cc");
+    const BENEFICIARY: Address = address!("00000000000000000000000000000000000000dd");
+
+    fn call(target: Address, value: u8) -> Vec<u8> {
+        let mut code = vec![PUSH1, 0, PUSH1, 0, PUSH1, 0, PUSH1, 0, PUSH1, value, PUSH20];
+        code.extend_from_slice(target.as_slice());
+        code.extend([GAS, CALL, POP]);
+        code
+    }
+
+    fn account(code: Vec<u8>) -> AccountInfo {
+        AccountInfo {
+            balance: U256::from(10),
+            nonce: 1,
+            ..test_utils::account(code)
+        }
+    }
+
+    fn trace(
+        config: CallTracerConfig,
+        to: Option<Address>,
+        data: Bytes,
+    ) -> (ExecutionResult<HaltReason>, CallFrame) {
+        // Logs, calls the reverter with value, then the destructed contract.
+        let mut caller_a = vec![PUSH1, 0, PUSH1, 0, LOG0];
+        caller_a.extend(call(REVERTER, 1));
+        caller_a.extend(call(DESTRUCTED, 0));
+        caller_a.push(STOP);
+
+        // Logs and reverts with `Error("no")`.
+        let mut reverter = vec![PUSH1, 0, PUSH1, 0, LOG0];
+        reverter.extend(test_utils::revert_with_no());
+
+        let mut destructed = vec![PUSH20];
+        destructed.extend_from_slice(BENEFICIARY.as_slice());
+        destructed.push(SELFDESTRUCT);
+
+        let mut evm = Evm::<EthereumWiring<InMemoryDB, CallTracer>>::builder()
+            .with_default_db()
+            .with_external_context(CallTracer::new(config))
+            .modify_db(|db| {
+                db.insert_account_info(
+                    CALLER,
+                    AccountInfo::from_balance(U256::from(10u64.pow(18))),
+                );
+                db.insert_account_info(CALLER_A, account(caller_a));
+                db.insert_account_info(REVERTER, account(reverter));
+                db.insert_account_info(DESTRUCTED, account(destructed));
+            })
+            .modify_tx_env(|tx| {
+                tx.caller = CALLER;
+                tx.transact_to = to.into();
+                tx.data = data;
+                tx.gas_limit = 1_000_000;
+            })
+            .append_handler_register(inspector_handle_register)
+            .build();
+        let result = evm.transact().unwrap().result;
+        let frame = evm.into_context().external.into_frame().unwrap();
+        (result, frame)
+    }
+
+    #[test]
+    fn call_tree() {
+        let config = CallTracerConfig {
+            only_top_call: false,
+            with_log: true,
+        };
+        let (result, frame) = trace(config, Some(CALLER_A), bytes!("01"));
+        assert!(result.is_success());
+
+        assert_eq!(frame.typ, "CALL");
+        assert_eq!((frame.from, frame.to), (CALLER, Some(CALLER_A)));
+        assert_eq!(frame.value, Some(U256::ZERO));
+        assert_eq!(frame.gas, 1_000_000);
+        assert_eq!(frame.gas_used, result.gas_used());
+        assert_eq!(frame.input, bytes!("01"));
+        assert_eq!(frame.error, None);
+        assert_eq!(frame.logs.len(), 1);
+        assert_eq!(frame.logs[0].address, CALLER_A);
+        assert_eq!(frame.logs[0].position, 0);
+        assert_eq!(frame.calls.len(), 2);
+
+        let reverted = &frame.calls[0];
+        assert_eq!(reverted.typ, "CALL");
+        assert_eq!((reverted.from, reverted.to), (CALLER_A, Some(REVERTER)));
+        assert_eq!(reverted.value, Some(U256::from(1)));
+        assert!(reverted.gas_used > 0 && reverted.gas_used < reverted.gas);
+        assert_eq!(reverted.output.len(), 100);
+        assert_eq!(reverted.error.as_deref(), Some("execution reverted"));
+        assert_eq!(reverted.revert_reason.as_deref(), Some("no"));
+        // The log of the reverted call is dropped.
+        assert!(reverted.logs.is_empty());
+
+        let destructed = &frame.calls[1];
+        assert_eq!(destructed.to, Some(DESTRUCTED));
+        assert_eq!(
+            destructed.calls,
+            vec![CallFrame {
+                typ: "SELFDESTRUCT".into(),
+                from: DESTRUCTED,
+                to: Some(BENEFICIARY),
+                value: Some(U256::from(10)),
+                ..Default::default()
+            }]
+        );
+    }
+
+    #[test]
+    fn only_top_call() {
+        // Logs are only included with `with_log`.
+        let (_, frame) = trace(CallTracerConfig::default(), Some(CALLER_A), Bytes::new());
+        assert_eq!(frame.calls.len(), 2);
+        assert!(frame.logs.is_empty());
+
+        let config = CallTracerConfig {
+            only_top_call: true,
+            with_log: true,
+        };
+        let (_, frame) = trace(config, Some(CALLER_A), Bytes::new());
+        assert!(frame.calls.is_empty());
+        assert_eq!(frame.logs.len(), 1);
+    }
+
+    #[test]
+    fn create() {
+        // Returns the one byte code `STOP`.
+        let init_code = Bytes::from([PUSH1, 1, PUSH1, 0, RETURN]);
+        let (result, frame) = trace(CallTracerConfig::default(), None, init_code.clone());
+        let ExecutionResult::Success { output, .. } = &result else {
+            panic!("expected success: {result:?}");
+        };
+
+        assert_eq!(frame.typ, "CREATE");
+        assert_eq!(frame.to, output.address().copied());
+        assert_eq!(frame.to, Some(CALLER.create(0)));
+        assert_eq!(frame.input, init_code);
+        assert_eq!(frame.output, bytes!("00"));
+        assert_eq!(frame.gas_used, result.gas_used());
+
+        // The created address of a reverted creation is unset.
+        let init_code = Bytes::from([PUSH1, 0, PUSH1, 0, REVERT]);
+        let (result, frame) = trace(CallTracerConfig::default(), None, init_code);
+        assert!(matches!(result, ExecutionResult::Revert { .. }));
+        assert_eq!(frame.typ, "CREATE");
+        assert_eq!(frame.to, None);
+        assert_eq!(frame.error.as_deref(), Some("execution reverted"));
+    }
+
+    #[cfg(feature = "serde-json")]
+    #[test]
+    fn geth_json() {
+        let config: CallTracerConfig =
+            serde_json::from_str(r#"{"onlyTopCall":false,"withLog":true}"#).unwrap();
+        let (_, frame) = trace(config, Some(CALLER_A), Bytes::new());
+        let json = serde_json::to_value(&frame).unwrap();
+
+        assert_eq!(json["type"], "CALL");
+        assert_eq!(json["from"], "0x1000000000000000000000000000000000000000");
+        assert_eq!(json["gas"], "0xf4240");
+        assert_eq!(json["value"], "0x0");
+        assert_eq!(json["input"], "0x");
+        assert!(json.get("output").is_none());
+        assert!(json.get("error").is_none());
+        assert_eq!(json["logs"][0]["position"], "0x0");
+        assert_eq!(json["calls"][0]["revertReason"], "no");
+        assert_eq!(json["calls"][1]["calls"][0]["type"], "SELFDESTRUCT");
+        assert_eq!(json["calls"][1]["calls"][0]["gasUsed"], "0x0");
+
+        let decoded: CallFrame = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded, frame);
+    }
+}
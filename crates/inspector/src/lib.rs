@@ -5,6 +5,8 @@
 #[cfg(not(feature = "std"))]
 extern crate alloc as std;
 
+mod access_list;
+mod call_tracer;
 #[cfg(feature = "std")]
 mod customprinter;
 #[cfg(all(feature = "std", feature = "serde-json"))]
@@ -13,16 +15,26 @@ mod gas;
 mod handler_register;
 mod inspector;
 mod noop;
+mod prestate;
+mod struct_logger;
+mod util;
 
+pub use access_list::{create_access_list, AccessListResult};
 pub use handler_register::{inspector_handle_register, GetInspector};
 pub use inspector::Inspector;
 
 /// [Inspector] implementations.
 pub mod inspectors {
+    pub use super::access_list::AccessListTracer;
+    pub use super::call_tracer::{CallFrame, CallLog, CallTracer, CallTracerConfig};
     #[cfg(feature = "std")]
     pub use super::customprinter::CustomPrintTracer;
     #[cfg(all(feature = "std", feature = "serde-json"))]
     pub use super::eip3155::TracerEip3155;
     pub use super::gas::GasInspector;
     pub use super::noop::NoOpInspector;
+    pub use super::prestate::{
+        PrestateAccount, PrestateDiff, PrestateFrame, PrestateTracer, PrestateTracerConfig,
+    };
+    pub use super::struct_logger::{StructLog, StructLogResult, StructLogger, StructLoggerConfig};
 }
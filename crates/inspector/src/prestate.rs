@@ -0,0 +1,573 @@
+use crate::Inspector;
+use revm::{
+    bytecode::opcode,
+    interpreter::{
+        CallInputs, CallOutcome, CreateInputs, CreateOutcome, EOFCreateInputs, Interpreter,
+    },
+    primitives::{Address, Bytes, B256, KECCAK_EMPTY, U256},
+    state::EvmState,
+    wiring::{Block, Transaction},
+    Database, EvmContext, EvmWiring,
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+#[cfg(feature = "serde")]
+use crate::util::is_zero;
+
+/// Configuration of the [PrestateTracer], the `tracerConfig` of the Geth `prestateTracer`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase", default))]
+pub struct PrestateTracerConfig {
+    /// Return the changed accounts before and after the transaction, instead of the state
+    /// read by the transaction.
+    pub diff_mode: bool,
+    /// Don't include the code of the accounts.
+    pub disable_code: bool,
+    /// Don't include the storage of the accounts.
+    pub disable_storage: bool,
+}
+
+/// State of an account in a [PrestateFrame].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PrestateAccount {
+    /// Balance, `None` in the post state if it didn't change.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub balance: Option<U256>,
+    /// Nonce, zero in the post state if it didn't change.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "is_zero"))]
+    pub nonce: u64,
+    /// Code, empty in the post state if it didn't change.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "<[u8]>::is_empty")
+    )]
+    pub code: Bytes,
+    /// Storage slots, only the changed slots in the post state.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "BTreeMap::is_empty")
+    )]
+    pub storage: BTreeMap<B256, B256>,
+}
+
+/// Changed accounts before and after the transaction.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PrestateDiff {
+    /// Changed accounts before the transaction.
+    pub pre: BTreeMap<Address, PrestateAccount>,
+    /// Changed fields of the accounts after the transaction.
+    pub post: BTreeMap<Address, PrestateAccount>,
+}
+
+/// Trace of a [PrestateTracer], serialized as the Geth `prestateTracer` result.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(untagged))]
+pub enum PrestateFrame {
+    /// The changed accounts, see [PrestateTracerConfig::diff_mode].
+    Diff(PrestateDiff),
+    /// Accounts read by the transaction, before the transaction.
+    Default(BTreeMap<Address, PrestateAccount>),
+}
+
+/// [Inspector] that records the state read by a transaction, like the Geth `prestateTracer`.
+///
+/// Accounts are recorded when they are first touched, so the recorded values are the ones before
+/// the transaction. They are read from the journaled state, and from the database if they are not
+/// loaded yet or were changed before the execution, like the caller. The trace is taken with
+/// [PrestateTracer::into_frame].
+#[derive(Clone, Debug, Default)]
+pub struct PrestateTracer {
+    config: PrestateTracerConfig,
+    /// Accounts before the transaction.
+    pre: BTreeMap<Address, PrestateAccount>,
+    /// Accounts that didn't exist before the transaction.
+    missing: BTreeSet<Address>,
+}
+
+impl PrestateTracer {
+    /// Creates a new tracer with the given configuration.
+    pub fn new(config: PrestateTracerConfig) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the configuration of the tracer.
+    pub fn config(&self) -> &PrestateTracerConfig {
+        &self.config
+    }
+
+    /// Consumes the tracer, returning the trace.
+    ///
+    /// `state` is the state changed by the transaction, it is only used in diff mode.
+    pub fn into_frame(self, state: &EvmState) -> PrestateFrame {
+        if !self.config.diff_mode {
+            return PrestateFrame::Default(self.pre);
+        }
+
+        let Self {
+            config,
+            mut pre,
+            missing,
+        } = self;
+        let mut post = BTreeMap::new();
+        pre.retain(|address, account| {
+            let Some(new) = state.get(address) else {
+                return false;
+            };
+            // Removed accounts are only in the pre state.
+            if new.is_selfdestructed() {
+                return true;
+            }
+
+            let mut changed = PrestateAccount::default();
+            if Some(new.info.balance) != account.balance {
+                changed.balance = Some(new.info.balance);
+            }
+            if new.info.nonce != account.nonce {
+                changed.nonce = new.info.nonce;
+            }
+            if !config.disable_code {
+                let code = match &new.info.code {
+                    Some(code) => code.original_bytes(),
+                    None if new.info.code_hash == KECCAK_EMPTY => Bytes::new(),
+                    None => account.code.clone(),
+                };
+                if code != account.code {
+                    changed.code = code;
+                }
+            }
+            let mut storage_changed = false;
+            account.storage.retain(|slot, value| {
+                let new_value = match new.storage.get(&U256::from_be_bytes(slot.0)) {
+                    Some(new_value) => new_value.present_value.into(),
+                    // Storage of created accounts starts empty.
+                    None if new.is_created() => B256::ZERO,
+                    None => *value,
+                };
+                if new_value == *value {
+                    return false;
+                }
+                storage_changed = true;
+                if !new_value.is_zero() {
+                    changed.storage.insert(*slot, new_value);
+                }
+                !value.is_zero()
+            });
+
+            let is_changed = changed != PrestateAccount::default() || storage_changed;
+            if is_changed {
+                post.insert(*address, changed);
+            }
+            is_changed && !missing.contains(address)
+        });
+        PrestateFrame::Diff(PrestateDiff { pre, post })
+    }
+
+    /// Records the account if it is touched for the first time.
+    fn lookup_account<EvmWiringT: EvmWiring>(
+        &mut self,
+        context: &mut EvmContext<EvmWiringT>,
+        address: Address,
+    ) {
+        if self.pre.contains_key(&address) {
+            return;
+        }
+        // Untouched accounts of the journal still have their value before the transaction.
+        let loaded = context
+            .journaled_state
+            .state
+            .get(&address)
+            .filter(|account| !account.is_touched())
+            .map(|account| (!account.is_loaded_as_not_existing()).then(|| account.info.clone()));
+        let db = &mut context.inner.db;
+        let info = match loaded.map_or_else(|| db.basic(address), Ok) {
+            Ok(Some(info)) => info,
+            Ok(None) => {
+                self.missing.insert(address);
+                Default::default()
+            }
+            Err(error) => {
+                context.inner.error = Err(error);
+                return;
+            }
+        };
+        let code = match info.code {
+            _ if self.config.disable_code => Bytes::new(),
+            Some(code) => code.original_bytes(),
+            None if info.code_hash == KECCAK_EMPTY => Bytes::new(),
+            None => match db.code_by_hash(info.code_hash) {
+                Ok(code) => code.original_bytes(),
+                Err(error) => {
+                    context.inner.error = Err(error);
+                    return;
+                }
+            },
+        };
+        self.pre.insert(
+            address,
+            PrestateAccount {
+                balance: Some(info.balance),
+                nonce: info.nonce,
+                code,
+                storage: BTreeMap::new(),
+            },
+        );
+    }
+
+    /// Records the account and the storage slot if they are touched for the first time.
+    fn lookup_storage<EvmWiringT: EvmWiring>(
+        &mut self,
+        context: &mut EvmContext<EvmWiringT>,
+        address: Address,
+        slot: U256,
+    ) {
+        self.lookup_account(context, address);
+        if self.config.disable_storage {
+            return;
+        }
+        let Some(account) = self.pre.get_mut(&address) else {
+            return;
+        };
+        let key = B256::from(slot);
+        if account.storage.contains_key(&key) {
+            return;
+        }
+        // Loaded slots keep the value they had before the transaction.
+        let loaded = context
+            .journaled_state
+            .state
+            .get(&address)
+            .and_then(|account| account.storage.get(&slot))
+            .map(|slot| slot.original_value);
+        let value = match loaded {
+            Some(value) => value,
+            None => match context.inner.db.storage(address, slot) {
+                Ok(value) => value,
+                Err(error) => {
+                    context.inner.error = Err(error);
+                    return;
+                }
+            },
+        };
+        account.storage.insert(key, value.into());
+    }
+
+    /// Records the sender and the coinbase when the transaction starts.
+    fn lookup_transaction<EvmWiringT: EvmWiring>(&mut self, context: &mut EvmContext<EvmWiringT>) {
+        if context.journaled_state.depth() != 0 {
+            return;
+        }
+        let caller = context.inner.env().tx.common_fields().caller();
+        let coinbase = *context.inner.env().block.coinbase();
+        self.lookup_account(context, caller);
+        self.lookup_account(context, coinbase);
+    }
+}
+
+impl<EvmWiringT: EvmWiring> Inspector<EvmWiringT> for PrestateTracer {
+    fn step(&mut self, interp: &mut Interpreter, context: &mut EvmContext<EvmWiringT>) {
+        let stack_address = || {
+            interp
+                .stack
+                .peek(0)
+                .ok()
+                .map(|word| Address::from_word(word.into()))
+        };
+        match interp.current_opcode() {
+            opcode::SLOAD | opcode::SSTORE => {
+                if let Ok(slot) = interp.stack.peek(0) {
+                    self.lookup_storage(context, interp.contract.target_address, slot);
+                }
+            }
+            opcode::BALANCE
+            | opcode::EXTCODESIZE
+            | opcode::EXTCODECOPY
+            | opcode::EXTCODEHASH
+            | opcode::SELFDESTRUCT => {
+                if let Some(address) = stack_address() {
+                    self.lookup_account(context, address);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn call(
+        &mut self,
+        context: &mut EvmContext<EvmWiringT>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        self.lookup_transaction(context);
+        self.lookup_account(context, inputs.caller);
+        self.lookup_account(context, inputs.target_address);
+        self.lookup_account(context, inputs.bytecode_address);
+        None
+    }
+
+    fn create(
+        &mut self,
+        context: &mut EvmContext<EvmWiringT>,
+        inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.lookup_transaction(context);
+        self.lookup_account(context, inputs.caller);
+        // The nonce of the caller is bumped after this hook.
+        let nonce = context
+            .journaled_state
+            .state
+            .get(&inputs.caller)
+            .map(|account| account.info.nonce);
+        if let Some(nonce) = nonce {
+            self.lookup_account(context, inputs.created_address(nonce));
+        }
+        None
+    }
+
+    fn eofcreate(
+        &mut self,
+        context: &mut EvmContext<EvmWiringT>,
+        inputs: &mut EOFCreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.lookup_transaction(context);
+        self.lookup_account(context, inputs.caller);
+        if let Some(address) = inputs.kind.created_address() {
+            self.lookup_account(context, *address);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inspector_handle_register;
+    use database::InMemoryDB;
+    use revm::{
+        bytecode::{opcode::*, Bytecode},
+        primitives::{address, HashMap},
+        specification::eip2930::{AccessList, AccessListItem},
+        state::AccountInfo,
+        test_utils::CALLER,
+        wiring::EthereumWiring,
+        wiring::TransactionType,
+        Evm,
+    };
+
+    const CONTRACT: Address = address!("00000000000000000000000000000000000000aa");
+    const OTHER: Address = address!("00000000000000000000000000000000000000bb");
+    const MISSING: Address = address!("00000000000000000000000000000000000000cc");
+
+    fn code() -> Bytes {
+        // Increments slot 0, clears slot 1 and reads the balance and code size of two accounts.
+        let mut code = vec![PUSH1, 0, SLOAD, PUSH1, 1, ADD, PUSH1, 0, SSTORE];
+        code.extend([PUSH1, 0, PUSH1, 1, SSTORE, PUSH1, 2, SLOAD, POP]);
+        code.push(PUSH20);
+        code.extend_from_slice(OTHER.as_slice());
+        code.extend([BALANCE, POP, PUSH20]);
+        code.extend_from_slice(MISSING.as_slice());
+        code.extend([EXTCODESIZE, POP, STOP]);
+        code.into()
+    }
+
+    /// [InMemoryDB] counting the account reads.
+    #[derive(Default)]
+    struct CountingDB {
+        db: InMemoryDB,
+        reads: HashMap<Address, usize>,
+    }
+
+    impl Database for CountingDB {
+        type Error = <InMemoryDB as Database>::Error;
+
+        fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+            *self.reads.entry(address).or_default() += 1;
+            self.db.basic(address)
+        }
+
+        fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+            self.db.code_by_hash(code_hash)
+        }
+
+        fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+            self.db.storage(address, index)
+        }
+
+        fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+            self.db.block_hash(number)
+        }
+    }
+
+    fn trace(config: PrestateTracerConfig) -> PrestateFrame {
+        trace_with_reads(config).0
+    }
+
+    fn trace_with_reads(config: PrestateTracerConfig) -> (PrestateFrame, HashMap<Address, usize>) {
+        let code = Bytecode::new_raw(code());
+        let mut evm = Evm::<EthereumWiring<CountingDB, PrestateTracer>>::builder()
+            .with_db(CountingDB::default())
+            .with_external_context(PrestateTracer::new(config))
+            .modify_db(|CountingDB { db, .. }| {
+                db.insert_account_info(CALLER, AccountInfo::from_balance(U256::from(100)));
+                db.insert_account_info(
+                    CONTRACT,
+                    AccountInfo {
+                        balance: U256::from(10),
+                        code_hash: code.hash_slow(),
+                        code: Some(code),
+                        nonce: 1,
+                    },
+                );
+                db.insert_account_storage(CONTRACT, U256::ZERO, U256::from(5))
+                    .unwrap();
+                db.insert_account_storage(CONTRACT, U256::from(1), U256::from(7))
+                    .unwrap();
+                db.insert_account_info(OTHER, AccountInfo::from_balance(U256::from(3)));
+            })
+            .modify_tx_env(|tx| {
+                tx.caller = CALLER;
+                tx.transact_to = CONTRACT.into();
+                tx.value = U256::from(1);
+                tx.gas_limit = 100_000;
+                // Loaded by the journal before the execution.
+                tx.tx_type = TransactionType::Eip2930;
+                tx.access_list = AccessList(vec![AccessListItem {
+                    address: OTHER,
+                    storage_keys: Vec::new(),
+                }]);
+            })
+            .append_handler_register(inspector_handle_register)
+            .build();
+        let state = evm.transact().unwrap().state;
+        let context = evm.into_context();
+        let frame = context.external.into_frame(&state);
+        (frame, context.evm.inner.db.reads)
+    }
+
+    fn slot(value: u64) -> B256 {
+        U256::from(value).into()
+    }
+
+    #[test]
+    fn prestate() {
+        let PrestateFrame::Default(pre) = trace(PrestateTracerConfig::default()) else {
+            panic!("expected the default mode");
+        };
+        assert_eq!(
+            pre.keys().copied().collect::<Vec<_>>(),
+            [Address::ZERO, CONTRACT, OTHER, MISSING, CALLER]
+        );
+        assert_eq!(pre[&CALLER].balance, Some(U256::from(100)));
+        assert_eq!(pre[&CALLER].nonce, 0);
+        assert_eq!(pre[&CONTRACT].code, code());
+        assert_eq!(
+            pre[&CONTRACT].storage,
+            BTreeMap::from([(slot(0), slot(5)), (slot(1), slot(7)), (slot(2), slot(0))])
+        );
+        assert_eq!(pre[&OTHER].balance, Some(U256::from(3)));
+        assert_eq!(
+            pre[&MISSING],
+            PrestateAccount {
+                balance: Some(U256::ZERO),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn journaled_accounts() {
+        let (PrestateFrame::Default(pre), reads) = trace_with_reads(Default::default()) else {
+            panic!("expected the default mode");
+        };
+        // The account of the access list is read from the journal.
+        assert_eq!(pre[&OTHER].balance, Some(U256::from(3)));
+        assert_eq!(reads[&OTHER], 1);
+        // The caller is changed before the execution, so it is read from the database.
+        assert_eq!(pre[&CALLER].balance, Some(U256::from(100)));
+        assert_eq!(reads[&CALLER], 2);
+    }
+
+    #[test]
+    fn diff() {
+        let config = PrestateTracerConfig {
+            diff_mode: true,
+            disable_code: true,
+            ..Default::default()
+        };
+        let PrestateFrame::Diff(diff) = trace(config) else {
+            panic!("expected the diff mode");
+        };
+        assert_eq!(
+            diff.pre,
+            BTreeMap::from([
+                (
+                    CALLER,
+                    PrestateAccount {
+                        balance: Some(U256::from(100)),
+                        ..Default::default()
+                    }
+                ),
+                (
+                    CONTRACT,
+                    PrestateAccount {
+                        balance: Some(U256::from(10)),
+                        nonce: 1,
+                        storage: BTreeMap::from([(slot(0), slot(5)), (slot(1), slot(7))]),
+                        ..Default::default()
+                    }
+                ),
+            ])
+        );
+        assert_eq!(
+            diff.post,
+            BTreeMap::from([
+                (
+                    CALLER,
+                    PrestateAccount {
+                        balance: Some(U256::from(99)),
+                        nonce: 1,
+                        ..Default::default()
+                    }
+                ),
+                (
+                    CONTRACT,
+                    PrestateAccount {
+                        balance: Some(U256::from(11)),
+                        // The cleared slot is only in the pre state.
+                        storage: BTreeMap::from([(slot(0), slot(6))]),
+                        ..Default::default()
+                    }
+                ),
+            ])
+        );
+    }
+
+    #[cfg(feature = "serde-json")]
+    #[test]
+    fn geth_json() {
+        let config = serde_json::from_str(r#"{"diffMode":true}"#).unwrap();
+        let json = serde_json::to_value(trace(config)).unwrap();
+        let caller = &json["pre"][&CALLER.to_checksum(None)];
+        assert_eq!(caller["balance"], "0x64");
+        assert!(caller.get("nonce").is_none());
+        let contract = &json["post"][&CONTRACT.to_checksum(None)];
+        assert_eq!(
+            contract["storage"]
+                ["0x0000000000000000000000000000000000000000000000000000000000000000"],
+            "0x0000000000000000000000000000000000000000000000000000000000000006"
+        );
+        assert_eq!(json["pre"][&CONTRACT.to_checksum(None)]["nonce"], 1);
+
+        let json = serde_json::to_value(trace(PrestateTracerConfig::default())).unwrap();
+        assert!(json.get("pre").is_none());
+        assert_eq!(json[&OTHER.to_checksum(None)]["balance"], "0x3");
+    }
+}
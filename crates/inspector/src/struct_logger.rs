@@ -0,0 +1,608 @@
+use crate::{
+    util::{error_message, transaction_gas_used},
+    Inspector,
+};
+use revm::{
+    bytecode::opcode::{self, OpCode},
+    interpreter::{
+        CallInputs, CallOutcome, CreateInputs, CreateOutcome, EOFCreateInputs, InstructionResult,
+        Interpreter, InterpreterResult, STACK_LIMIT,
+    },
+    primitives::{Address, Bytes, HashMap, B256, U256},
+    wiring::Transaction,
+    EvmContext, EvmWiring,
+};
+use std::{collections::BTreeMap, format, string::String, vec::Vec};
+
+#[cfg(feature = "serde")]
+use crate::util::is_zero;
+
+/// Configuration of the [StructLogger], the logger options of the Geth `debug_traceTransaction`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase", default))]
+pub struct StructLoggerConfig {
+    /// Capture the memory of each step.
+    pub enable_memory: bool,
+    /// Don't capture the stack of each step.
+    pub disable_stack: bool,
+    /// Don't capture the storage of the `SLOAD` and `SSTORE` steps.
+    pub disable_storage: bool,
+    /// Capture the return data of the last call of each step.
+    pub enable_return_data: bool,
+    /// Maximum number of steps to capture, `0` for no limit.
+    pub limit: usize,
+}
+
+/// Step of a [StructLogger] trace, serialized as a Geth `structLogs` entry.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct StructLog {
+    /// Program counter.
+    pub pc: u64,
+    /// Name of the opcode.
+    pub op: String,
+    /// Gas left before executing the step.
+    pub gas: u64,
+    /// Gas cost of the step, including the gas given to a call.
+    pub gas_cost: u64,
+    /// Depth of the call, `1` for the top-level call.
+    pub depth: u64,
+    /// Error of a step that failed before executing, running out of gas or stack.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub error: Option<String>,
+    /// Stack before executing the step, from bottom to top.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub stack: Option<Vec<U256>>,
+    /// Return data of the last call made by the frame.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "<[u8]>::is_empty")
+    )]
+    pub return_data: Bytes,
+    /// Memory before executing the step, in words.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none", with = "unprefixed")
+    )]
+    pub memory: Option<Vec<B256>>,
+    /// Storage of the contract read and written so far, only for `SLOAD` and `SSTORE` steps.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none", with = "unprefixed")
+    )]
+    pub storage: Option<BTreeMap<B256, B256>>,
+    /// Refund counter of the transaction before executing the step.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "is_zero"))]
+    pub refund: u64,
+}
+
+/// Trace of a [StructLogger], serialized as the Geth `debug_traceTransaction` default result.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct StructLogResult {
+    /// Gas used by the transaction.
+    pub gas: u64,
+    /// Whether the transaction failed.
+    pub failed: bool,
+    /// Returned data, or the revert data if the transaction reverted.
+    #[cfg_attr(feature = "serde", serde(with = "unprefixed"))]
+    pub return_value: Bytes,
+    /// Executed steps.
+    pub struct_logs: Vec<StructLog>,
+}
+
+/// [Inspector] that logs every step of a transaction, like the Geth struct logger.
+///
+/// The storage of the `SLOAD` and `SSTORE` steps contains the slots of the executing contract
+/// read and written so far in the transaction.
+///
+/// The tracer is meant for a single transaction, the trace is taken with
+/// [StructLogger::into_result].
+#[derive(Clone, Debug, Default)]
+pub struct StructLogger {
+    config: StructLoggerConfig,
+    logs: Vec<StructLog>,
+    /// Slots read and written by each contract.
+    storage: HashMap<Address, BTreeMap<B256, B256>>,
+    /// Refund counter at the start of the calls that have not ended.
+    frames: Vec<i64>,
+    /// Refund counter before the current step.
+    refund: i64,
+    /// Whether the current step is logged.
+    logged: bool,
+    /// Opcode of the current step.
+    op: u8,
+    /// Stack length before the current step.
+    stack_len: usize,
+    /// Slot read by the current `SLOAD` step.
+    sload: Option<B256>,
+    /// The trace, set when the top-level call ends.
+    result: Option<StructLogResult>,
+}
+
+impl StructLogger {
+    /// Creates a new tracer with the given configuration.
+    pub fn new(config: StructLoggerConfig) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the configuration of the tracer.
+    pub fn config(&self) -> &StructLoggerConfig {
+        &self.config
+    }
+
+    /// Returns the trace if the top-level call has ended.
+    pub fn result(&self) -> Option<&StructLogResult> {
+        self.result.as_ref()
+    }
+
+    /// Consumes the tracer, returning the trace if the top-level call has ended.
+    pub fn into_result(self) -> Option<StructLogResult> {
+        self.result
+    }
+
+    fn start(&mut self) {
+        if self.frames.is_empty() {
+            self.logs.clear();
+            self.storage.clear();
+            self.refund = 0;
+            self.logged = false;
+            self.result = None;
+        }
+        self.frames.push(self.refund);
+    }
+
+    /// Removes the gas given to a create from the cost of its step, as Geth charges it while
+    /// executing the step.
+    fn start_create(&mut self, gas_limit: u64) {
+        if !self.frames.is_empty() && self.logged {
+            if let Some(log) = self.logs.last_mut() {
+                log.gas_cost = log.gas_cost.saturating_sub(gas_limit);
+            }
+        }
+        self.start();
+    }
+
+    fn end<EvmWiringT: EvmWiring>(
+        &mut self,
+        context: &mut EvmContext<EvmWiringT>,
+        result: &InterpreterResult,
+    ) {
+        if self.frames.pop().is_none() || !self.frames.is_empty() {
+            return;
+        }
+        let gas_limit = context.inner.env().tx.common_fields().gas_limit();
+        let return_value = if result.is_ok() || result.result.is_revert() {
+            result.output.clone()
+        } else {
+            Bytes::new()
+        };
+        self.result = Some(StructLogResult {
+            gas: transaction_gas_used(context.spec_id(), gas_limit, result),
+            failed: !result.is_ok(),
+            return_value,
+            struct_logs: core::mem::take(&mut self.logs),
+        });
+    }
+}
+
+impl<EvmWiringT: EvmWiring> Inspector<EvmWiringT> for StructLogger {
+    fn step(&mut self, interp: &mut Interpreter, context: &mut EvmContext<EvmWiringT>) {
+        self.refund = self.frames.last().copied().unwrap_or_default() + interp.gas.refunded();
+        self.logged = self.config.limit == 0 || self.logs.len() < self.config.limit;
+        self.sload = None;
+        if !self.logged {
+            return;
+        }
+
+        let op = interp.current_opcode();
+        let stack = interp.stack.data();
+        self.op = op;
+        self.stack_len = stack.len();
+        let storage = (!self.config.disable_storage)
+            .then(|| {
+                let address = interp.contract.target_address;
+                match (op, stack.as_slice()) {
+                    (opcode::SLOAD, [.., slot]) => {
+                        self.sload = Some((*slot).into());
+                        Some(self.storage.entry(address).or_default().clone())
+                    }
+                    (opcode::SSTORE, [.., value, slot]) => {
+                        let storage = self.storage.entry(address).or_default();
+                        storage.insert((*slot).into(), (*value).into());
+                        Some(storage.clone())
+                    }
+                    _ => None,
+                }
+            })
+            .flatten();
+        let memory = self.config.enable_memory.then(|| {
+            interp
+                .shared_memory
+                .context_memory()
+                .chunks_exact(32)
+                .map(B256::from_slice)
+                .collect()
+        });
+        let return_data = if self.config.enable_return_data {
+            interp.return_data_buffer.clone()
+        } else {
+            Bytes::new()
+        };
+
+        self.logs.push(StructLog {
+            pc: interp.program_counter() as u64,
+            op: match OpCode::new(op) {
+                Some(op) => op.as_str().into(),
+                None => format!("opcode {op:#x} not defined"),
+            },
+            gas: interp.gas.remaining(),
+            gas_cost: 0,
+            depth: context.journaled_state.depth(),
+            error: None,
+            stack: (!self.config.disable_stack).then(|| stack.clone()),
+            return_data,
+            memory,
+            storage,
+            refund: self.refund.max(0) as u64,
+        });
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<EvmWiringT>) {
+        if !self.logged {
+            return;
+        }
+        let Some(log) = self.logs.last_mut() else {
+            return;
+        };
+        log.gas_cost = log.gas.saturating_sub(interp.gas.remaining());
+
+        let result = interp.instruction_result;
+        if let Some(slot) = self.sload.take().filter(|_| result.is_ok()) {
+            // The loaded value replaces the slot on the stack.
+            let value = interp.stack.peek(0).unwrap_or_default().into();
+            let storage = self
+                .storage
+                .entry(interp.contract.target_address)
+                .or_default();
+            storage.insert(slot, value);
+            log.storage = Some(storage.clone());
+        }
+        log.error = step_error(result, self.op, self.stack_len);
+    }
+
+    fn call(
+        &mut self,
+        _context: &mut EvmContext<EvmWiringT>,
+        _inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        self.start();
+        None
+    }
+
+    fn call_end(
+        &mut self,
+        context: &mut EvmContext<EvmWiringT>,
+        _inputs: &CallInputs,
+        outcome: CallOutcome,
+    ) -> CallOutcome {
+        self.end(context, &outcome.result);
+        outcome
+    }
+
+    fn create(
+        &mut self,
+        _context: &mut EvmContext<EvmWiringT>,
+        inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.start_create(inputs.gas_limit);
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        context: &mut EvmContext<EvmWiringT>,
+        _inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        self.end(context, &outcome.result);
+        outcome
+    }
+
+    fn eofcreate(
+        &mut self,
+        _context: &mut EvmContext<EvmWiringT>,
+        inputs: &mut EOFCreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.start_create(inputs.gas_limit);
+        None
+    }
+
+    fn eofcreate_end(
+        &mut self,
+        context: &mut EvmContext<EvmWiringT>,
+        _inputs: &EOFCreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        self.end(context, &outcome.result);
+        outcome
+    }
+}
+
+/// Returns the Geth error of a step that failed before executing.
+///
+/// Errors raised while executing the step, like a revert, are not logged by Geth.
+fn step_error(result: InstructionResult, op: u8, len: usize) -> Option<String> {
+    let (inputs, outputs) = OpCode::new(op).map_or((0, 0), |op| op.input_output());
+    match result {
+        InstructionResult::OutOfGas
+        | InstructionResult::MemoryOOG
+        | InstructionResult::MemoryLimitOOG
+        | InstructionResult::InvalidOperandOOG => Some(error_message(result)),
+        InstructionResult::StackUnderflow => Some(format!("stack underflow ({len} <=> {inputs})")),
+        InstructionResult::StackOverflow => Some(format!(
+            "stack limit reached {len} ({})",
+            STACK_LIMIT + inputs as usize - outputs as usize
+        )),
+        _ => None,
+    }
+}
+
+/// Serializes data as hex without the `0x` prefix, as Geth does for the memory, the storage and
+/// the returned value.
+#[cfg(feature = "serde")]
+mod unprefixed {
+    use revm::primitives::{hex, Bytes, B256};
+    use serde::{de, Deserialize, Deserializer, Serializer};
+    use std::{collections::BTreeMap, string::String, vec::Vec};
+
+    pub(super) trait Unprefixed: Sized {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+
+        fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>;
+    }
+
+    pub(super) fn serialize<T: Unprefixed, S: Serializer>(
+        value: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        value.serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, T: Unprefixed, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        T::deserialize(deserializer)
+    }
+
+    fn parse<E: de::Error>(word: &str) -> Result<B256, E> {
+        word.parse().map_err(E::custom)
+    }
+
+    impl Unprefixed for Bytes {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_str(&hex::encode(self))
+        }
+
+        fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let data = String::deserialize(deserializer)?;
+            hex::decode(data).map(Into::into).map_err(de::Error::custom)
+        }
+    }
+
+    impl Unprefixed for Option<Vec<B256>> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            match self {
+                Some(words) => serializer.collect_seq(words.iter().map(hex::encode)),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            Option::<Vec<String>>::deserialize(deserializer)?
+                .map(|words| words.iter().map(|word| parse(word)).collect())
+                .transpose()
+        }
+    }
+
+    impl Unprefixed for Option<BTreeMap<B256, B256>> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            match self {
+                Some(storage) => serializer.collect_map(
+                    storage
+                        .iter()
+                        .map(|(slot, value)| (hex::encode(slot), hex::encode(value))),
+                ),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            Option::<BTreeMap<String, String>>::deserialize(deserializer)?
+                .map(|storage| {
+                    storage
+                        .iter()
+                        .map(|(slot, value)| Ok((parse(slot)?, parse(value)?)))
+                        .collect()
+                })
+                .transpose()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inspector_handle_register;
+    use database::InMemoryDB;
+    use revm::{
+        bytecode::opcode::*,
+        primitives::address,
+        state::AccountInfo,
+        test_utils::{account, CALLER, CALLER_A},
+        wiring::{
+            result::{ExecutionResult, HaltReason},
+            EthereumWiring,
+        },
+        Evm,
+    };
+
+    const CLEARER: Address = address!("00000000000000000000000000000000000000bb");
+    const STORER: Address = address!("00000000000000000000000000000000000000cc");
+
+    fn trace(
+        config: StructLoggerConfig,
+        to: Address,
+        gas_limit: u64,
+    ) -> (ExecutionResult<HaltReason>, StructLogResult) {
+        // Calls the clearer, then stops.
+        let mut caller_a = vec![PUSH1, 0, PUSH1, 0, PUSH1, 0, PUSH1, 0, PUSH1, 0, PUSH20];
+        caller_a.extend_from_slice(CLEARER.as_slice());
+        caller_a.extend([GAS, CALL, POP, STOP]);
+
+        // Clears slot 0 and returns one byte.
+        let clearer = vec![PUSH1, 0, PUSH1, 0, SSTORE, PUSH1, 1, PUSH1, 0, RETURN];
+
+        // Stores 0x2a in slot 0, loads it and returns it.
+        let storer = vec![
+            PUSH1, 0x2a, PUSH1, 0, SSTORE, PUSH1, 0, SLOAD, PUSH1, 0, MSTORE, PUSH1, 0x20, PUSH1,
+            0, RETURN,
+        ];
+
+        let mut evm = Evm::<EthereumWiring<InMemoryDB, StructLogger>>::builder()
+            .with_default_db()
+            .with_external_context(StructLogger::new(config))
+            .modify_db(|db| {
+                db.insert_account_info(
+                    CALLER,
+                    AccountInfo::from_balance(U256::from(10u64.pow(18))),
+                );
+                db.insert_account_info(CALLER_A, account(caller_a));
+                db.insert_account_info(CLEARER, account(clearer));
+                db.insert_account_storage(CLEARER, U256::ZERO, U256::from(1))
+                    .unwrap();
+                db.insert_account_info(STORER, account(storer));
+                // Underflows the stack.
+                db.insert_account_info(Address::ZERO, account(vec![PUSH1, 1, ADD]));
+            })
+            .modify_tx_env(|tx| {
+                tx.caller = CALLER;
+                tx.transact_to = to.into();
+                tx.gas_limit = gas_limit;
+            })
+            .append_handler_register(inspector_handle_register)
+            .build();
+        let result = evm.transact().unwrap().result;
+        let trace = evm.into_context().external.into_result().unwrap();
+        (result, trace)
+    }
+
+    #[test]
+    fn nested_call() {
+        let config = StructLoggerConfig {
+            enable_return_data: true,
+            ..Default::default()
+        };
+        let (result, trace) = trace(config, CALLER_A, 100_000);
+        assert!(result.is_success());
+        assert_eq!(trace.gas, result.gas_used());
+        assert!(!trace.failed);
+        assert!(trace.return_value.is_empty());
+
+        let logs = &trace.struct_logs;
+        let ops: Vec<_> = logs
+            .iter()
+            .map(|log| (log.op.as_str(), log.depth))
+            .collect();
+        assert_eq!(ops.len(), 8 + 6 + 2);
+        assert_eq!(ops[7], ("CALL", 1));
+        assert_eq!(ops[8], ("PUSH1", 2));
+        assert_eq!(ops[14], ("POP", 1));
+        assert_eq!(logs[0].gas, 100_000 - 21_000);
+        assert_eq!(logs[0].stack, Some(Vec::new()));
+        assert_eq!(logs[0].memory, None);
+
+        // The call costs the cold account access and the gas given to the call.
+        assert_eq!(logs[7].gas_cost, 2600 + logs[8].gas);
+
+        // Clearing the slot refunds gas for the rest of the transaction.
+        let sstore = &logs[10];
+        assert_eq!(sstore.op, "SSTORE");
+        assert_eq!(sstore.gas_cost, 5000);
+        assert_eq!(sstore.refund, 0);
+        assert_eq!(
+            sstore.storage,
+            Some(BTreeMap::from([(B256::ZERO, B256::ZERO)]))
+        );
+        assert_eq!(logs[11].refund, 4800);
+        assert_eq!(logs[14].refund, 4800);
+
+        assert!(logs[7].return_data.is_empty());
+        assert_eq!(logs[14].return_data, Bytes::from([0]));
+        assert!(logs.iter().all(|log| log.error.is_none()));
+    }
+
+    #[test]
+    fn config() {
+        let config = StructLoggerConfig {
+            disable_stack: true,
+            disable_storage: true,
+            limit: 5,
+            ..Default::default()
+        };
+        let (result, trace) = trace(config, STORER, 100_000);
+        assert_eq!(trace.gas, result.gas_used());
+        assert_eq!(trace.return_value[..], B256::with_last_byte(0x2a)[..]);
+        assert_eq!(trace.struct_logs.len(), 5);
+        assert!(trace
+            .struct_logs
+            .iter()
+            .all(|log| log.stack.is_none() && log.storage.is_none()));
+    }
+
+    #[test]
+    fn step_errors() {
+        let (result, oog) = trace(StructLoggerConfig::default(), STORER, 21_005);
+        assert!(!result.is_success());
+        assert_eq!(oog.gas, 21_005);
+        assert!(oog.failed);
+        assert!(oog.return_value.is_empty());
+        assert_eq!(oog.struct_logs.len(), 2);
+        assert_eq!(oog.struct_logs[0].error, None);
+        assert_eq!(oog.struct_logs[1].error.as_deref(), Some("out of gas"));
+
+        let (_, underflow) = trace(StructLoggerConfig::default(), Address::ZERO, 100_000);
+        assert!(underflow.failed);
+        assert_eq!(
+            underflow.struct_logs[1].error.as_deref(),
+            Some("stack underflow (1 <=> 2)")
+        );
+    }
+
+    #[cfg(feature = "serde-json")]
+    #[test]
+    fn json() {
+        // See `tests/struct_logger/ref.txt` for the provenance of the fixture.
+        let config: StructLoggerConfig = serde_json::from_str(r#"{"enableMemory":true}"#).unwrap();
+        let (_, trace) = trace(config, STORER, 100_000);
+
+        let fixture = include_str!("../../../tests/struct_logger/storer.json");
+        let expected: serde_json::Value = serde_json::from_str(fixture).unwrap();
+        assert_eq!(serde_json::to_value(&trace).unwrap(), expected);
+
+        let decoded: StructLogResult = serde_json::from_str(fixture).unwrap();
+        assert_eq!(decoded, trace);
+    }
+}
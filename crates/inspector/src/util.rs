@@ -0,0 +1,58 @@
+use revm::{
+    interpreter::{Gas, InstructionResult, InterpreterResult},
+    specification::hardfork::SpecId,
+};
+use std::{format, string::String};
+
+/// Returns the gas used by the transaction, after the refund.
+pub(crate) fn transaction_gas_used(
+    spec_id: SpecId,
+    gas_limit: u64,
+    result: &InterpreterResult,
+) -> u64 {
+    let mut gas = Gas::new_spent(gas_limit);
+    if result.result.is_error() {
+        return gas_limit;
+    }
+    gas.erase_cost(result.gas.remaining());
+    if result.is_ok() {
+        gas.record_refund(result.gas.refunded());
+        gas.set_final_refund(spec_id.is_enabled_in(SpecId::LONDON));
+    }
+    gas.spent() - gas.refunded() as u64
+}
+
+/// Returns the Geth error message of the failed call.
+pub(crate) fn error_message(result: InstructionResult) -> String {
+    let message = match result {
+        InstructionResult::Revert => "execution reverted",
+        InstructionResult::OutOfGas
+        | InstructionResult::MemoryOOG
+        | InstructionResult::MemoryLimitOOG
+        | InstructionResult::PrecompileOOG
+        | InstructionResult::InvalidOperandOOG
+        | InstructionResult::ReentrancySentryOOG => "out of gas",
+        InstructionResult::OpcodeNotFound | InstructionResult::InvalidFEOpcode => "invalid opcode",
+        InstructionResult::CallNotAllowedInsideStatic
+        | InstructionResult::StateChangeDuringStaticCall => "write protection",
+        InstructionResult::InvalidJump => "invalid jump destination",
+        InstructionResult::StackUnderflow => "stack underflow",
+        InstructionResult::StackOverflow => "stack overflow",
+        InstructionResult::OutOfOffset => "return data out of bounds",
+        InstructionResult::CallTooDeep => "max call depth exceeded",
+        InstructionResult::OutOfFunds => "insufficient balance for transfer",
+        InstructionResult::CreateCollision => "contract address collision",
+        InstructionResult::NonceOverflow => "nonce uint64 overflow",
+        InstructionResult::CreateContractSizeLimit => "max code size exceeded",
+        InstructionResult::CreateInitCodeSizeLimit => "max initcode size exceeded",
+        InstructionResult::CreateContractStartingWithEF => "invalid code: must not begin with 0xef",
+        InstructionResult::PrecompileError => "precompiled contract failed",
+        result => return format!("{result:?}"),
+    };
+    message.into()
+}
+
+#[cfg(feature = "serde")]
+pub(crate) fn is_zero(value: &u64) -> bool {
+    *value == 0
+}
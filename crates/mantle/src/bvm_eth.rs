@@ -0,0 +1,194 @@
+//! BVM_ETH predeploy helpers.
+//!
+//! ETH bridged to Mantle is represented by the BVM_ETH ERC-20 predeploy. Deposit transactions
+//! carry the bridged amount in `eth_value`, which is minted to the sender, and optionally an
+//! amount in `eth_tx_hash` that is then transferred from the sender to the recipient.
+use revm::{
+    database_interface::Database,
+    primitives::{address, b256, keccak256, Address, Log, LogData, B256, U256},
+    state::{Account, EvmStorageSlot},
+    JournaledState,
+};
+
+/// The address of the BVM_ETH predeploy.
+pub const BVM_ETH_ADDRESS: Address = address!("deaddeaddeaddeaddeaddeaddeaddeaddead1111");
+
+/// Storage slot of the `balances` mapping of the BVM_ETH contract.
+const BALANCES_SLOT: U256 = U256::from_limbs([0u64, 0, 0, 0]);
+
+/// Storage slot of the `totalSupply` of the BVM_ETH contract.
+const TOTAL_SUPPLY_SLOT: U256 = U256::from_limbs([2u64, 0, 0, 0]);
+
+/// `keccak256("Mint(address,uint256)")`
+pub const MINT_EVENT_TOPIC: B256 =
+    b256!("0f6798a560793a54c3bcfe86a93cde1e73087d944c0ea20544137d4121396885");
+
+/// `keccak256("Transfer(address,address,uint256)")`
+pub const TRANSFER_EVENT_TOPIC: B256 =
+    b256!("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef");
+
+/// Returns the storage slot holding the BVM_ETH balance of `owner`.
+///
+/// This is `keccak256(pad32(owner) . pad32(BALANCES_SLOT))` as laid out by Solidity.
+pub fn balance_slot(owner: Address) -> U256 {
+    let mut preimage = [0u8; 64];
+    preimage[12..32].copy_from_slice(owner.as_slice());
+    preimage[32..].copy_from_slice(&BALANCES_SLOT.to_be_bytes::<32>());
+    keccak256(preimage).into()
+}
+
+/// Returns the storage slot holding the BVM_ETH total supply.
+pub const fn total_supply_slot() -> U256 {
+    TOTAL_SUPPLY_SLOT
+}
+
+/// Mints `amount` of BVM_ETH to `to` inside the journaled state and emits a `Mint` event.
+pub fn mint<DB: Database>(
+    journaled_state: &mut JournaledState,
+    db: &mut DB,
+    to: Address,
+    amount: U256,
+) -> Result<(), DB::Error> {
+    journaled_state.load_account(BVM_ETH_ADDRESS, db)?;
+    journaled_state.touch(&BVM_ETH_ADDRESS);
+
+    add_to_slot(journaled_state, db, balance_slot(to), amount)?;
+    add_to_slot(journaled_state, db, TOTAL_SUPPLY_SLOT, amount)?;
+
+    journaled_state.log(Log {
+        address: BVM_ETH_ADDRESS,
+        data: LogData::new_unchecked(
+            std::vec![MINT_EVENT_TOPIC, to.into_word()],
+            amount.to_be_bytes_vec().into(),
+        ),
+    });
+    Ok(())
+}
+
+/// Transfers `amount` of BVM_ETH from `from` to `to` inside the journaled state and emits a
+/// `Transfer` event.
+///
+/// Transfers to self are a no-op, matching the Mantle node.
+pub fn transfer<DB: Database>(
+    journaled_state: &mut JournaledState,
+    db: &mut DB,
+    from: Address,
+    to: Address,
+    amount: U256,
+) -> Result<(), DB::Error> {
+    if from == to {
+        return Ok(());
+    }
+    journaled_state.load_account(BVM_ETH_ADDRESS, db)?;
+    journaled_state.touch(&BVM_ETH_ADDRESS);
+
+    let from_slot = balance_slot(from);
+    let from_balance = journaled_state.sload(BVM_ETH_ADDRESS, from_slot, db)?.data;
+    journaled_state.sstore(
+        BVM_ETH_ADDRESS,
+        from_slot,
+        from_balance.saturating_sub(amount),
+        db,
+    )?;
+    add_to_slot(journaled_state, db, balance_slot(to), amount)?;
+
+    journaled_state.log(Log {
+        address: BVM_ETH_ADDRESS,
+        data: LogData::new_unchecked(
+            std::vec![TRANSFER_EVENT_TOPIC, from.into_word(), to.into_word()],
+            amount.to_be_bytes_vec().into(),
+        ),
+    });
+    Ok(())
+}
+
+/// Builds the BVM_ETH account after minting `mint` to `from` and transferring `transfer` from
+/// `from` to `to`, reading the current values directly from the database.
+///
+/// This is used for failed deposits, where the journaled state is discarded but the bridged
+/// ETH must still be credited to the sender and transferred to the recipient.
+pub fn failed_deposit_account<DB: Database>(
+    db: &mut DB,
+    from: Address,
+    to: Address,
+    mint: U256,
+    transfer: U256,
+) -> Result<Account, DB::Error> {
+    let mut account = Account::from(db.basic(BVM_ETH_ADDRESS)?.unwrap_or_default());
+    if !mint.is_zero() {
+        update_slot(db, &mut account, balance_slot(from), |v| {
+            v.saturating_add(mint)
+        })?;
+        update_slot(db, &mut account, TOTAL_SUPPLY_SLOT, |v| {
+            v.saturating_add(mint)
+        })?;
+    }
+    if !transfer.is_zero() && from != to {
+        update_slot(db, &mut account, balance_slot(from), |v| {
+            v.saturating_sub(transfer)
+        })?;
+        update_slot(db, &mut account, balance_slot(to), |v| {
+            v.saturating_add(transfer)
+        })?;
+    }
+    account.mark_touch();
+    Ok(account)
+}
+
+/// Updates `slot` of the BVM_ETH `account`, reading its original value from the database on
+/// first access.
+fn update_slot<DB: Database>(
+    db: &mut DB,
+    account: &mut Account,
+    slot: U256,
+    f: impl FnOnce(U256) -> U256,
+) -> Result<(), DB::Error> {
+    let (original, present) = match account.storage.get(&slot) {
+        Some(value) => (value.original_value(), value.present_value()),
+        None => {
+            let original = db.storage(BVM_ETH_ADDRESS, slot)?;
+            (original, original)
+        }
+    };
+    account
+        .storage
+        .insert(slot, EvmStorageSlot::new_changed(original, f(present)));
+    Ok(())
+}
+
+/// Adds `amount` to the value stored in `slot` of the BVM_ETH contract.
+fn add_to_slot<DB: Database>(
+    journaled_state: &mut JournaledState,
+    db: &mut DB,
+    slot: U256,
+    amount: U256,
+) -> Result<(), DB::Error> {
+    let value = journaled_state.sload(BVM_ETH_ADDRESS, slot, db)?.data;
+    journaled_state.sstore(BVM_ETH_ADDRESS, slot, value.saturating_add(amount), db)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_topics() {
+        assert_eq!(MINT_EVENT_TOPIC, keccak256("Mint(address,uint256)"));
+        assert_eq!(
+            TRANSFER_EVENT_TOPIC,
+            keccak256("Transfer(address,address,uint256)")
+        );
+    }
+
+    #[test]
+    fn test_balance_slot() {
+        // keccak256(abi.encode(address(1), uint256(0)))
+        assert_eq!(
+            balance_slot(address!("0000000000000000000000000000000000000001")),
+            U256::from_be_bytes(
+                b256!("ada5013122d395ba3c54772283fb069b10426056ef8ca54750cb9bb552a59e7d").0
+            )
+        );
+    }
+}
@@ -0,0 +1,251 @@
+//! Hardfork activation schedule of a Mantle chain.
+use crate::MantleSpecId;
+
+pub use op_stack::{ChainSpec, ForkCondition};
+
+/// Chain ID of Mantle mainnet.
+pub const MANTLE_MAINNET_CHAIN_ID: u64 = 5000;
+
+/// Chain ID of Mantle Sepolia.
+pub const MANTLE_SEPOLIA_CHAIN_ID: u64 = 5003;
+
+/// Activation timestamp of Everest on Mantle mainnet.
+///
+/// The preset timestamps must match the `mantleEverestTime`, `mantleSkadiTime` and
+/// `mantleLimbTime` of the chain config shipped with the Mantle node for that network.
+pub const MANTLE_MAINNET_EVEREST_TIMESTAMP: u64 = 1742385600;
+
+/// Activation timestamp of Skadi on Mantle mainnet.
+pub const MANTLE_MAINNET_SKADI_TIMESTAMP: u64 = 1756278000;
+
+/// Activation timestamp of Limb on Mantle mainnet.
+pub const MANTLE_MAINNET_LIMB_TIMESTAMP: u64 = 1768395600;
+
+/// Activation timestamp of Everest on Mantle Sepolia.
+pub const MANTLE_SEPOLIA_EVEREST_TIMESTAMP: u64 = 1741766400;
+
+/// Activation timestamp of Skadi on Mantle Sepolia.
+pub const MANTLE_SEPOLIA_SKADI_TIMESTAMP: u64 = 1752649200;
+
+/// Activation timestamp of Limb on Mantle Sepolia.
+pub const MANTLE_SEPOLIA_LIMB_TIMESTAMP: u64 = 1764745200;
+
+/// Hardfork activation schedule of a Mantle chain.
+pub type MantleChainSpec = ChainSpec<MantleSpecId>;
+
+/// Mantle mainnet.
+pub fn mainnet() -> MantleChainSpec {
+    bedrock_genesis(MANTLE_MAINNET_CHAIN_ID).with_hardforks([
+        (
+            MantleSpecId::EVEREST,
+            ForkCondition::Timestamp(MANTLE_MAINNET_EVEREST_TIMESTAMP),
+        ),
+        (
+            MantleSpecId::SKADI,
+            ForkCondition::Timestamp(MANTLE_MAINNET_SKADI_TIMESTAMP),
+        ),
+        (
+            MantleSpecId::LIMB,
+            ForkCondition::Timestamp(MANTLE_MAINNET_LIMB_TIMESTAMP),
+        ),
+    ])
+}
+
+/// Mantle Sepolia.
+pub fn sepolia() -> MantleChainSpec {
+    bedrock_genesis(MANTLE_SEPOLIA_CHAIN_ID).with_hardforks([
+        (
+            MantleSpecId::EVEREST,
+            ForkCondition::Timestamp(MANTLE_SEPOLIA_EVEREST_TIMESTAMP),
+        ),
+        (
+            MantleSpecId::SKADI,
+            ForkCondition::Timestamp(MANTLE_SEPOLIA_SKADI_TIMESTAMP),
+        ),
+        (
+            MantleSpecId::LIMB,
+            ForkCondition::Timestamp(MANTLE_SEPOLIA_LIMB_TIMESTAMP),
+        ),
+    ])
+}
+
+/// Chain spec with every hardfork up to Regolith active at genesis.
+fn bedrock_genesis(chain_id: u64) -> MantleChainSpec {
+    MantleChainSpec::new(chain_id)
+        .with_hardforks(
+            [
+                MantleSpecId::HOMESTEAD,
+                MantleSpecId::TANGERINE,
+                MantleSpecId::SPURIOUS_DRAGON,
+                MantleSpecId::BYZANTIUM,
+                MantleSpecId::PETERSBURG,
+                MantleSpecId::ISTANBUL,
+                MantleSpecId::BERLIN,
+                MantleSpecId::LONDON,
+                MantleSpecId::MERGE,
+                MantleSpecId::BEDROCK,
+            ]
+            .map(|spec_id| (spec_id, ForkCondition::Block(0))),
+        )
+        .with_hardfork(MantleSpecId::REGOLITH, ForkCondition::Timestamp(0))
+}
+
+/// Loads the chain spec from the `config` of a genesis JSON file.
+#[cfg(feature = "serde-json")]
+pub fn from_genesis_json(json: &str) -> Result<MantleChainSpec, serde_json::Error> {
+    let genesis: genesis::Genesis = serde_json::from_str(json)?;
+    Ok(genesis.config.into())
+}
+
+#[cfg(feature = "serde-json")]
+mod genesis {
+    use super::*;
+
+    #[derive(serde::Deserialize)]
+    pub(super) struct Genesis {
+        pub(super) config: ChainConfig,
+    }
+
+    /// OP stack chain config with the activations of the Mantle hardforks.
+    #[derive(serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub(super) struct ChainConfig {
+        #[serde(flatten)]
+        op_stack: op_stack::chain_spec::ChainConfig,
+        mantle_everest_time: Option<u64>,
+        mantle_skadi_time: Option<u64>,
+        mantle_limb_time: Option<u64>,
+    }
+
+    impl From<ChainConfig> for MantleChainSpec {
+        fn from(config: ChainConfig) -> Self {
+            let hardforks = [
+                (MantleSpecId::EVEREST, config.mantle_everest_time),
+                (MantleSpecId::SKADI, config.mantle_skadi_time),
+                (MantleSpecId::LIMB, config.mantle_limb_time),
+            ];
+            MantleChainSpec::from(&config.op_stack).with_hardforks(
+                hardforks
+                    .into_iter()
+                    .filter_map(|(spec_id, time)| Some((spec_id, ForkCondition::Timestamp(time?)))),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wiring::MantleEvmWiring;
+    use database::InMemoryDB;
+    use revm::{primitives::U256, wiring::default::block::BlockEnv, Evm};
+
+    #[test]
+    fn test_spec_id_at() {
+        let spec = MantleChainSpec::new(1)
+            .with_hardfork(MantleSpecId::BEDROCK, ForkCondition::Block(10))
+            .with_hardfork(MantleSpecId::REGOLITH, ForkCondition::Timestamp(100))
+            .with_hardfork(MantleSpecId::SKADI, ForkCondition::Timestamp(300))
+            .with_hardfork(MantleSpecId::PRAGUE, ForkCondition::Timestamp(300))
+            .with_hardfork(MantleSpecId::EVEREST, ForkCondition::Timestamp(200));
+
+        assert_eq!(spec.spec_id_at(0, 0), MantleSpecId::FRONTIER);
+        assert_eq!(spec.spec_id_at(10, 0), MantleSpecId::BEDROCK);
+        assert_eq!(spec.spec_id_at(10, 100), MantleSpecId::REGOLITH);
+        assert_eq!(spec.spec_id_at(10, 299), MantleSpecId::EVEREST);
+        assert_eq!(spec.spec_id_at(10, 300), MantleSpecId::SKADI);
+    }
+
+    #[test]
+    fn test_presets() {
+        for (spec, chain_id, activations) in [
+            (
+                mainnet(),
+                MANTLE_MAINNET_CHAIN_ID,
+                [
+                    MANTLE_MAINNET_EVEREST_TIMESTAMP,
+                    MANTLE_MAINNET_SKADI_TIMESTAMP,
+                    MANTLE_MAINNET_LIMB_TIMESTAMP,
+                ],
+            ),
+            (
+                sepolia(),
+                MANTLE_SEPOLIA_CHAIN_ID,
+                [
+                    MANTLE_SEPOLIA_EVEREST_TIMESTAMP,
+                    MANTLE_SEPOLIA_SKADI_TIMESTAMP,
+                    MANTLE_SEPOLIA_LIMB_TIMESTAMP,
+                ],
+            ),
+        ] {
+            assert_eq!(spec.chain_id(), chain_id);
+            assert_eq!(spec.spec_id_at(0, 0), MantleSpecId::REGOLITH);
+
+            let [everest, skadi, limb] = activations;
+            for (timestamp, expected) in [
+                (everest - 1, MantleSpecId::REGOLITH),
+                (everest, MantleSpecId::EVEREST),
+                (everest + 1, MantleSpecId::EVEREST),
+                (skadi - 1, MantleSpecId::EVEREST),
+                (skadi, MantleSpecId::SKADI),
+                (skadi + 1, MantleSpecId::SKADI),
+                (limb - 1, MantleSpecId::SKADI),
+                (limb, MantleSpecId::LIMB),
+                (limb + 1, MantleSpecId::LIMB),
+                (u64::MAX, MantleSpecId::LIMB),
+            ] {
+                assert_eq!(
+                    spec.spec_id_at(1, timestamp),
+                    expected,
+                    "{chain_id} {timestamp}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_configure() {
+        let block = BlockEnv {
+            number: U256::from(1),
+            timestamp: U256::from(MANTLE_MAINNET_SKADI_TIMESTAMP),
+            ..Default::default()
+        };
+        let evm = mainnet()
+            .configure(
+                Evm::<MantleEvmWiring<InMemoryDB, ()>>::builder()
+                    .with_default_db()
+                    .with_default_ext_ctx(),
+                block.clone(),
+            )
+            .build();
+
+        assert_eq!(evm.spec_id(), MantleSpecId::SKADI);
+        assert_eq!(evm.context.evm.env.block, block);
+        assert_eq!(evm.context.evm.env.cfg.chain_id, MANTLE_MAINNET_CHAIN_ID);
+    }
+
+    #[cfg(feature = "serde-json")]
+    #[test]
+    fn test_from_genesis_json() {
+        let spec = from_genesis_json(
+            r#"{
+                "config": {
+                    "chainId": 5003,
+                    "homesteadBlock": 0,
+                    "londonBlock": 0,
+                    "bedrockBlock": 0,
+                    "regolithTime": 0,
+                    "mantleEverestTime": 100,
+                    "mantleSkadiTime": 200
+                },
+                "alloc": {}
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(spec.chain_id(), MANTLE_SEPOLIA_CHAIN_ID);
+        assert_eq!(spec.spec_id_at(0, 0), MantleSpecId::REGOLITH);
+        assert_eq!(spec.spec_id_at(0, 100), MantleSpecId::EVEREST);
+        assert_eq!(spec.spec_id_at(0, 200), MantleSpecId::SKADI);
+    }
+}
@@ -0,0 +1,621 @@
+//! Execution of whole Mantle blocks.
+use crate::{
+    wiring::MantleEvmWiring, MantleChainSpec, MantleDepositReceipt, MantleFeeReceipt,
+    MantleResultAndState, MantleSpecId, OpTransaction, OpTransactionError, OpTransactionType,
+    L1_BLOCK_CONTRACT, L1_INFO_DEPOSITOR,
+};
+use core::fmt;
+use database::{states::bundle_state::BundleRetention, BundleState, State};
+use op_stack::{OpStackHardfork, OpStackUpgrade};
+use revm::{
+    database_interface::{Database, DatabaseCommit},
+    primitives::{alloy_primitives::Bloom, Log, TxKind, B256},
+    specification::{eip4788::BEACON_ROOTS_ADDRESS, hardfork::SpecId},
+    transaction::Transaction,
+    wiring::{
+        default::{block::BlockEnv, TxEnv},
+        result::EVMError,
+    },
+    Evm,
+};
+use std::{format, vec::Vec};
+
+/// A Mantle block to execute.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MantleBlock {
+    /// The block environment built from the header.
+    pub env: BlockEnv,
+    /// The parent beacon block root of the header, required since Cancun.
+    pub parent_beacon_block_root: Option<B256>,
+    /// The transactions of the block, starting with the L1 attributes deposit.
+    pub transactions: Vec<OpTransaction<TxEnv>>,
+}
+
+/// Receipt of a transaction executed in a block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MantleReceipt {
+    /// Type of the transaction.
+    pub tx_type: OpTransactionType,
+    /// Whether the transaction succeeded.
+    pub success: bool,
+    /// Gas used by this transaction and all the previous ones of the block.
+    pub cumulative_gas_used: u64,
+    /// Logs emitted by the transaction.
+    pub logs: Vec<Log>,
+    /// Deposit fields of the receipt, None for non-deposit transactions.
+    pub deposit_receipt: Option<MantleDepositReceipt>,
+    /// Fee breakdown, None for deposit transactions.
+    pub fee_receipt: Option<MantleFeeReceipt>,
+}
+
+impl MantleReceipt {
+    /// Returns the bloom filter of the logs.
+    pub fn logs_bloom(&self) -> Bloom {
+        let mut bloom = Bloom::ZERO;
+        for log in &self.logs {
+            bloom.accrue_log(log);
+        }
+        bloom
+    }
+}
+
+/// Result of the execution of a block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MantleBlockExecutionOutput {
+    /// Receipts of the transactions, in block order.
+    pub receipts: Vec<MantleReceipt>,
+    /// Gas used by the block.
+    pub gas_used: u64,
+    /// State changes of the block, with the reverts. Used to compute the state root.
+    pub bundle: BundleState,
+}
+
+/// Error of the execution of a block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MantleBlockExecutionError<DBError> {
+    /// The first transaction of a block is not the L1 attributes deposit.
+    MissingL1InfoDeposit,
+    /// The gas used by the transactions is above the block gas limit.
+    BlockGasUsedMoreThanGasLimit {
+        /// Index of the transaction that can't be executed.
+        index: usize,
+        /// Gas used by the previous transactions.
+        cumulative_gas_used: u64,
+        /// Gas limit of the block.
+        block_gas_limit: u64,
+    },
+    /// The parent beacon block root is required since Cancun.
+    MissingParentBeaconBlockRoot,
+    /// The beacon roots contract call failed.
+    BeaconRootContractCall(EVMError<DBError, OpTransactionError>),
+    /// The gas limit of a transaction is above the gas left in the block.
+    TransactionGasLimitMoreThanAvailableBlockGas {
+        /// Index of the transaction in the block.
+        index: usize,
+        /// Gas limit of the transaction.
+        transaction_gas_limit: u64,
+        /// Gas left in the block.
+        block_available_gas: u64,
+    },
+    /// A transaction is invalid or the database failed.
+    Transaction {
+        /// Index of the transaction in the block.
+        index: usize,
+        /// The error of the transaction.
+        error: EVMError<DBError, OpTransactionError>,
+    },
+}
+
+#[cfg(feature = "std")]
+impl<DBError: std::error::Error + 'static> std::error::Error
+    for MantleBlockExecutionError<DBError>
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::BeaconRootContractCall(error) => Some(error),
+            Self::Transaction { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl<DBError: fmt::Display> fmt::Display for MantleBlockExecutionError<DBError> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingL1InfoDeposit => {
+                f.write_str("first transaction is not the L1 attributes deposit")
+            }
+            Self::BlockGasUsedMoreThanGasLimit {
+                index,
+                cumulative_gas_used,
+                block_gas_limit,
+            } => write!(
+                f,
+                "block gas used {cumulative_gas_used} before transaction {index} is more than \
+                 the block gas limit {block_gas_limit}"
+            ),
+            Self::MissingParentBeaconBlockRoot => f.write_str("missing parent beacon block root"),
+            Self::BeaconRootContractCall(error) => {
+                write!(f, "beacon roots contract call failed: {error}")
+            }
+            Self::TransactionGasLimitMoreThanAvailableBlockGas {
+                index,
+                transaction_gas_limit,
+                block_available_gas,
+            } => write!(
+                f,
+                "transaction {index} gas limit {transaction_gas_limit} is more than the \
+                 available block gas {block_available_gas}"
+            ),
+            Self::Transaction { index, error } => write!(f, "transaction {index}: {error}"),
+        }
+    }
+}
+
+/// Executes Mantle blocks on top of a database.
+///
+/// The changes of the executed blocks are kept in a [State], so consecutive blocks can be
+/// executed before flushing them to the database.
+#[derive(Debug)]
+pub struct MantleBlockExecutor<DB> {
+    chain_spec: MantleChainSpec,
+    state: State<DB>,
+}
+
+impl<DB: Database> MantleBlockExecutor<DB> {
+    /// Creates an executor of the blocks of `chain_spec` on top of `db`.
+    pub fn new(chain_spec: MantleChainSpec, db: DB) -> Self {
+        Self {
+            chain_spec,
+            state: State::builder()
+                .with_database(db)
+                .with_bundle_update()
+                .build(),
+        }
+    }
+
+    /// Returns the chain spec.
+    pub fn chain_spec(&self) -> &MantleChainSpec {
+        &self.chain_spec
+    }
+
+    /// Returns the state with the changes of the executed blocks.
+    pub fn state(&self) -> &State<DB> {
+        &self.state
+    }
+
+    /// Returns the state with the changes of the executed blocks.
+    pub fn state_mut(&mut self) -> &mut State<DB> {
+        &mut self.state
+    }
+
+    /// Consumes the executor and returns the state.
+    pub fn into_state(self) -> State<DB> {
+        self.state
+    }
+
+    /// Executes the block and returns the receipts and the state changes of the block.
+    ///
+    /// The L1 attributes deposit, sent by [L1_INFO_DEPOSITOR] to [L1_BLOCK_CONTRACT], must be the
+    /// first transaction since Bedrock. It is executed
+    /// like any other deposit and the following transactions read the L1 block info it sets.
+    /// Since Cancun, the parent beacon block root is stored in the beacon roots contract
+    /// before the transactions are executed.
+    ///
+    /// On error, the state holds the changes of the transactions executed before the error
+    /// and the executor should be discarded.
+    pub fn execute_block(
+        &mut self,
+        block: &MantleBlock,
+    ) -> Result<MantleBlockExecutionOutput, MantleBlockExecutionError<DB::Error>> {
+        let receipts = self.execute_transactions(block)?;
+        self.state.merge_transitions(BundleRetention::Reverts);
+        Ok(MantleBlockExecutionOutput {
+            gas_used: receipts
+                .last()
+                .map(|receipt| receipt.cumulative_gas_used)
+                .unwrap_or_default(),
+            receipts,
+            bundle: self.state.take_bundle(),
+        })
+    }
+
+    fn execute_transactions(
+        &mut self,
+        block: &MantleBlock,
+    ) -> Result<Vec<MantleReceipt>, MantleBlockExecutionError<DB::Error>> {
+        let spec_id = self.chain_spec.spec_id_for_block(&block.env);
+        self.state
+            .set_state_clear_flag(spec_id.is_enabled_in(MantleSpecId::SPURIOUS_DRAGON));
+
+        if spec_id.is_upgrade_enabled(OpStackUpgrade::Bedrock) {
+            let is_l1_info_deposit = matches!(
+                block.transactions.first(),
+                Some(OpTransaction::Deposit(deposit))
+                    if deposit.from == L1_INFO_DEPOSITOR
+                        && deposit.to == TxKind::Call(L1_BLOCK_CONTRACT)
+            );
+            if !is_l1_info_deposit {
+                return Err(MantleBlockExecutionError::MissingL1InfoDeposit);
+            }
+        }
+
+        let mut evm = self
+            .chain_spec
+            .configure(
+                Evm::<MantleEvmWiring<&mut State<DB>, ()>>::builder()
+                    .with_db(&mut self.state)
+                    .with_default_ext_ctx(),
+                block.env.clone(),
+            )
+            .build();
+
+        if spec_id.is_eth_enabled(SpecId::CANCUN) {
+            apply_beacon_root_contract_call(&mut evm, block)?;
+        }
+
+        let is_regolith = spec_id.is_upgrade_enabled(OpStackUpgrade::Regolith);
+        let block_gas_limit: u64 = block.env.gas_limit.try_into().unwrap_or(u64::MAX);
+        let mut cumulative_gas_used = 0u64;
+        let mut receipts = Vec::with_capacity(block.transactions.len());
+        for (index, tx) in block.transactions.iter().enumerate() {
+            // Pre-Regolith system transactions are not counted against the block gas limit.
+            let is_system_transaction =
+                matches!(tx, OpTransaction::Deposit(deposit) if deposit.is_system_transaction);
+            let block_available_gas = block_gas_limit.checked_sub(cumulative_gas_used).ok_or(
+                MantleBlockExecutionError::BlockGasUsedMoreThanGasLimit {
+                    index,
+                    cumulative_gas_used,
+                    block_gas_limit,
+                },
+            )?;
+            let transaction_gas_limit = tx.common_fields().gas_limit();
+            if transaction_gas_limit > block_available_gas
+                && (is_regolith || !is_system_transaction)
+            {
+                return Err(
+                    MantleBlockExecutionError::TransactionGasLimitMoreThanAvailableBlockGas {
+                        index,
+                        transaction_gas_limit,
+                        block_available_gas,
+                    },
+                );
+            }
+
+            *evm.tx_mut() = tx.clone();
+            let MantleResultAndState {
+                result,
+                state,
+                fee_receipt,
+                deposit_receipt,
+            } = MantleResultAndState::transact(&mut evm)
+                .map_err(|error| MantleBlockExecutionError::Transaction { index, error })?;
+            evm.db_mut().commit(state);
+
+            cumulative_gas_used += result.gas_used();
+            receipts.push(MantleReceipt {
+                tx_type: tx.tx_type(),
+                success: result.is_success(),
+                cumulative_gas_used,
+                logs: result.into_logs(),
+                deposit_receipt,
+                fee_receipt,
+            });
+        }
+
+        Ok(receipts)
+    }
+}
+
+/// Stores the parent beacon block root in the beacon roots contract (EIP-4788).
+fn apply_beacon_root_contract_call<DB: Database>(
+    evm: &mut Evm<'_, MantleEvmWiring<&mut State<DB>, ()>>,
+    block: &MantleBlock,
+) -> Result<(), MantleBlockExecutionError<DB::Error>> {
+    // There is no parent beacon block root in the genesis block.
+    if block.env.number.is_zero() {
+        return Ok(());
+    }
+    let parent_beacon_block_root = block
+        .parent_beacon_block_root
+        .ok_or(MantleBlockExecutionError::MissingParentBeaconBlockRoot)?;
+
+    let result = evm
+        .system_call_commit(BEACON_ROOTS_ADDRESS, parent_beacon_block_root.0.into())
+        .map_err(MantleBlockExecutionError::BeaconRootContractCall)?;
+    if !result.is_success() {
+        return Err(MantleBlockExecutionError::BeaconRootContractCall(
+            EVMError::Custom(format!("unsuccessful call: {result:?}")),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        chain_spec::{self, MANTLE_MAINNET_CHAIN_ID},
+        l1block::L1_BLOCK_BEDROCK_SELECTOR,
+        transaction::deposit::TxDeposit,
+        L1BlockInfo, Predeploys, L1_FEE_RECIPIENT,
+    };
+    use database::InMemoryDB;
+    use revm::{
+        bytecode::Bytecode,
+        primitives::{address, b256, bytes, Address, U256},
+        specification::eip4788::SYSTEM_ADDRESS,
+        state::AccountInfo,
+    };
+
+    const CALLER: Address = address!("0000000000000000000000000000000000000a11");
+    const RECIPIENT: Address = address!("0000000000000000000000000000000000000b0b");
+    const COINBASE: Address = address!("0000000000000000000000000000000000000c0c");
+
+    fn l1_info_deposit() -> OpTransaction<TxEnv> {
+        OpTransaction::Deposit(TxDeposit {
+            from: L1_INFO_DEPOSITOR,
+            to: TxKind::Call(L1_BLOCK_CONTRACT),
+            gas_limit: 50_000,
+            ..Default::default()
+        })
+    }
+
+    fn executor(spec_id: MantleSpecId) -> MantleBlockExecutor<InMemoryDB> {
+        predeploys_executor(Predeploys::new(spec_id).with_l1_block_info(L1BlockInfo {
+            token_ratio: Some(U256::from(1)),
+            ..Default::default()
+        }))
+    }
+
+    fn predeploys_executor(predeploys: Predeploys) -> MantleBlockExecutor<InMemoryDB> {
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(
+            CALLER,
+            AccountInfo {
+                balance: U256::from(1_000_000_000),
+                ..Default::default()
+            },
+        );
+        predeploys.commit(&mut db).unwrap();
+        MantleBlockExecutor::new(chain_spec::mainnet(), db)
+    }
+
+    fn block(transactions: Vec<OpTransaction<TxEnv>>) -> MantleBlock {
+        MantleBlock {
+            env: BlockEnv {
+                number: U256::from(1),
+                timestamp: U256::from(1),
+                coinbase: COINBASE,
+                gas_limit: U256::from(30_000_000),
+                basefee: U256::from(1),
+                ..Default::default()
+            },
+            parent_beacon_block_root: None,
+            transactions,
+        }
+    }
+
+    #[test]
+    fn test_execute_block() {
+        let mut executor = executor(MantleSpecId::REGOLITH);
+        let output = executor
+            .execute_block(&block(vec![
+                l1_info_deposit(),
+                OpTransaction::Deposit(TxDeposit {
+                    from: CALLER,
+                    to: TxKind::Call(RECIPIENT),
+                    mint: Some(100),
+                    value: U256::from(100),
+                    gas_limit: 100_000,
+                    ..Default::default()
+                }),
+                OpTransaction::Base {
+                    tx: TxEnv {
+                        caller: CALLER,
+                        gas_limit: 100_000,
+                        gas_price: U256::from(1),
+                        transact_to: TxKind::Call(RECIPIENT),
+                        value: U256::from(10),
+                        nonce: 1,
+                        chain_id: Some(MANTLE_MAINNET_CHAIN_ID),
+                        ..Default::default()
+                    },
+                    enveloped_tx: Some(bytes!("FACADE")),
+                },
+            ]))
+            .unwrap();
+
+        let receipts = &output.receipts;
+        assert_eq!(receipts.len(), 3);
+        assert!(receipts.iter().all(|receipt| receipt.success));
+        assert_eq!(receipts[0].cumulative_gas_used, 21_000);
+        assert_eq!(receipts[1].cumulative_gas_used, 42_000);
+        assert_eq!(receipts[2].cumulative_gas_used, 63_000);
+        assert_eq!(output.gas_used, 63_000);
+
+        assert_eq!(receipts[1].tx_type, OpTransactionType::Deposit);
+        assert_eq!(
+            receipts[1].deposit_receipt,
+            Some(MantleDepositReceipt {
+                deposit_nonce: Some(0),
+                deposit_receipt_version: None,
+            })
+        );
+        assert_eq!(receipts[1].fee_receipt, None);
+        assert_eq!(receipts[2].deposit_receipt, None);
+        assert!(receipts[2].fee_receipt.is_some());
+        assert_eq!(receipts[2].logs_bloom(), Bloom::ZERO);
+
+        let recipient = output.bundle.account(&RECIPIENT).unwrap();
+        assert_eq!(recipient.info.as_ref().unwrap().balance, U256::from(110));
+        assert_eq!(output.bundle.reverts.len(), 1);
+    }
+
+    #[test]
+    fn test_l1_info_deposit_sets_l1_cost() {
+        // Stores the L1 base fee argument of `setL1BlockValues`:
+        // PUSH1 0x44 CALLDATALOAD PUSH1 1 SSTORE STOP
+        let code = Bytecode::new_raw(bytes!("60443560015500"));
+        let l1_fee = |l1_base_fee: u64| {
+            let mut executor = predeploys_executor(
+                Predeploys::new(MantleSpecId::REGOLITH)
+                    .with_l1_block_info(L1BlockInfo {
+                        l1_base_fee: U256::from(1),
+                        l1_base_fee_scalar: U256::from(1_000_000),
+                        token_ratio: Some(U256::from(1)),
+                        ..Default::default()
+                    })
+                    .with_code(L1_BLOCK_CONTRACT, code.clone()),
+            );
+
+            let mut input = L1_BLOCK_BEDROCK_SELECTOR.to_vec();
+            input.extend([0u8; 8 * 32]);
+            input[4 + 2 * 32..4 + 3 * 32]
+                .copy_from_slice(&U256::from(l1_base_fee).to_be_bytes::<32>());
+            let output = executor
+                .execute_block(&block(vec![
+                    OpTransaction::Deposit(TxDeposit {
+                        from: L1_INFO_DEPOSITOR,
+                        to: TxKind::Call(L1_BLOCK_CONTRACT),
+                        gas_limit: 100_000,
+                        input: input.into(),
+                        ..Default::default()
+                    }),
+                    OpTransaction::Base {
+                        tx: TxEnv {
+                            caller: CALLER,
+                            gas_limit: 100_000,
+                            gas_price: U256::from(1),
+                            transact_to: TxKind::Call(RECIPIENT),
+                            chain_id: Some(MANTLE_MAINNET_CHAIN_ID),
+                            ..Default::default()
+                        },
+                        enveloped_tx: Some(bytes!("FACADE")),
+                    },
+                ]))
+                .unwrap();
+
+            let fee_receipt = output.receipts[1].fee_receipt.clone().unwrap();
+            assert_eq!(fee_receipt.l1_gas_price, U256::from(l1_base_fee));
+            let l1_fee_vault = output.bundle.account(&L1_FEE_RECIPIENT).unwrap();
+            assert_eq!(
+                l1_fee_vault.info.as_ref().unwrap().balance,
+                fee_receipt.l1_fee
+            );
+            fee_receipt.l1_fee
+        };
+
+        let l1_fee_1000 = l1_fee(1_000);
+        assert!(!l1_fee_1000.is_zero());
+        assert_eq!(l1_fee(3_000), l1_fee_1000 * U256::from(3));
+    }
+
+    #[test]
+    fn test_missing_l1_info_deposit() {
+        let mut executor = executor(MantleSpecId::REGOLITH);
+        for transactions in [
+            vec![],
+            vec![OpTransaction::Deposit(TxDeposit {
+                from: CALLER,
+                to: TxKind::Call(L1_BLOCK_CONTRACT),
+                gas_limit: 1_000_000,
+                ..Default::default()
+            })],
+            vec![
+                OpTransaction::Base {
+                    tx: TxEnv {
+                        caller: CALLER,
+                        gas_limit: 100_000,
+                        gas_price: U256::from(1),
+                        chain_id: Some(MANTLE_MAINNET_CHAIN_ID),
+                        ..Default::default()
+                    },
+                    enveloped_tx: Some(bytes!("FACADE")),
+                },
+                l1_info_deposit(),
+            ],
+            vec![OpTransaction::Deposit(TxDeposit {
+                from: L1_INFO_DEPOSITOR,
+                to: TxKind::Call(RECIPIENT),
+                gas_limit: 1_000_000,
+                ..Default::default()
+            })],
+        ] {
+            assert_eq!(
+                executor.execute_block(&block(transactions)),
+                Err(MantleBlockExecutionError::MissingL1InfoDeposit)
+            );
+        }
+    }
+
+    #[test]
+    fn test_transaction_gas_limit_more_than_available_block_gas() {
+        let mut executor = executor(MantleSpecId::REGOLITH);
+        let mut block = block(vec![
+            l1_info_deposit(),
+            OpTransaction::Base {
+                tx: TxEnv {
+                    caller: CALLER,
+                    gas_limit: 100_000,
+                    gas_price: U256::from(1),
+                    chain_id: Some(MANTLE_MAINNET_CHAIN_ID),
+                    ..Default::default()
+                },
+                enveloped_tx: Some(bytes!("FACADE")),
+            },
+        ]);
+        block.env.gas_limit = U256::from(100_000);
+
+        assert_eq!(
+            executor.execute_block(&block),
+            Err(
+                MantleBlockExecutionError::TransactionGasLimitMoreThanAvailableBlockGas {
+                    index: 1,
+                    transaction_gas_limit: 100_000,
+                    block_available_gas: 79_000,
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn test_beacon_root_contract_call() {
+        let timestamp = 1_800_000_000u64;
+        let root = b256!("1111111111111111111111111111111111111111111111111111111111111111");
+        let mut block = block(vec![l1_info_deposit()]);
+        block.env.timestamp = U256::from(timestamp);
+        block.env.set_blob_excess_gas_and_price(0);
+
+        let beacon_roots_executor = || {
+            // PUSH1 0 CALLDATALOAD TIMESTAMP SSTORE STOP
+            let code = Bytecode::new_raw(bytes!("600035425500"));
+            let mut executor = executor(MantleSpecId::EVEREST);
+            executor.state_mut().insert_account(
+                BEACON_ROOTS_ADDRESS,
+                AccountInfo {
+                    code_hash: code.hash_slow(),
+                    code: Some(code),
+                    ..Default::default()
+                },
+            );
+            executor
+        };
+
+        assert_eq!(
+            beacon_roots_executor().execute_block(&block),
+            Err(MantleBlockExecutionError::MissingParentBeaconBlockRoot)
+        );
+
+        block.parent_beacon_block_root = Some(root);
+        let output = beacon_roots_executor().execute_block(&block).unwrap();
+        let beacon_roots = output.bundle.account(&BEACON_ROOTS_ADDRESS).unwrap();
+        assert_eq!(
+            beacon_roots.storage_slot(U256::from(timestamp)),
+            Some(U256::from_be_bytes(root.0))
+        );
+        assert!(output.bundle.account(&SYSTEM_ADDRESS).is_none());
+        assert!(output.bundle.account(&COINBASE).is_none());
+    }
+}
@@ -1,25 +1,26 @@
 //! Handler related to Mantle chain
 
-use crate::BASE_FEE_RECIPIENT;
 use crate::{
+    bvm_eth::{self, BVM_ETH_ADDRESS},
     mantle_spec_to_generic,
     transaction::{
-        deposit::DepositTransaction, error::OpTransactionError, OpTransactionType, OpTxTrait,
+        deposit::{DepositTransaction, MantleDepositTransaction},
+        error::OpTransactionError,
+        OpTransactionType, OpTxTrait,
     },
     wiring::{MantleContextTrait, MantleWiring},
-    MantleHaltReason, MantleSpec, MantleSpecId,
+    MantleDepositReceipt, MantleFeeReceipt, MantleHaltReason, MantleSpec, MantleSpecId,
+    TokenRatioGasModel,
 };
-use core::ops::Mul;
+use op_stack::handler::{self, DepositGas};
 use revm::{
-    database_interface::Database,
     handler::{
-        mainnet::{self, deduct_caller_inner, validate_block_env, validate_tx_env},
+        mainnet::{self, validate_block_env, validate_tx_env},
         register::EvmHandler,
     },
-    interpreter::{as_u64_saturated, return_ok, return_revert, Gas},
-    precompile::{secp256r1, PrecompileSpecId},
-    primitives::{HashMap, U256},
-    state::Account,
+    interpreter::Gas,
+    precompile::PrecompileSpecId,
+    primitives::{HashMap, TxKind, U256},
     transaction::CommonTxFields,
     wiring::{
         default::EnvWiring,
@@ -109,28 +110,55 @@ pub fn validate_initial_tx_gas<EvmWiringT: MantleWiring, SPEC: MantleSpec>(
 where
     <EvmWiringT::Transaction as Transaction>::TransactionError: From<InvalidTransaction>,
 {
+    *context.evm.chain.fee_receipt_mut() = None;
+    *context.evm.chain.deposit_receipt_mut() = None;
     let mut initial_gas_spend = mainnet::validate_initial_tx_gas::<EvmWiringT, SPEC>(context)?;
 
     // If the transaction is not a deposit transaction, it cannot be a system transaction.
     if context.evm.env.tx.tx_type() != OpTransactionType::Deposit {
-        let l1_block_info =
-            super::L1BlockInfo::try_fetch(&mut context.evm.inner.db, SPEC::MANTLE_SPEC_ID)
-                .map_err(EVMError::Database)?;
+        let l1_block_info = match context.evm.chain.l1_block_info() {
+            Some(l1_block_info) if context.evm.chain.is_l1_block_info_fixed() => {
+                l1_block_info.clone()
+            }
+            _ => super::L1BlockInfo::try_fetch(&mut context.evm.inner.db, SPEC::MANTLE_SPEC_ID)
+                .map_err(EVMError::Database)?,
+        };
 
-        // storage l1 block info for later use.
-        *context.evm.chain.l1_block_info_mut() = Some(l1_block_info.clone());
+        let gas_model =
+            TokenRatioGasModel::new(SPEC::MANTLE_SPEC_ID, l1_block_info.get_token_ratio());
+        initial_gas_spend = gas_model
+            .initial_gas(
+                initial_gas_spend,
+                context.evm.env.tx.common_fields().gas_limit(),
+            )
+            .map_err(|e| EVMError::Transaction(e.into()))?;
 
-        let toeken_ratio = l1_block_info.get_token_ratio();
-        initial_gas_spend = initial_gas_spend
-            .checked_mul(as_u64_saturated!(toeken_ratio))
-            .ok_or(EVMError::Transaction(
-                InvalidTransaction::CallGasCostMoreThanGasLimit.into(),
-            ))?;
+        // storage l1 block info for later use.
+        *context.evm.chain.l1_block_info_mut() = Some(l1_block_info);
     }
 
     Ok(initial_gas_spend)
 }
 
+/// Returns the token ratio gas model of the current transaction.
+///
+/// Non-deposit transactions expect the L1 block info to be loaded in
+/// [validate_initial_tx_gas].
+fn token_ratio_gas_model<EvmWiringT: MantleWiring, SPEC: MantleSpec>(
+    context: &Context<EvmWiringT>,
+) -> TokenRatioGasModel {
+    if context.evm.env.tx.tx_type() == OpTransactionType::Deposit {
+        return TokenRatioGasModel::deposit(SPEC::MANTLE_SPEC_ID);
+    }
+    let token_ratio = context
+        .evm
+        .chain
+        .l1_block_info()
+        .expect("L1BlockInfo should be loaded")
+        .get_token_ratio();
+    TokenRatioGasModel::new(SPEC::MANTLE_SPEC_ID, token_ratio)
+}
+
 /// Handle output of the transaction
 #[inline]
 pub fn last_frame_return<EvmWiringT: MantleWiring, SPEC: MantleSpec>(
@@ -138,65 +166,19 @@ pub fn last_frame_return<EvmWiringT: MantleWiring, SPEC: MantleSpec>(
     frame_result: &mut FrameResult,
 ) -> EVMResultGeneric<(), EvmWiringT> {
     let env = context.evm.inner.env();
-    let is_deposit = env.tx.tx_type() == OpTransactionType::Deposit;
+    let deposit = (env.tx.tx_type() == OpTransactionType::Deposit).then(|| DepositGas {
+        is_system_transaction: env.tx.deposit().is_system_transaction(),
+    });
     let tx_gas_limit = env.tx.common_fields().gas_limit();
-    let is_regolith = SPEC::mantle_enabled(MantleSpecId::REGOLITH);
 
     let instruction_result = frame_result.interpreter_result().result;
-    let gas = frame_result.gas_mut();
-    let remaining = gas.remaining();
-    let refunded = gas.refunded();
-    // Spend the gas limit. Gas is reimbursed when the tx returns successfully.
-    *gas = Gas::new_spent(tx_gas_limit);
-
-    match instruction_result {
-        return_ok!() => {
-            // On Mantle, deposit transactions report gas usage uniquely to other
-            // transactions due to them being pre-paid on L1.
-            //
-            // Hardfork Behavior:
-            // - Bedrock (success path):
-            //   - Deposit transactions (non-system) report their gas limit as the usage.
-            //     No refunds.
-            //   - Deposit transactions (system) report 0 gas used. No refunds.
-            //   - Regular transactions report gas usage as normal.
-            // - Regolith (success path):
-            //   - Deposit transactions (all) report their gas used as normal. Refunds
-            //     enabled.
-            //   - Regular transactions report their gas used as normal.
-            if !is_deposit || is_regolith {
-                // For regular transactions prior to Regolith and all transactions after
-                // Regolith, gas is reported as normal.
-                gas.erase_cost(remaining);
-                gas.record_refund(refunded);
-            } else if is_deposit {
-                let tx = env.tx.deposit();
-                if tx.is_system_transaction() {
-                    // System transactions were a special type of deposit transaction in
-                    // the Bedrock hardfork that did not incur any gas costs.
-                    gas.erase_cost(tx_gas_limit);
-                }
-            }
-        }
-        return_revert!() => {
-            // On Mantle, deposit transactions report gas usage uniquely to other
-            // transactions due to them being pre-paid on L1.
-            //
-            // Hardfork Behavior:
-            // - Bedrock (revert path):
-            //   - Deposit transactions (all) report the gas limit as the amount of gas
-            //     used on failure. No refunds.
-            //   - Regular transactions receive a refund on remaining gas as normal.
-            // - Regolith (revert path):
-            //   - Deposit transactions (all) report the actual gas used as the amount of
-            //     gas used on failure. Refunds on remaining gas enabled.
-            //   - Regular transactions receive a refund on remaining gas as normal.
-            if !is_deposit || is_regolith {
-                gas.erase_cost(remaining);
-            }
-        }
-        _ => {}
-    }
+    handler::last_frame_gas(
+        SPEC::MANTLE_SPEC_ID,
+        frame_result.gas_mut(),
+        instruction_result,
+        tx_gas_limit,
+        deposit,
+    );
     Ok(())
 }
 
@@ -210,14 +192,12 @@ pub fn refund<EvmWiringT: MantleWiring, SPEC: MantleSpec>(
     gas.record_refund(eip7702_refund);
 
     let env = context.evm.inner.env();
-    let is_deposit = env.tx.tx_type() == OpTransactionType::Deposit;
-    let is_regolith = SPEC::mantle_enabled(MantleSpecId::REGOLITH);
-
-    // Prior to Regolith, deposit transactions did not receive gas refunds.
-    let is_gas_refund_disabled = env.cfg.is_gas_refund_disabled() || (is_deposit && !is_regolith);
-    if !is_gas_refund_disabled {
-        gas.set_final_refund(SPEC::MANTLE_SPEC_ID.is_enabled_in(MantleSpecId::LONDON));
-    }
+    handler::final_refund(
+        SPEC::MANTLE_SPEC_ID,
+        gas,
+        env.tx.tx_type() == OpTransactionType::Deposit,
+        env.cfg.is_gas_refund_disabled(),
+    );
 }
 
 /// Load precompiles for Mantle chain.
@@ -225,18 +205,12 @@ pub fn refund<EvmWiringT: MantleWiring, SPEC: MantleSpec>(
 pub fn load_precompiles<EvmWiringT: MantleWiring, SPEC: MantleSpec>(
 ) -> ContextPrecompiles<EvmWiringT> {
     let mut precompiles = ContextPrecompiles::new(PrecompileSpecId::from_spec_id(SPEC::SPEC_ID));
+    handler::extend_precompiles(&mut precompiles, SPEC::MANTLE_SPEC_ID);
 
-    if SPEC::mantle_enabled(MantleSpecId::FJORD) {
+    if SPEC::mantle_enabled(MantleSpecId::LIMB) {
         precompiles.extend([
-            // EIP-7212: secp256r1 P256verify
-            secp256r1::P256VERIFY,
-        ])
-    }
-
-    if SPEC::mantle_enabled(MantleSpecId::GRANITE) {
-        precompiles.extend([
-            // Restrict bn256Pairing input size
-            crate::bn128::pair::GRANITE,
+            // EIP-7823: Restrict modexp input size
+            crate::modexp::LIMB,
         ])
     }
 
@@ -250,7 +224,7 @@ pub fn deduct_caller<EvmWiringT: MantleWiring, SPEC: MantleSpec>(
 ) -> EVMResultGeneric<(), EvmWiringT> {
     let caller = context.evm.inner.env.tx.common_fields().caller();
     // load caller's account.
-    let mut caller_account = context
+    let caller_account = context
         .evm
         .inner
         .journaled_state
@@ -259,19 +233,30 @@ pub fn deduct_caller<EvmWiringT: MantleWiring, SPEC: MantleSpec>(
 
     let is_deposit = context.evm.inner.env.tx.tx_type() == OpTransactionType::Deposit;
 
+    // The recipient of bridged BVM_ETH, the created address is derived from the nonce
+    // before it is bumped.
+    let eth_recipient = match context.evm.inner.env.tx.kind() {
+        TxKind::Call(to) => to,
+        TxKind::Create => caller.create(caller_account.info.nonce),
+    };
+
     // If the transaction is a deposit with a `mint` value, add the mint value
     // in wei to the caller's balance. This should be persisted to the database
     // prior to the rest of execution.
+    // The deposit receipt reports the nonce of the sender before it is bumped.
+    let caller_nonce = caller_account.info.nonce;
+
     if is_deposit {
-        let tx = context.evm.inner.env.tx.deposit();
-        if let Some(mint) = tx.mint() {
-            caller_account.info.balance += U256::from(mint);
-        }
+        handler::mint_deposit(
+            caller_account.data,
+            context.evm.inner.env.tx.deposit().mint(),
+        );
     }
 
     // We deduct caller max balance after minting and before deducing the
     // l1 cost, max values is already checked in pre_validate but l1 cost wasn't.
-    deduct_caller_inner::<EvmWiringT, SPEC>(caller_account.data, &context.evm.inner.env);
+    // The token ratio only scales the gas, so the gas price is charged unscaled.
+    mainnet::deduct_caller_inner::<EvmWiringT, SPEC>(caller_account.data, &context.evm.inner.env);
 
     // If the transaction is not a deposit transaction, subtract the L1 data fee from the
     // caller's balance directly after minting the requested amount of ETH.
@@ -292,48 +277,50 @@ pub fn deduct_caller<EvmWiringT: MantleWiring, SPEC: MantleSpec>(
             .l1_block_info()
             .expect("L1BlockInfo should be loaded")
             .calculate_tx_l1_cost(enveloped_tx, SPEC::MANTLE_SPEC_ID);
-        if tx_l1_cost.gt(&caller_account.info.balance) {
-            return Err(EVMError::Transaction(
-                InvalidTransaction::LackOfFundForMaxFee {
-                    fee: tx_l1_cost.into(),
-                    balance: caller_account.info.balance.into(),
-                }
-                .into(),
-            ));
+        handler::deduct_l1_cost(caller_account.data, tx_l1_cost)
+            .map_err(|error| EVMError::Transaction(error.into()))?;
+    }
+
+    // If the deposit bridges ETH, mint it as BVM_ETH to the caller and transfer the
+    // requested amount to the recipient. Like the `mint` value, this is persisted even
+    // if the execution reverts.
+    if is_deposit {
+        let tx = context.evm.inner.env.tx.deposit();
+        let eth_value = tx.eth_value().unwrap_or_default();
+        let eth_tx_value = tx.eth_tx_hash().unwrap_or_default();
+        let journaled_state = &mut context.evm.inner.journaled_state;
+        let db = &mut context.evm.inner.db;
+        if eth_value != 0 {
+            bvm_eth::mint(journaled_state, db, caller, U256::from(eth_value))
+                .map_err(EVMError::Database)?;
         }
-        caller_account.info.balance = caller_account.info.balance.saturating_sub(tx_l1_cost);
+        if eth_tx_value != 0 {
+            bvm_eth::transfer(
+                journaled_state,
+                db,
+                caller,
+                eth_recipient,
+                U256::from(eth_tx_value),
+            )
+            .map_err(EVMError::Database)?;
+        }
+        *context.evm.inner.chain.deposit_receipt_mut() = Some(MantleDepositReceipt::new(
+            SPEC::MANTLE_SPEC_ID,
+            caller_nonce,
+        ));
     }
     Ok(())
 }
 
+/// Reimburse the caller for the unused and refunded gas.
+///
+/// The gas is already scaled by the token ratio, so this is the mainnet reimbursement.
 #[inline]
 pub fn reimburse_caller<EvmWiringT: MantleWiring, SPEC: MantleSpec>(
     context: &mut Context<EvmWiringT>,
     gas: &Gas,
 ) -> EVMResultGeneric<(), EvmWiringT> {
-    let caller = context.evm.env.tx.common_fields().caller();
-    let effective_gas_price = context.evm.env.effective_gas_price();
-
-    let token_ratio = context
-        .evm
-        .chain
-        .l1_block_info()
-        .expect("L1BlockInfo should be loaded")
-        .get_token_ratio();
-
-    // return balance of not spend gas.
-    let caller_account = context
-        .evm
-        .inner
-        .journaled_state
-        .load_account(caller, &mut context.evm.inner.db)
-        .map_err(EVMError::Database)?;
-
-    caller_account.data.info.balance = caller_account.data.info.balance.saturating_add(
-        effective_gas_price * U256::from(gas.remaining() + gas.refunded() as u64) * token_ratio,
-    );
-
-    Ok(())
+    mainnet::reimburse_caller::<EvmWiringT>(context, gas)
 }
 
 /// Reward beneficiary with gas fee.
@@ -344,53 +331,63 @@ pub fn reward_beneficiary<EvmWiringT: MantleWiring, SPEC: MantleSpec>(
 ) -> EVMResultGeneric<(), EvmWiringT> {
     let is_deposit = context.evm.inner.env.tx.tx_type() == OpTransactionType::Deposit;
 
-    // transfer fee to coinbase/beneficiary.
     if !is_deposit {
+        // transfer the priority fee to coinbase/beneficiary.
         mainnet::reward_beneficiary::<EvmWiringT, SPEC>(context, gas)?;
-    }
 
-    if !is_deposit {
+        // EIP-1559 discard basefee for coinbase transfer, it is paid to the Base Fee Vault instead.
+        let gas_model = token_ratio_gas_model::<EvmWiringT, SPEC>(context);
+        let basefee = *context.evm.env.block.basefee();
+        let effective_gas_price = context.evm.env.effective_gas_price();
+        let coinbase_gas_price = if SPEC::mantle_enabled(MantleSpecId::LONDON) {
+            effective_gas_price.saturating_sub(basefee)
+        } else {
+            effective_gas_price
+        };
+        let priority_fee = gas_model.gas_fee(gas, coinbase_gas_price);
+
         // If the transaction is not a deposit transaction, fees are paid out
         // to both the Base Fee Vault as well as the L1 Fee Vault.
-        // let l1_block_info = context
-        //     .evm
-        //     .chain
-        //     .l1_block_info()
-        //     .expect("L1BlockInfo should be loaded");
-
-        // let Some(enveloped_tx) = &context.evm.inner.env.tx.enveloped_tx() else {
-        //     return Err(EVMError::Custom(
-        //         "[MANTLE] Failed to load enveloped transaction.".into(),
-        //     ));
-        // };
-
-        // let l1_cost = l1_block_info.calculate_tx_l1_cost(enveloped_tx, SPEC::MANTLE_SPEC_ID);
-
-        // // Send the L1 cost of the transaction to the L1 Fee Vault.
-        // let mut l1_fee_vault_account = context
-        //     .evm
-        //     .inner
-        //     .journaled_state
-        //     .load_account(L1_FEE_RECIPIENT, &mut context.evm.inner.db)
-        //     .map_err(EVMError::Database)?;
-        // l1_fee_vault_account.mark_touch();
-        // l1_fee_vault_account.info.balance += l1_cost;
+        let l1_block_info = context
+            .evm
+            .chain
+            .l1_block_info()
+            .expect("L1BlockInfo should be loaded");
+
+        let Some(enveloped_tx) = &context.evm.inner.env.tx.enveloped_tx() else {
+            return Err(EVMError::Custom(
+                "[MANTLE] Failed to load enveloped transaction.".into(),
+            ));
+        };
+
+        let l1_cost = l1_block_info.calculate_tx_l1_cost(enveloped_tx, SPEC::MANTLE_SPEC_ID);
+        let base_fee = gas_model.gas_fee(gas, basefee);
+        let fee_receipt = MantleFeeReceipt {
+            l1_fee: l1_cost,
+            l1_gas_used: l1_block_info.tx_l1_gas_used(enveloped_tx, SPEC::MANTLE_SPEC_ID),
+            l1_gas_price: l1_block_info.l1_base_fee,
+            l1_fee_scalar: l1_block_info.l1_base_fee_scalar,
+            l1_blob_base_fee: l1_block_info.l1_blob_base_fee,
+            l1_blob_base_fee_scalar: l1_block_info.l1_blob_base_fee_scalar,
+            token_ratio: gas_model.token_ratio(),
+            base_fee,
+            priority_fee,
+        };
+        let l1_fee_recipient = context.evm.chain.l1_fee_recipient();
+        let base_fee_recipient = context.evm.chain.base_fee_recipient();
+
+        let journaled_state = &mut context.evm.inner.journaled_state;
+        let db = &mut context.evm.inner.db;
+
+        // Send the L1 cost of the transaction to the L1 Fee Vault.
+        handler::pay_fee_vault(journaled_state, db, l1_fee_recipient, l1_cost)
+            .map_err(EVMError::Database)?;
 
         // Send the base fee of the transaction to the Base Fee Vault.
-        let mut base_fee_vault_account = context
-            .evm
-            .inner
-            .journaled_state
-            .load_account(BASE_FEE_RECIPIENT, &mut context.evm.inner.db)
+        handler::pay_fee_vault(journaled_state, db, base_fee_recipient, base_fee)
             .map_err(EVMError::Database)?;
-        base_fee_vault_account.mark_touch();
-        base_fee_vault_account.info.balance += context
-            .evm
-            .inner
-            .env
-            .block
-            .basefee()
-            .mul(U256::from(gas.spent() - gas.refunded() as u64));
+
+        *context.evm.chain.fee_receipt_mut() = Some(fee_receipt);
     }
     Ok(())
 }
@@ -408,7 +405,7 @@ pub fn output<EvmWiringT: MantleWiring, SPEC: MantleSpec>(
         // we bubble up to the global return handler. The mint value will be persisted
         // and the caller nonce will be incremented there.
         let is_deposit = context.evm.inner.env.tx.tx_type() == OpTransactionType::Deposit;
-        if is_deposit && SPEC::mantle_enabled(MantleSpecId::REGOLITH) {
+        if is_deposit && handler::is_halted_deposit_error(SPEC::MANTLE_SPEC_ID) {
             return Err(EVMError::Transaction(
                 OpTransactionError::HaltedDepositPostRegolith,
             ));
@@ -424,6 +421,10 @@ pub fn end<EvmWiringT: MantleWiring, SPEC: MantleSpec>(
     evm_output: EVMResult<EvmWiringT>,
 ) -> EVMResult<EvmWiringT> {
     let is_deposit = context.evm.inner.env.tx.tx_type() == OpTransactionType::Deposit;
+    if evm_output.is_err() {
+        *context.evm.chain.fee_receipt_mut() = None;
+        *context.evm.chain.deposit_receipt_mut() = None;
+    }
     evm_output.or_else(|err| {
         if matches!(err, EVMError::Transaction(_)) && is_deposit {
             let tx = context.evm.inner.env.tx.deposit();
@@ -436,36 +437,44 @@ pub fn end<EvmWiringT: MantleWiring, SPEC: MantleSpec>(
 
             // Increment sender nonce and account balance for the mint amount. Deposits
             // always persist the mint amount, even if the transaction fails.
-            let account = {
-                let mut acc = Account::from(
-                    context
-                        .evm
-                        .inner
-                        .db
-                        .basic(tx.caller())
-                        .unwrap_or_default()
-                        .unwrap_or_default(),
-                );
-                acc.info.nonce = acc.info.nonce.saturating_add(1);
-                acc.info.balance = acc
-                    .info
-                    .balance
-                    .saturating_add(U256::from(tx.mint().unwrap_or_default()));
-                acc.mark_touch();
-                acc
-            };
-            let state = HashMap::from_iter([(tx.caller(), account)]);
+            let (account, deposit_nonce) =
+                handler::failed_deposit_caller(&mut context.evm.inner.db, tx.caller(), tx.mint())
+                    .map_err(EVMError::Database)?;
+            let mut state = HashMap::from_iter([(tx.caller(), account)]);
+
+            // Bridged ETH is minted as BVM_ETH and transferred to the recipient even if the
+            // deposit fails.
+            let eth_value = tx.eth_value().unwrap_or_default();
+            let eth_tx_value = tx.eth_tx_hash().unwrap_or_default();
+            if eth_value != 0 || eth_tx_value != 0 {
+                let eth_recipient = match tx.to() {
+                    TxKind::Call(to) => to,
+                    TxKind::Create => tx.caller().create(deposit_nonce),
+                };
+                let bvm_eth_account = bvm_eth::failed_deposit_account(
+                    &mut context.evm.inner.db,
+                    tx.caller(),
+                    eth_recipient,
+                    U256::from(eth_value),
+                    U256::from(eth_tx_value),
+                )
+                .map_err(EVMError::Database)?;
+                state.insert(BVM_ETH_ADDRESS, bvm_eth_account);
+            }
 
             // The gas used of a failed deposit post-regolith is the gas
             // limit of the transaction. pre-regolith, it is the gas limit
             // of the transaction for non system transactions and 0 for system
             // transactions.
-            let gas_used =
-                if SPEC::mantle_enabled(MantleSpecId::REGOLITH) || !tx.is_system_transaction() {
-                    tx.gas_limit()
-                } else {
-                    0
-                };
+            let gas_used = handler::failed_deposit_gas_used(
+                SPEC::MANTLE_SPEC_ID,
+                tx.gas_limit(),
+                tx.is_system_transaction(),
+            );
+            *context.evm.inner.chain.deposit_receipt_mut() = Some(MantleDepositReceipt::new(
+                SPEC::MANTLE_SPEC_ID,
+                deposit_nonce,
+            ));
 
             Ok(ResultAndState {
                 result: ExecutionResult::Halt {
@@ -484,18 +493,22 @@ pub fn end<EvmWiringT: MantleWiring, SPEC: MantleSpec>(
 mod tests {
     use super::*;
     use crate::{
-        transaction::deposit::TxDeposit, wiring::MantleEvmWiring, BedrockSpec, L1BlockInfo,
-        LatestSpec, OpTransaction, RegolithSpec,
+        transaction::deposit::TxDeposit,
+        wiring::{Context as MantleContext, MantleEvmWiring},
+        BedrockSpec, L1BlockInfo, LatestSpec, MantleResultAndState, OpTransaction, Predeploys,
+        RegolithSpec, SkadiSpec, BASE_FEE_RECIPIENT, DEPOSIT_RECEIPT_VERSION, L1_FEE_RECIPIENT,
     };
     use database::InMemoryDB;
     use revm::{
-        database_interface::EmptyDB,
+        bytecode::Bytecode,
+        database_interface::{Database, EmptyDB},
         interpreter::{CallOutcome, InstructionResult, InterpreterResult},
-        primitives::{bytes, Address, Bytes, B256},
+        primitives::{address, bytes, Address, Bytes, B256},
         state::AccountInfo,
         wiring::default::{block::BlockEnv, Env, TxEnv},
+        Evm,
     };
-    use std::boxed::Box;
+    use std::{boxed::Box, vec::Vec};
 
     type TestEmptyOpWiring = MantleEvmWiring<EmptyDB, ()>;
     type TestMemOpWiring = MantleEvmWiring<InMemoryDB, ()>;
@@ -769,6 +782,655 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_reward_fee_vaults() {
+        let mut context = Context::<TestMemOpWiring>::new_with_db(InMemoryDB::default());
+        *context.evm.chain.l1_block_info_mut() = Some(L1BlockInfo {
+            l1_base_fee: U256::from(1_000),
+            l1_fee_overhead: Some(U256::from(1_000)),
+            l1_base_fee_scalar: U256::from(1_000),
+            ..Default::default()
+        });
+        context.evm.inner.env.block.basefee = U256::from(2);
+        context.evm.inner.env.tx = OpTransaction::Base {
+            tx: TxEnv::default(),
+            enveloped_tx: Some(bytes!("FACADE")),
+        };
+
+        let mut gas = Gas::new(100);
+        assert!(gas.record_cost(30));
+        reward_beneficiary::<TestMemOpWiring, RegolithSpec>(&mut context, &gas).unwrap();
+
+        let mut balance = |address| {
+            context
+                .evm
+                .inner
+                .journaled_state
+                .load_account(address, &mut context.evm.inner.db)
+                .unwrap()
+                .info
+                .balance
+        };
+        // l1block cost is 1048 fee.
+        assert_eq!(balance(L1_FEE_RECIPIENT), U256::from(1048));
+        assert_eq!(balance(BASE_FEE_RECIPIENT), U256::from(60));
+    }
+
+    #[test]
+    fn test_reward_custom_fee_vaults() {
+        let l1_vault = address!("0000000000000000000000000000000000001a1a");
+        let base_vault = address!("0000000000000000000000000000000000001919");
+        let mut context = Context::<TestMemOpWiring>::new_with_db(InMemoryDB::default());
+        context.evm.chain = MantleContext::default()
+            .with_l1_fee_recipient(l1_vault)
+            .with_base_fee_recipient(base_vault);
+        *context.evm.chain.l1_block_info_mut() = Some(L1BlockInfo {
+            l1_base_fee: U256::from(1_000),
+            l1_fee_overhead: Some(U256::from(1_000)),
+            l1_base_fee_scalar: U256::from(1_000),
+            ..Default::default()
+        });
+        context.evm.inner.env.block.basefee = U256::from(2);
+        context.evm.inner.env.tx = OpTransaction::Base {
+            tx: TxEnv::default(),
+            enveloped_tx: Some(bytes!("FACADE")),
+        };
+
+        let mut gas = Gas::new(100);
+        assert!(gas.record_cost(30));
+        reward_beneficiary::<TestMemOpWiring, RegolithSpec>(&mut context, &gas).unwrap();
+
+        let state = &context.evm.inner.journaled_state.state;
+        assert_eq!(state[&l1_vault].info.balance, U256::from(1048));
+        assert_eq!(state[&base_vault].info.balance, U256::from(60));
+        assert!(!state.contains_key(&L1_FEE_RECIPIENT));
+        assert!(!state.contains_key(&BASE_FEE_RECIPIENT));
+    }
+
+    #[rstest::rstest]
+    fn test_token_ratio_balance_conservation(
+        #[values(1, 2, 4_000)] token_ratio: u64,
+        #[values(1, 3)] basefee: u64,
+        #[values(3, 10)] gas_price: u64,
+        #[values(false, true)] clears_storage: bool,
+        #[values(false, true)] reverts: bool,
+    ) {
+        let caller = address!("0000000000000000000000000000000000000a11");
+        let contract = address!("0000000000000000000000000000000000000c0d");
+        let coinbase = address!("0000000000000000000000000000000000c0ffee");
+        let caller_balance = U256::from(10).pow(U256::from(18));
+
+        let mut code = Vec::new();
+        if clears_storage {
+            // PUSH1 0 PUSH1 0 SSTORE, refunds the cleared slot.
+            code.extend_from_slice(&[0x60, 0x00, 0x60, 0x00, 0x55]);
+        }
+        if reverts {
+            // PUSH1 0 PUSH1 0 REVERT
+            code.extend_from_slice(&[0x60, 0x00, 0x60, 0x00, 0xfd]);
+        }
+        let code = Bytecode::new_raw(code.into());
+
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(
+            caller,
+            AccountInfo {
+                balance: caller_balance,
+                ..Default::default()
+            },
+        );
+        db.insert_account_info(
+            contract,
+            AccountInfo {
+                code_hash: code.hash_slow(),
+                code: Some(code),
+                ..Default::default()
+            },
+        );
+        db.insert_account_storage(contract, U256::ZERO, U256::from(1))
+            .unwrap();
+        Predeploys::new(MantleSpecId::REGOLITH)
+            .with_l1_block_info(L1BlockInfo {
+                l1_base_fee: U256::from(1_000),
+                l1_fee_overhead: Some(U256::from(1_000)),
+                l1_base_fee_scalar: U256::from(1_000),
+                token_ratio: Some(U256::from(token_ratio)),
+                ..Default::default()
+            })
+            .commit(&mut db)
+            .unwrap();
+
+        let mut evm = Evm::<TestMemOpWiring>::builder()
+            .with_db(db)
+            .with_default_ext_ctx()
+            .with_spec_id(MantleSpecId::REGOLITH)
+            .modify_block_env(|block| {
+                block.coinbase = coinbase;
+                block.basefee = U256::from(basefee);
+            })
+            .with_tx_env(OpTransaction::Base {
+                tx: TxEnv {
+                    caller,
+                    gas_limit: 21_000 * token_ratio + 100_000,
+                    gas_price: U256::from(gas_price),
+                    transact_to: TxKind::Call(contract),
+                    ..Default::default()
+                },
+                enveloped_tx: Some(bytes!("FACADE")),
+            })
+            .build();
+
+        let MantleResultAndState {
+            result,
+            state,
+            fee_receipt,
+            deposit_receipt,
+        } = MantleResultAndState::transact(&mut evm).unwrap();
+        assert_eq!(deposit_receipt, None);
+        assert_eq!(result.is_success(), !reverts);
+        assert!(result.gas_used() >= 21_000 * token_ratio);
+
+        let balance = |address| {
+            state
+                .get(&address)
+                .map(|account| account.info.balance)
+                .unwrap_or_default()
+        };
+        let paid = caller_balance - balance(caller);
+        let l1_fee = balance(L1_FEE_RECIPIENT);
+        assert!(!l1_fee.is_zero());
+        assert_eq!(
+            paid,
+            balance(coinbase) + balance(BASE_FEE_RECIPIENT) + l1_fee
+        );
+        assert_eq!(
+            paid,
+            U256::from(result.gas_used()) * U256::from(gas_price) + l1_fee
+        );
+
+        let fee_receipt = fee_receipt.unwrap();
+        assert_eq!(fee_receipt.l1_fee, l1_fee);
+        assert_eq!(fee_receipt.base_fee, balance(BASE_FEE_RECIPIENT));
+        assert_eq!(fee_receipt.priority_fee, balance(coinbase));
+        assert_eq!(fee_receipt.token_ratio, U256::from(token_ratio));
+        // 3 non-zero bytes and the overhead.
+        assert_eq!(fee_receipt.l1_gas_used, U256::from(3 * 16 + 1_000));
+        assert_eq!(fee_receipt.l1_gas_price, U256::from(1_000));
+        assert_eq!(fee_receipt.l1_fee_scalar, U256::from(1_000));
+    }
+
+    #[test]
+    fn test_fee_receipt_cleared_for_deposits() {
+        let mut evm = Evm::<TestMemOpWiring>::builder()
+            .with_db(InMemoryDB::default())
+            .with_default_ext_ctx()
+            .with_spec_id(MantleSpecId::REGOLITH)
+            .with_tx_env(OpTransaction::Deposit(TxDeposit {
+                gas_limit: 100_000,
+                ..Default::default()
+            }))
+            .build();
+        *evm.context.evm.chain.fee_receipt_mut() = Some(MantleFeeReceipt::default());
+
+        let result = MantleResultAndState::transact(&mut evm).unwrap();
+        assert!(result.result.is_success());
+        assert_eq!(result.fee_receipt, None);
+        assert_eq!(evm.context.evm.chain.fee_receipt(), None);
+    }
+
+    #[test]
+    fn test_initial_gas_scaled_by_token_ratio() {
+        let mut db = InMemoryDB::default();
+        Predeploys::new(MantleSpecId::REGOLITH)
+            .with_token_ratio(U256::from(10))
+            .commit(&mut db)
+            .unwrap();
+        let mut context = Context::<TestMemOpWiring>::new_with_db(db);
+        context.evm.inner.env.tx = OpTransaction::Base {
+            tx: TxEnv {
+                gas_limit: 210_000,
+                ..Default::default()
+            },
+            enveloped_tx: Some(bytes!("FACADE")),
+        };
+        assert_eq!(
+            validate_initial_tx_gas::<TestMemOpWiring, RegolithSpec>(&mut context),
+            Ok(210_000)
+        );
+
+        context.evm.inner.env.tx = OpTransaction::Base {
+            tx: TxEnv {
+                gas_limit: 209_999,
+                ..Default::default()
+            },
+            enveloped_tx: Some(bytes!("FACADE")),
+        };
+        assert_eq!(
+            validate_initial_tx_gas::<TestMemOpWiring, RegolithSpec>(&mut context),
+            Err(EVMError::Transaction(
+                InvalidTransaction::CallGasCostMoreThanGasLimit.into()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_initial_gas_with_fixed_l1_block_info() {
+        // The database has a token ratio of 10, the fixed L1 block info one of 2.
+        let mut db = InMemoryDB::default();
+        Predeploys::new(MantleSpecId::REGOLITH)
+            .with_token_ratio(U256::from(10))
+            .commit(&mut db)
+            .unwrap();
+        let mut context = Context::<TestMemOpWiring>::new_with_db(db);
+        context.evm.chain = MantleContext::default()
+            .with_l1_block_info(L1BlockInfo::default().with_token_ratio(U256::from(2)));
+        context.evm.inner.env.tx = OpTransaction::Base {
+            tx: TxEnv {
+                gas_limit: 42_000,
+                ..Default::default()
+            },
+            enveloped_tx: Some(bytes!("FACADE")),
+        };
+        assert_eq!(
+            validate_initial_tx_gas::<TestMemOpWiring, RegolithSpec>(&mut context),
+            Ok(42_000)
+        );
+        assert_eq!(
+            context.evm.chain.l1_block_info().unwrap().token_ratio,
+            Some(U256::from(2))
+        );
+    }
+
+    #[test]
+    fn test_load_precompiles_skadi() {
+        let precompiles = load_precompiles::<TestEmptyOpWiring, SkadiSpec>();
+        // BLS12-381 G1ADD from Prague
+        assert!(precompiles.contains(&address!("000000000000000000000000000000000000000b")));
+        // No P256VERIFY, Skadi does not carry Fjord
+        assert!(!precompiles.contains(&address!("0000000000000000000000000000000000000100")));
+    }
+
+    #[rstest::rstest]
+    #[case::skadi(MantleSpecId::SKADI, true)]
+    #[case::limb(MantleSpecId::LIMB, false)]
+    fn test_modexp_input_bound(#[case] spec_id: MantleSpecId, #[case] success: bool) {
+        // base length of 1025 bytes, exponent and modulus are empty.
+        let mut input = [0u8; 96];
+        input[30..32].copy_from_slice(&1025u16.to_be_bytes());
+
+        let mut evm = Evm::<TestMemOpWiring>::builder()
+            .with_db(InMemoryDB::default())
+            .with_default_ext_ctx()
+            .with_spec_id(spec_id)
+            .with_tx_env(OpTransaction::Deposit(TxDeposit {
+                to: TxKind::Call(address!("0000000000000000000000000000000000000005")),
+                gas_limit: 100_000,
+                input: Bytes::copy_from_slice(&input),
+                ..Default::default()
+            }))
+            .build();
+
+        let result = evm.transact().unwrap().result;
+        assert_eq!(result.is_success(), success, "{result:?}");
+    }
+
+    #[test]
+    fn test_deposit_mint_and_transfer_bvm_eth() {
+        let caller = address!("0000000000000000000000000000000000000a11");
+        let recipient = address!("0000000000000000000000000000000000000b0b");
+        let mut db = InMemoryDB::default();
+        db.insert_account_storage(
+            BVM_ETH_ADDRESS,
+            bvm_eth::total_supply_slot(),
+            U256::from(1_000),
+        )
+        .unwrap();
+
+        let mut context = Context::<TestMemOpWiring>::new_with_db(db);
+        context.evm.inner.env.tx = OpTransaction::Deposit(TxDeposit {
+            from: caller,
+            to: TxKind::Call(recipient),
+            eth_value: Some(100),
+            eth_tx_hash: Some(40),
+            ..Default::default()
+        });
+
+        deduct_caller::<TestMemOpWiring, RegolithSpec>(&mut context).unwrap();
+
+        let bvm_eth = &context.evm.inner.journaled_state.state[&BVM_ETH_ADDRESS];
+        assert!(bvm_eth.is_touched());
+        let slot = |key: U256| bvm_eth.storage[&key].present_value;
+        assert_eq!(slot(bvm_eth::balance_slot(caller)), U256::from(60));
+        assert_eq!(slot(bvm_eth::balance_slot(recipient)), U256::from(40));
+        assert_eq!(slot(bvm_eth::total_supply_slot()), U256::from(1_100));
+
+        let logs = &context.evm.inner.journaled_state.logs;
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[0].topics()[0], bvm_eth::MINT_EVENT_TOPIC);
+        assert_eq!(logs[1].topics()[0], bvm_eth::TRANSFER_EVENT_TOPIC);
+        assert_eq!(logs[1].data.data, U256::from(40).to_be_bytes_vec());
+    }
+
+    #[test]
+    fn test_deposit_without_eth_value_does_not_touch_bvm_eth() {
+        let mut context = Context::<TestMemOpWiring>::new_with_db(InMemoryDB::default());
+        context.evm.inner.env.tx = OpTransaction::Deposit(TxDeposit {
+            mint: Some(10),
+            ..Default::default()
+        });
+
+        deduct_caller::<TestMemOpWiring, RegolithSpec>(&mut context).unwrap();
+
+        assert!(!context
+            .evm
+            .inner
+            .journaled_state
+            .state
+            .contains_key(&BVM_ETH_ADDRESS));
+        assert!(context.evm.inner.journaled_state.logs.is_empty());
+    }
+
+    #[test]
+    fn test_reverted_deposit_keeps_bvm_eth() {
+        let caller = address!("0000000000000000000000000000000000000a11");
+        let recipient = address!("0000000000000000000000000000000000000b0b");
+        let mut db = InMemoryDB::default();
+        // PUSH1 0 PUSH1 0 REVERT
+        let code = Bytecode::new_raw(bytes!("60006000fd"));
+        db.insert_account_info(
+            recipient,
+            AccountInfo {
+                code_hash: code.hash_slow(),
+                code: Some(code),
+                ..Default::default()
+            },
+        );
+
+        let mut evm = Evm::<TestMemOpWiring>::builder()
+            .with_db(db)
+            .with_default_ext_ctx()
+            .with_spec_id(MantleSpecId::REGOLITH)
+            .with_tx_env(OpTransaction::Deposit(TxDeposit {
+                from: caller,
+                to: TxKind::Call(recipient),
+                gas_limit: 100_000,
+                eth_value: Some(100),
+                eth_tx_hash: Some(40),
+                ..Default::default()
+            }))
+            .build();
+
+        let ResultAndState { result, state } = evm.transact().unwrap();
+        assert!(
+            matches!(result, ExecutionResult::Revert { .. }),
+            "{result:?}"
+        );
+
+        let storage = &state[&BVM_ETH_ADDRESS].storage;
+        assert_eq!(
+            storage[&bvm_eth::balance_slot(caller)].present_value,
+            U256::from(60)
+        );
+        assert_eq!(
+            storage[&bvm_eth::balance_slot(recipient)].present_value,
+            U256::from(40)
+        );
+        assert_eq!(
+            storage[&bvm_eth::total_supply_slot()].present_value,
+            U256::from(100)
+        );
+    }
+
+    #[test]
+    fn test_failed_deposit_mints_bvm_eth() {
+        let caller = address!("0000000000000000000000000000000000000a11");
+        let recipient = address!("0000000000000000000000000000000000000b0b");
+        let mut db = InMemoryDB::default();
+        db.insert_account_storage(
+            BVM_ETH_ADDRESS,
+            bvm_eth::balance_slot(caller),
+            U256::from(5),
+        )
+        .unwrap();
+
+        let mut context = Context::<TestMemOpWiring>::new_with_db(db);
+        context.evm.inner.env.tx = OpTransaction::Deposit(TxDeposit {
+            from: caller,
+            to: TxKind::Call(recipient),
+            gas_limit: 100,
+            eth_value: Some(100),
+            eth_tx_hash: Some(40),
+            ..Default::default()
+        });
+
+        let ResultAndState { result, state } = end::<TestMemOpWiring, RegolithSpec>(
+            &mut context,
+            Err(EVMError::Transaction(
+                OpTransactionError::HaltedDepositPostRegolith,
+            )),
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            ExecutionResult::Halt {
+                reason: MantleHaltReason::FailedDeposit,
+                gas_used: 100,
+            }
+        );
+
+        // Both the mint and the transfer to the recipient are persisted.
+        let storage = &state[&BVM_ETH_ADDRESS].storage;
+        let caller_slot = &storage[&bvm_eth::balance_slot(caller)];
+        assert_eq!(caller_slot.original_value(), U256::from(5));
+        assert_eq!(caller_slot.present_value, U256::from(65));
+        assert_eq!(
+            storage[&bvm_eth::balance_slot(recipient)].present_value,
+            U256::from(40)
+        );
+        assert_eq!(
+            storage[&bvm_eth::total_supply_slot()].present_value,
+            U256::from(100)
+        );
+    }
+
+    #[test]
+    fn test_halted_deposit_transfers_bvm_eth() {
+        let caller = address!("0000000000000000000000000000000000000a11");
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(
+            caller,
+            AccountInfo {
+                nonce: 3,
+                ..Default::default()
+            },
+        );
+
+        // INVALID, the created contract halts.
+        let mut evm = Evm::<TestMemOpWiring>::builder()
+            .with_db(db)
+            .with_default_ext_ctx()
+            .with_spec_id(MantleSpecId::REGOLITH)
+            .with_tx_env(OpTransaction::Deposit(TxDeposit {
+                from: caller,
+                to: TxKind::Create,
+                gas_limit: 100_000,
+                input: bytes!("fe"),
+                eth_value: Some(100),
+                eth_tx_hash: Some(40),
+                ..Default::default()
+            }))
+            .build();
+
+        let ResultAndState { result, state } = evm.transact().unwrap();
+        assert_eq!(
+            result,
+            ExecutionResult::Halt {
+                reason: MantleHaltReason::FailedDeposit,
+                gas_used: 100_000,
+            }
+        );
+        assert_eq!(state[&caller].info.nonce, 4);
+
+        // The bridged ETH goes to the address the contract would have been created at.
+        let storage = &state[&BVM_ETH_ADDRESS].storage;
+        assert_eq!(
+            storage[&bvm_eth::balance_slot(caller)].present_value,
+            U256::from(60)
+        );
+        assert_eq!(
+            storage[&bvm_eth::balance_slot(caller.create(3))].present_value,
+            U256::from(40)
+        );
+    }
+
+    #[test]
+    fn test_deposit_receipt() {
+        let caller = address!("0000000000000000000000000000000000000a11");
+        for (spec_id, expected) in [
+            (MantleSpecId::BEDROCK, MantleDepositReceipt::default()),
+            (
+                MantleSpecId::REGOLITH,
+                MantleDepositReceipt {
+                    deposit_nonce: Some(5),
+                    deposit_receipt_version: None,
+                },
+            ),
+            (
+                MantleSpecId::CANYON,
+                MantleDepositReceipt {
+                    deposit_nonce: Some(5),
+                    deposit_receipt_version: Some(DEPOSIT_RECEIPT_VERSION),
+                },
+            ),
+        ] {
+            let mut db = InMemoryDB::default();
+            db.insert_account_info(
+                caller,
+                AccountInfo {
+                    nonce: 5,
+                    ..Default::default()
+                },
+            );
+            let mut evm = Evm::<TestMemOpWiring>::builder()
+                .with_db(db)
+                .with_default_ext_ctx()
+                .with_spec_id(spec_id)
+                .with_tx_env(OpTransaction::Deposit(TxDeposit {
+                    from: caller,
+                    to: TxKind::Call(Address::ZERO),
+                    gas_limit: 100_000,
+                    ..Default::default()
+                }))
+                .build();
+
+            let result = MantleResultAndState::transact(&mut evm).unwrap();
+            assert!(result.result.is_success());
+            assert_eq!(result.deposit_receipt, Some(expected), "{spec_id:?}");
+            assert_eq!(result.state[&caller].info.nonce, 6);
+        }
+    }
+
+    #[test]
+    fn test_deposit_receipt_cleared_for_non_deposits() {
+        let mut db = InMemoryDB::default();
+        Predeploys::new(MantleSpecId::REGOLITH)
+            .commit(&mut db)
+            .unwrap();
+        let mut evm = Evm::<TestMemOpWiring>::builder()
+            .with_db(db)
+            .with_default_ext_ctx()
+            .with_spec_id(MantleSpecId::REGOLITH)
+            .with_tx_env(OpTransaction::Base {
+                tx: TxEnv {
+                    gas_limit: 100_000,
+                    ..Default::default()
+                },
+                enveloped_tx: Some(bytes!("FACADE")),
+            })
+            .build();
+        *evm.context.evm.chain.deposit_receipt_mut() = Some(MantleDepositReceipt::default());
+
+        let result = MantleResultAndState::transact(&mut evm).unwrap();
+        assert!(result.result.is_success());
+        assert_eq!(result.deposit_receipt, None);
+    }
+
+    #[test]
+    fn test_failed_deposit_receipt() {
+        let caller = address!("0000000000000000000000000000000000000a11");
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(
+            caller,
+            AccountInfo {
+                nonce: 7,
+                ..Default::default()
+            },
+        );
+        let mut context = Context::<TestMemOpWiring>::new_with_db(db);
+        context.evm.inner.env.tx = OpTransaction::Deposit(TxDeposit {
+            from: caller,
+            gas_limit: 100,
+            ..Default::default()
+        });
+
+        let ResultAndState { state, .. } = end::<TestMemOpWiring, LatestSpec>(
+            &mut context,
+            Err(EVMError::Transaction(
+                OpTransactionError::HaltedDepositPostRegolith,
+            )),
+        )
+        .unwrap();
+        assert_eq!(state[&caller].info.nonce, 8);
+        assert_eq!(
+            context.evm.chain.deposit_receipt(),
+            Some(&MantleDepositReceipt {
+                deposit_nonce: Some(7),
+                deposit_receipt_version: Some(DEPOSIT_RECEIPT_VERSION),
+            })
+        );
+    }
+
+    #[test]
+    fn test_failed_deposit_propagates_database_error() {
+        struct FailingDB;
+
+        impl Database for FailingDB {
+            type Error = &'static str;
+
+            fn basic(&mut self, _address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+                Err("basic")
+            }
+
+            fn code_by_hash(&mut self, _code_hash: B256) -> Result<Bytecode, Self::Error> {
+                Err("code_by_hash")
+            }
+
+            fn storage(&mut self, _address: Address, _index: U256) -> Result<U256, Self::Error> {
+                Err("storage")
+            }
+
+            fn block_hash(&mut self, _number: u64) -> Result<B256, Self::Error> {
+                Err("block_hash")
+            }
+        }
+
+        type FailingWiring = MantleEvmWiring<FailingDB, ()>;
+        let mut context = Context::<FailingWiring>::new_with_db(FailingDB);
+        context.evm.inner.env.tx = OpTransaction::Deposit(TxDeposit {
+            gas_limit: 100,
+            ..Default::default()
+        });
+
+        let result = end::<FailingWiring, RegolithSpec>(
+            &mut context,
+            Err(EVMError::Transaction(
+                OpTransactionError::HaltedDepositPostRegolith,
+            )),
+        );
+        assert_eq!(result, Err(EVMError::Database("basic")));
+        assert_eq!(context.evm.chain.deposit_receipt(), None);
+    }
+
     #[test]
     fn test_validate_sys_tx() {
         // mark the tx as a system transaction.
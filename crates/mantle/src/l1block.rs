@@ -1,50 +1,85 @@
-use crate::fast_lz::flz_compress_len;
-use core::ops::Mul;
-use revm::{
-    database_interface::Database,
-    primitives::{address, Address, U256},
+use crate::{fast_lz::flz_compress_len, transaction::envelope::encode_unsigned};
+use core::fmt::Display;
+use op_stack::{
+    l1block::{self, estimated_size_fjord, L1CostParams, NON_ZERO_BYTE_COST},
+    OpStackHardfork, OpStackUpgrade,
 };
+use revm::{database_interface::Database, primitives::U256, wiring::default::TxEnv};
 
 use super::MantleSpecId;
 
-const ZERO_BYTE_COST: u64 = 4;
-const NON_ZERO_BYTE_COST: u64 = 16;
+pub(crate) const TOKEN_RATIO_SLOT: U256 = U256::from_limbs([0u64, 0, 0, 0]);
 
-/// The two 4-byte Ecotone fee scalar values are packed into the same storage slot as the 8-byte sequence number.
-/// Byte offset within the storage slot of the 4-byte baseFeeScalar attribute.
-const BASE_FEE_SCALAR_OFFSET: usize = 16;
-/// The two 4-byte Ecotone fee scalar values are packed into the same storage slot as the 8-byte sequence number.
-/// Byte offset within the storage slot of the 4-byte blobBaseFeeScalar attribute.
-const BLOB_BASE_FEE_SCALAR_OFFSET: usize = 20;
+/// An empty 64-bit set of scalar values.
+const EMPTY_SCALARS: [u8; 8] = [0u8; 8];
 
-const L1_BASE_FEE_SLOT: U256 = U256::from_limbs([1u64, 0, 0, 0]);
-const L1_OVERHEAD_SLOT: U256 = U256::from_limbs([5u64, 0, 0, 0]);
-const L1_SCALAR_SLOT: U256 = U256::from_limbs([6u64, 0, 0, 0]);
+/// Selector of `setL1BlockValues(uint64,uint64,uint256,bytes32,uint64,bytes32,uint256,uint256)`.
+pub const L1_BLOCK_BEDROCK_SELECTOR: [u8; 4] = [0x01, 0x5d, 0x8e, 0xb9];
 
-const TOKEN_RATIO_SLOT: U256 = U256::from_limbs([0u64, 0, 0, 0]);
+/// Selector of `setL1BlockValuesEcotone()`, whose arguments are tightly packed.
+pub const L1_BLOCK_ECOTONE_SELECTOR: [u8; 4] = [0x44, 0x0a, 0x5e, 0x20];
 
-/// [ECOTONE_L1_BLOB_BASE_FEE_SLOT] was added in the Ecotone upgrade and stores the L1 blobBaseFee attribute.
-const ECOTONE_L1_BLOB_BASE_FEE_SLOT: U256 = U256::from_limbs([7u64, 0, 0, 0]);
+/// Selector of the GasPriceOracle `setTokenRatio(uint256)`.
+pub const SET_TOKEN_RATIO_SELECTOR: [u8; 4] = [0xe3, 0x8e, 0x91, 0xf9];
 
-/// As of the ecotone upgrade, this storage slot stores the 32-bit basefeeScalar and blobBaseFeeScalar attributes at
-/// offsets [BASE_FEE_SCALAR_OFFSET] and [BLOB_BASE_FEE_SCALAR_OFFSET] respectively.
-const ECOTONE_L1_FEE_SCALARS_SLOT: U256 = U256::from_limbs([3u64, 0, 0, 0]);
+/// Length of the Bedrock `setL1BlockValues` calldata: the selector and eight ABI words.
+const L1_BLOCK_BEDROCK_LEN: usize = 4 + 8 * 32;
 
-/// An empty 64-bit set of scalar values.
-const EMPTY_SCALARS: [u8; 8] = [0u8; 8];
+/// Length of the Ecotone `setL1BlockValuesEcotone` calldata: the selector, two `uint32`
+/// scalars, three `uint64` and four 32-byte values.
+const L1_BLOCK_ECOTONE_LEN: usize = 4 + 2 * 4 + 3 * 8 + 4 * 32;
 
-/// The address of L1 fee recipient.
-/// Mantle does not use this address.
-// pub const L1_FEE_RECIPIENT: Address = address!("420000000000000000000000000000000000001A");
+/// Length of the `setTokenRatio` calldata.
+const SET_TOKEN_RATIO_LEN: usize = 4 + 32;
 
-/// The address of the base fee recipient.
-pub const BASE_FEE_RECIPIENT: Address = address!("4200000000000000000000000000000000000019");
+/// Number of non-zero bytes accounted for the signature of unsigned transactions.
+pub const SIGNATURE_OVERHEAD: usize = 68;
 
-/// The address of the L1Block contract.
-pub const L1_BLOCK_CONTRACT: Address = address!("4200000000000000000000000000000000000015");
+pub use op_stack::l1block::{
+    BASE_FEE_RECIPIENT, GAS_ORACLE_CONTRACT, L1_BLOCK_CONTRACT, L1_FEE_RECIPIENT, L1_INFO_DEPOSITOR,
+};
+pub(crate) use op_stack::l1block::{
+    BASE_FEE_SCALAR_OFFSET, BLOB_BASE_FEE_SCALAR_OFFSET, ECOTONE_L1_BLOB_BASE_FEE_SLOT,
+    ECOTONE_L1_FEE_SCALARS_SLOT, L1_BASE_FEE_SLOT, L1_OVERHEAD_SLOT, L1_SCALAR_SLOT,
+};
+
+/// Error returned when decoding L1 block values or the token ratio from calldata.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum L1BlockInfoError {
+    /// The calldata does not start with a known selector.
+    UnknownSelector,
+    /// The calldata does not have the length expected for its selector.
+    InvalidLength {
+        /// The expected calldata length.
+        expected: usize,
+        /// The actual calldata length.
+        got: usize,
+    },
+}
+
+impl Display for L1BlockInfoError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::UnknownSelector => write!(f, "unknown L1 block values selector"),
+            Self::InvalidLength { expected, got } => {
+                write!(f, "invalid calldata length: expected {expected}, got {got}")
+            }
+        }
+    }
+}
 
-/// The address of the gas oracle contract.
-pub const GAS_ORACLE_CONTRACT: Address = address!("420000000000000000000000000000000000000F");
+impl core::error::Error for L1BlockInfoError {}
+
+/// L1 data fee estimate of an unsigned transaction.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct L1FeeEstimate {
+    /// The L1 gas used, as the GasPriceOracle `getL1GasUsed`.
+    pub l1_gas_used: U256,
+    /// The expected L1 fee, as the GasPriceOracle `getL1Fee`.
+    pub l1_fee: U256,
+    /// The L1 fee upper bound, as the GasPriceOracle `getL1FeeUpperBound`.
+    pub l1_fee_upper_bound: U256,
+}
 
 /// L1 block info
 ///
@@ -91,7 +126,7 @@ impl L1BlockInfo {
         let l1_base_fee = db.storage(L1_BLOCK_CONTRACT, L1_BASE_FEE_SLOT)?;
         let token_ratio = db.storage(GAS_ORACLE_CONTRACT, TOKEN_RATIO_SLOT)?;
 
-        if !spec_id.is_enabled_in(MantleSpecId::ECOTONE) {
+        if !spec_id.is_upgrade_enabled(OpStackUpgrade::Ecotone) {
             let l1_fee_overhead = db.storage(L1_BLOCK_CONTRACT, L1_OVERHEAD_SLOT)?;
             let l1_fee_scalar = db.storage(L1_BLOCK_CONTRACT, L1_SCALAR_SLOT)?;
 
@@ -137,140 +172,427 @@ impl L1BlockInfo {
         }
     }
 
-    /// Calculate the data gas for posting the transaction on L1. Calldata costs 16 gas per byte
-    /// after compression.
+    /// Decodes the calldata of the L1 attributes deposit, the first transaction of each block.
     ///
-    /// Prior to fjord, calldata costs 16 gas per non-zero byte and 4 gas per zero byte.
+    /// Both the Bedrock `setL1BlockValues` and the Ecotone `setL1BlockValuesEcotone` formats are
+    /// accepted, as the first Ecotone block still carries the Bedrock format. The result matches
+    /// [L1BlockInfo::try_fetch] after the deposit is executed, except for the token ratio which
+    /// is set separately with [L1BlockInfo::with_token_ratio].
+    pub fn try_from_calldata(
+        input: &[u8],
+        spec_id: MantleSpecId,
+    ) -> Result<L1BlockInfo, L1BlockInfoError> {
+        match input.get(..4) {
+            Some(selector) if selector == L1_BLOCK_BEDROCK_SELECTOR => {
+                Self::try_from_bedrock_calldata(input, spec_id)
+            }
+            Some(selector) if selector == L1_BLOCK_ECOTONE_SELECTOR => {
+                Self::try_from_ecotone_calldata(input)
+            }
+            _ => Err(L1BlockInfoError::UnknownSelector),
+        }
+    }
+
+    /// Decodes the calldata of `setL1BlockValues`.
     ///
-    /// Prior to regolith, an extra 68 non-zero bytes were included in the rollup data costs to
-    /// account for the empty signature.
-    pub fn data_gas(&self, input: &[u8], spec_id: MantleSpecId) -> U256 {
-        if spec_id.is_enabled_in(MantleSpecId::FJORD) {
-            let estimated_size = self.tx_estimated_size_fjord(input);
+    /// If Ecotone is active the fee scalars are not set yet, so the Bedrock cost function is
+    /// used.
+    fn try_from_bedrock_calldata(
+        input: &[u8],
+        spec_id: MantleSpecId,
+    ) -> Result<L1BlockInfo, L1BlockInfoError> {
+        check_length(input, L1_BLOCK_BEDROCK_LEN)?;
+        let word = |index: usize| U256::from_be_slice(&input[4 + index * 32..4 + (index + 1) * 32]);
 
-            return estimated_size
-                .saturating_mul(U256::from(NON_ZERO_BYTE_COST))
-                .wrapping_div(U256::from(1_000_000));
+        let l1_block_info = L1BlockInfo {
+            l1_base_fee: word(2),
+            l1_fee_overhead: Some(word(6)),
+            l1_base_fee_scalar: word(7),
+            ..Default::default()
         };
+        if !spec_id.is_upgrade_enabled(OpStackUpgrade::Ecotone) {
+            return Ok(l1_block_info);
+        }
+        Ok(L1BlockInfo {
+            l1_blob_base_fee: Some(U256::ZERO),
+            l1_blob_base_fee_scalar: Some(U256::ZERO),
+            empty_scalars: true,
+            ..l1_block_info
+        })
+    }
 
-        let mut rollup_data_gas_cost = U256::from(input.iter().fold(0, |acc, byte| {
-            acc + if *byte == 0x00 {
-                ZERO_BYTE_COST
-            } else {
-                NON_ZERO_BYTE_COST
-            }
-        }));
+    /// Decodes the calldata of `setL1BlockValuesEcotone`.
+    ///
+    /// The arguments are packed as `baseFeeScalar (uint32)`, `blobBaseFeeScalar (uint32)`,
+    /// `sequenceNumber (uint64)`, `timestamp (uint64)`, `number (uint64)`, `basefee (uint256)`,
+    /// `blobBaseFee (uint256)`, `hash (bytes32)` and `batcherHash (bytes32)`.
+    fn try_from_ecotone_calldata(input: &[u8]) -> Result<L1BlockInfo, L1BlockInfoError> {
+        check_length(input, L1_BLOCK_ECOTONE_LEN)?;
+        let scalars = &input[4..12];
+        let l1_blob_base_fee = U256::from_be_slice(&input[68..100]);
+
+        // As in `try_fetch`, unset scalars fall back to the Bedrock cost function. The L1 fee
+        // overhead is not part of the Ecotone calldata, so it is left unset.
+        let empty_scalars = l1_blob_base_fee.is_zero() && scalars == EMPTY_SCALARS;
+
+        Ok(L1BlockInfo {
+            l1_base_fee: U256::from_be_slice(&input[36..68]),
+            l1_fee_overhead: None,
+            l1_base_fee_scalar: U256::from_be_slice(&scalars[..4]),
+            l1_blob_base_fee: Some(l1_blob_base_fee),
+            l1_blob_base_fee_scalar: Some(U256::from_be_slice(&scalars[4..])),
+            token_ratio: None,
+            empty_scalars,
+        })
+    }
 
-        // Prior to regolith, an extra 68 non zero bytes were included in the rollup data costs.
-        if !spec_id.is_enabled_in(MantleSpecId::REGOLITH) {
-            rollup_data_gas_cost += U256::from(NON_ZERO_BYTE_COST).mul(U256::from(68));
+    /// Decodes the token ratio from the calldata of the GasPriceOracle `setTokenRatio`.
+    pub fn token_ratio_from_calldata(input: &[u8]) -> Result<U256, L1BlockInfoError> {
+        if input.get(..4) != Some(SET_TOKEN_RATIO_SELECTOR.as_slice()) {
+            return Err(L1BlockInfoError::UnknownSelector);
         }
+        check_length(input, SET_TOKEN_RATIO_LEN)?;
+        Ok(U256::from_be_slice(&input[4..]))
+    }
 
-        rollup_data_gas_cost
+    /// Sets the token ratio, as read from the GasPriceOracle storage or its `setTokenRatio`
+    /// calldata.
+    pub fn with_token_ratio(mut self, token_ratio: U256) -> Self {
+        self.token_ratio = Some(token_ratio);
+        self
     }
 
-    // Calculate the estimated compressed transaction size in bytes, scaled by 1e6.
-    // This value is computed based on the following formula:
-    // max(minTransactionSize, intercept + fastlzCoef*fastlzSize)
-    fn tx_estimated_size_fjord(&self, input: &[u8]) -> U256 {
-        let fastlz_size = U256::from(flz_compress_len(input));
+    /// Calculate the data gas for posting the transaction on L1. Calldata costs 16 gas per byte
+    /// after compression.
+    ///
+    /// Prior to fjord, calldata costs 16 gas per non-zero byte and 4 gas per zero byte.
+    ///
+    /// Prior to regolith, an extra 68 non-zero bytes were included in the rollup data costs to
+    /// account for the empty signature.
+    pub fn data_gas(&self, input: &[u8], spec_id: MantleSpecId) -> U256 {
+        l1block::data_gas(input, spec_id)
+    }
 
-        fastlz_size
-            .saturating_mul(U256::from(836_500))
-            .saturating_sub(U256::from(42_585_600))
-            .max(U256::from(100_000_000))
+    /// Returns the L1 gas used to post the transaction, as reported in receipts.
+    ///
+    /// This includes the L1 fee overhead as long as the Bedrock cost function is used.
+    pub fn tx_l1_gas_used(&self, input: &[u8], spec_id: MantleSpecId) -> U256 {
+        L1CostParams::tx_l1_gas_used(self, input, spec_id)
     }
 
     /// Calculate the gas cost of a transaction based on L1 block data posted on L2, depending on the [MantleSpecId] passed.
+    ///
+    /// The Bedrock cost is scaled by the token ratio.
     pub fn calculate_tx_l1_cost(&self, input: &[u8], spec_id: MantleSpecId) -> U256 {
-        // If the input is a deposit transaction or empty, the default value is zero.
-        if input.is_empty() || input.first() == Some(&0x7F) {
-            return U256::ZERO;
-        }
+        L1CostParams::calculate_tx_l1_cost(self, input, spec_id)
+    }
 
-        if spec_id.is_enabled_in(MantleSpecId::FJORD) {
-            self.calculate_tx_l1_cost_fjord(input)
-        } else if spec_id.is_enabled_in(MantleSpecId::ECOTONE) {
-            self.calculate_tx_l1_cost_ecotone(input, spec_id)
-        } else {
-            self.calculate_tx_l1_cost_bedrock(input, spec_id)
+    /// Estimates the L1 data fee of an unsigned transaction.
+    ///
+    /// The template is encoded as it would be signed, see
+    /// [encode_unsigned].
+    pub fn estimate_tx_l1_fee(&self, tx: &TxEnv, spec_id: MantleSpecId) -> L1FeeEstimate {
+        let unsigned_tx = encode_unsigned(tx);
+        L1FeeEstimate {
+            l1_gas_used: self.unsigned_tx_l1_gas_used(&unsigned_tx, spec_id),
+            l1_fee: self.unsigned_tx_l1_fee(&unsigned_tx, spec_id),
+            l1_fee_upper_bound: self.unsigned_tx_l1_fee_upper_bound(unsigned_tx.len(), spec_id),
         }
     }
 
-    /// Calculate the gas cost of a transaction based on L1 block data posted on L2, pre-Ecotone.
-    fn calculate_tx_l1_cost_bedrock(&self, input: &[u8], spec_id: MantleSpecId) -> U256 {
-        let rollup_data_gas_cost = self.data_gas(input, spec_id);
+    /// Returns the L1 gas used by an unsigned transaction, as the GasPriceOracle `getL1GasUsed`.
+    ///
+    /// [SIGNATURE_OVERHEAD] non-zero bytes are added for the missing signature.
+    pub fn unsigned_tx_l1_gas_used(&self, unsigned_tx: &[u8], spec_id: MantleSpecId) -> U256 {
+        if spec_id.is_upgrade_enabled(OpStackUpgrade::Fjord) {
+            let fastlz_size =
+                U256::from(flz_compress_len(unsigned_tx) as usize + SIGNATURE_OVERHEAD);
+            return estimated_size_fjord(fastlz_size)
+                .saturating_mul(U256::from(NON_ZERO_BYTE_COST))
+                .wrapping_div(U256::from(1_000_000));
+        }
 
-        rollup_data_gas_cost
-            .saturating_add(self.l1_fee_overhead.unwrap_or_default())
-            .saturating_mul(self.l1_base_fee)
-            .saturating_mul(self.l1_base_fee_scalar)
-            .saturating_mul(self.get_token_ratio())
-            .wrapping_div(U256::from(1_000_000))
+        let l1_gas_used = self.unsigned_tx_data_gas(unsigned_tx);
+        if spec_id.is_upgrade_enabled(OpStackUpgrade::Ecotone) && !self.empty_scalars {
+            return l1_gas_used;
+        }
+        l1_gas_used.saturating_add(self.l1_fee_overhead.unwrap_or_default())
     }
 
-    /// Calculate the gas cost of a transaction based on L1 block data posted on L2, post-Ecotone.
+    /// Returns the L1 fee of an unsigned transaction, as the GasPriceOracle `getL1Fee`.
     ///
-    /// [MantleSpecId::ECOTONE] L1 cost function:
-    /// `(calldataGas/16)*(l1BaseFee*16*l1BaseFeeScalar + l1BlobBaseFee*l1BlobBaseFeeScalar)/1e6`
-    ///
-    /// We divide "calldataGas" by 16 to change from units of calldata gas to "estimated # of bytes when compressed".
-    /// Known as "compressedTxSize" in the spec.
+    /// [SIGNATURE_OVERHEAD] non-zero bytes are added for the missing signature, so this is the
+    /// fee charged by [L1BlockInfo::calculate_tx_l1_cost] for the signed transaction, up to the
+    /// compression of the signature after Fjord.
+    pub fn unsigned_tx_l1_fee(&self, unsigned_tx: &[u8], spec_id: MantleSpecId) -> U256 {
+        if spec_id.is_upgrade_enabled(OpStackUpgrade::Fjord) {
+            let fastlz_size =
+                U256::from(flz_compress_len(unsigned_tx) as usize + SIGNATURE_OVERHEAD);
+            return self.l1_cost_fjord(estimated_size_fjord(fastlz_size));
+        }
+        self.l1_cost_from_data_gas(self.unsigned_tx_data_gas(unsigned_tx), spec_id)
+    }
+
+    /// Returns an upper bound of the L1 fee of an unsigned transaction of the given size, as the
+    /// GasPriceOracle `getL1FeeUpperBound`.
     ///
-    /// Function is actually computed as follows for better precision under integer arithmetic:
-    /// `calldataGas*(l1BaseFee*16*l1BaseFeeScalar + l1BlobBaseFee*l1BlobBaseFeeScalar)/16e6`
-    fn calculate_tx_l1_cost_ecotone(&self, input: &[u8], spec_id: MantleSpecId) -> U256 {
-        // There is an edgecase where, for the very first Ecotone block (unless it is activated at Genesis), we must
-        // use the Bedrock cost function. To determine if this is the case, we can check if the Ecotone parameters are
-        // unset.
-        if self.empty_scalars {
-            return self.calculate_tx_l1_cost_bedrock(input, spec_id);
+    /// After Fjord this bounds the compressed size by the worst case of FastLZ. Before, every
+    /// byte is assumed to be non-zero.
+    pub fn unsigned_tx_l1_fee_upper_bound(
+        &self,
+        unsigned_tx_size: usize,
+        spec_id: MantleSpecId,
+    ) -> U256 {
+        let tx_size = U256::from(unsigned_tx_size + SIGNATURE_OVERHEAD);
+        if spec_id.is_upgrade_enabled(OpStackUpgrade::Fjord) {
+            let fastlz_upper_bound = tx_size + tx_size / U256::from(255) + U256::from(16);
+            return self.l1_cost_fjord(estimated_size_fjord(fastlz_upper_bound));
+        }
+        let data_gas = tx_size.saturating_mul(U256::from(NON_ZERO_BYTE_COST));
+        self.l1_cost_from_data_gas(data_gas, spec_id)
+    }
+
+    /// Calldata gas of an unsigned transaction, including the signature overhead.
+    fn unsigned_tx_data_gas(&self, unsigned_tx: &[u8]) -> U256 {
+        // The signature is added explicitly, so use the Regolith cost which does not add it.
+        self.data_gas(unsigned_tx, MantleSpecId::REGOLITH)
+            .saturating_add(U256::from(NON_ZERO_BYTE_COST * SIGNATURE_OVERHEAD as u64))
+    }
+
+    /// Pre-Fjord L1 cost of the given calldata gas.
+    fn l1_cost_from_data_gas(&self, data_gas: U256, spec_id: MantleSpecId) -> U256 {
+        if spec_id.is_upgrade_enabled(OpStackUpgrade::Ecotone) && !self.empty_scalars {
+            self.l1_cost_ecotone(data_gas)
+        } else {
+            self.l1_cost_bedrock(data_gas)
         }
+    }
 
-        let rollup_data_gas_cost = self.data_gas(input, spec_id);
-        let l1_fee_scaled = self.calculate_l1_fee_scaled_ecotone();
+    pub fn get_token_ratio(&self) -> U256 {
+        self.token_ratio.unwrap_or(U256::from(1))
+    }
+}
 
-        l1_fee_scaled
-            .saturating_mul(rollup_data_gas_cost)
-            .wrapping_div(U256::from(1_000_000 * NON_ZERO_BYTE_COST))
+impl L1CostParams for L1BlockInfo {
+    fn l1_base_fee(&self) -> U256 {
+        self.l1_base_fee
     }
 
-    /// Calculate the gas cost of a transaction based on L1 block data posted on L2, post-Fjord.
-    ///
-    /// [MantleSpecId::FJORD] L1 cost function:
-    /// `estimatedSize*(baseFeeScalar*l1BaseFee*16 + blobFeeScalar*l1BlobBaseFee)/1e12`
-    fn calculate_tx_l1_cost_fjord(&self, input: &[u8]) -> U256 {
-        let l1_fee_scaled = self.calculate_l1_fee_scaled_ecotone();
-        let estimated_size = self.tx_estimated_size_fjord(input);
+    fn l1_fee_overhead(&self) -> Option<U256> {
+        self.l1_fee_overhead
+    }
 
-        estimated_size
-            .saturating_mul(l1_fee_scaled)
-            .wrapping_div(U256::from(1_000_000_000_000u64))
+    fn l1_base_fee_scalar(&self) -> U256 {
+        self.l1_base_fee_scalar
     }
 
-    // l1BaseFee*16*l1BaseFeeScalar + l1BlobBaseFee*l1BlobBaseFeeScalar
-    fn calculate_l1_fee_scaled_ecotone(&self) -> U256 {
-        let calldata_cost_per_byte = self
-            .l1_base_fee
-            .saturating_mul(U256::from(NON_ZERO_BYTE_COST))
-            .saturating_mul(self.l1_base_fee_scalar);
-        let blob_cost_per_byte = self
-            .l1_blob_base_fee
-            .unwrap_or_default()
-            .saturating_mul(self.l1_blob_base_fee_scalar.unwrap_or_default());
+    fn l1_blob_base_fee(&self) -> Option<U256> {
+        self.l1_blob_base_fee
+    }
 
-        calldata_cost_per_byte.saturating_add(blob_cost_per_byte)
+    fn l1_blob_base_fee_scalar(&self) -> Option<U256> {
+        self.l1_blob_base_fee_scalar
     }
 
-    pub fn get_token_ratio(&self) -> U256 {
-        self.token_ratio.unwrap_or(U256::from(1))
+    fn empty_scalars(&self) -> bool {
+        self.empty_scalars
+    }
+
+    /// Mantle scales the Bedrock L1 cost by the token ratio.
+    fn bedrock_cost_multiplier(&self) -> U256 {
+        self.get_token_ratio()
+    }
+}
+
+fn check_length(input: &[u8], expected: usize) -> Result<(), L1BlockInfoError> {
+    if input.len() == expected {
+        Ok(())
+    } else {
+        Err(L1BlockInfoError::InvalidLength {
+            expected,
+            got: input.len(),
+        })
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use revm::primitives::{bytes, hex};
+    use crate::BVM_ETH_ADDRESS;
+    use database::InMemoryDB;
+    use revm::{
+        primitives::{address, bytes, hex, keccak256, TxKind},
+        specification::eip2930::{AccessList, AccessListItem},
+        transaction::TransactionType,
+    };
+    use std::vec::Vec;
+
+    fn bedrock_calldata(l1_base_fee: u64, overhead: u64, scalar: u64) -> Vec<u8> {
+        let mut input = L1_BLOCK_BEDROCK_SELECTOR.to_vec();
+        for word in [
+            18_000_000,
+            1_700_000_000,
+            l1_base_fee,
+            0xaa,
+            3,
+            0xbb,
+            overhead,
+            scalar,
+        ] {
+            input.extend_from_slice(&U256::from(word).to_be_bytes::<32>());
+        }
+        input
+    }
+
+    fn ecotone_calldata(
+        l1_base_fee: u64,
+        l1_blob_base_fee: u64,
+        base_fee_scalar: u32,
+        blob_base_fee_scalar: u32,
+    ) -> Vec<u8> {
+        let mut input = L1_BLOCK_ECOTONE_SELECTOR.to_vec();
+        input.extend_from_slice(&base_fee_scalar.to_be_bytes());
+        input.extend_from_slice(&blob_base_fee_scalar.to_be_bytes());
+        input.extend_from_slice(&3u64.to_be_bytes());
+        input.extend_from_slice(&1_700_000_000u64.to_be_bytes());
+        input.extend_from_slice(&18_000_000u64.to_be_bytes());
+        input.extend_from_slice(&U256::from(l1_base_fee).to_be_bytes::<32>());
+        input.extend_from_slice(&U256::from(l1_blob_base_fee).to_be_bytes::<32>());
+        input.extend_from_slice(&[0xaa; 32]);
+        input.extend_from_slice(&[0xbb; 32]);
+        input
+    }
+
+    #[test]
+    fn test_selectors() {
+        assert_eq!(
+            keccak256(
+                "setL1BlockValues(uint64,uint64,uint256,bytes32,uint64,bytes32,uint256,uint256)"
+            )[..4],
+            L1_BLOCK_BEDROCK_SELECTOR
+        );
+        assert_eq!(
+            keccak256("setL1BlockValuesEcotone()")[..4],
+            L1_BLOCK_ECOTONE_SELECTOR
+        );
+        assert_eq!(
+            keccak256("setTokenRatio(uint256)")[..4],
+            SET_TOKEN_RATIO_SELECTOR
+        );
+    }
+
+    #[test]
+    fn test_from_bedrock_calldata_matches_storage() {
+        let input = bedrock_calldata(7_789_722_029, 188, 10_000);
+
+        let mut db = InMemoryDB::default();
+        for (slot, value) in [
+            (L1_BASE_FEE_SLOT, 7_789_722_029u64),
+            (L1_OVERHEAD_SLOT, 188),
+            (L1_SCALAR_SLOT, 10_000),
+        ] {
+            db.insert_account_storage(L1_BLOCK_CONTRACT, slot, U256::from(value))
+                .unwrap();
+        }
+        db.insert_account_storage(GAS_ORACLE_CONTRACT, TOKEN_RATIO_SLOT, U256::from(4368))
+            .unwrap();
+
+        let decoded = L1BlockInfo::try_from_calldata(&input, MantleSpecId::BEDROCK)
+            .unwrap()
+            .with_token_ratio(U256::from(4368));
+        assert_eq!(
+            decoded,
+            L1BlockInfo::try_fetch(&mut db, MantleSpecId::BEDROCK).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_from_bedrock_calldata_first_ecotone_block() {
+        let input = bedrock_calldata(1_000, 1_000, 1_000);
+        let l1_block_info = L1BlockInfo::try_from_calldata(&input, MantleSpecId::ECOTONE)
+            .unwrap()
+            .with_token_ratio(U256::from(1_000));
+        assert!(l1_block_info.empty_scalars);
+        assert_eq!(l1_block_info.l1_blob_base_fee, Some(U256::ZERO));
+
+        // The Bedrock cost function is used with the Bedrock fee parameters.
+        let gas_cost = l1_block_info.calculate_tx_l1_cost(&bytes!("FACADE"), MantleSpecId::ECOTONE);
+        assert_eq!(gas_cost, U256::from(1_048_000));
+    }
+
+    #[test]
+    fn test_from_ecotone_calldata_matches_storage() {
+        let input = ecotone_calldata(1_000, 2_000, 1_368, 810_949);
+
+        let mut scalars = [0u8; 32];
+        scalars[BASE_FEE_SCALAR_OFFSET..BASE_FEE_SCALAR_OFFSET + 4]
+            .copy_from_slice(&1_368u32.to_be_bytes());
+        scalars[BLOB_BASE_FEE_SCALAR_OFFSET..BLOB_BASE_FEE_SCALAR_OFFSET + 4]
+            .copy_from_slice(&810_949u32.to_be_bytes());
+        let mut db = InMemoryDB::default();
+        for (slot, value) in [
+            (L1_BASE_FEE_SLOT, U256::from(1_000)),
+            (ECOTONE_L1_BLOB_BASE_FEE_SLOT, U256::from(2_000)),
+            (ECOTONE_L1_FEE_SCALARS_SLOT, U256::from_be_bytes(scalars)),
+        ] {
+            db.insert_account_storage(L1_BLOCK_CONTRACT, slot, value)
+                .unwrap();
+        }
+        db.insert_account_storage(GAS_ORACLE_CONTRACT, TOKEN_RATIO_SLOT, U256::from(20))
+            .unwrap();
+
+        let decoded = L1BlockInfo::try_from_calldata(&input, MantleSpecId::FJORD)
+            .unwrap()
+            .with_token_ratio(U256::from(20));
+        assert_eq!(
+            decoded,
+            L1BlockInfo::try_fetch(&mut db, MantleSpecId::FJORD).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_from_ecotone_calldata_empty_scalars() {
+        let input = ecotone_calldata(1_000, 0, 0, 0);
+        let l1_block_info = L1BlockInfo::try_from_calldata(&input, MantleSpecId::ECOTONE).unwrap();
+        assert!(l1_block_info.empty_scalars);
+        assert_eq!(l1_block_info.l1_fee_overhead, None);
+    }
+
+    #[test]
+    fn test_from_calldata_errors() {
+        assert_eq!(
+            L1BlockInfo::try_from_calldata(&[0xde, 0xad, 0xbe, 0xef], MantleSpecId::BEDROCK),
+            Err(L1BlockInfoError::UnknownSelector)
+        );
+        assert_eq!(
+            L1BlockInfo::try_from_calldata(&[], MantleSpecId::BEDROCK),
+            Err(L1BlockInfoError::UnknownSelector)
+        );
+
+        let mut input = ecotone_calldata(1_000, 2_000, 1, 1);
+        input.pop();
+        assert_eq!(
+            L1BlockInfo::try_from_calldata(&input, MantleSpecId::ECOTONE),
+            Err(L1BlockInfoError::InvalidLength {
+                expected: 164,
+                got: 163
+            })
+        );
+    }
+
+    #[test]
+    fn test_token_ratio_from_calldata() {
+        let mut input = SET_TOKEN_RATIO_SELECTOR.to_vec();
+        input.extend_from_slice(&U256::from(4368).to_be_bytes::<32>());
+        assert_eq!(
+            L1BlockInfo::token_ratio_from_calldata(&input),
+            Ok(U256::from(4368))
+        );
+        assert_eq!(
+            L1BlockInfo::token_ratio_from_calldata(&bedrock_calldata(1, 1, 1)),
+            Err(L1BlockInfoError::UnknownSelector)
+        );
+    }
 
     #[test]
     fn test_data_gas_non_zero_bytes() {
@@ -359,6 +681,38 @@ mod tests {
         assert_eq!(gas_cost, U256::ZERO);
     }
 
+    #[test]
+    fn test_calculate_tx_l1_cost_everest() {
+        let l1_block_info = L1BlockInfo {
+            l1_base_fee: U256::from(1_000),
+            l1_fee_overhead: Some(U256::from(1_000)),
+            l1_base_fee_scalar: U256::from(1_000),
+            token_ratio: Some(U256::from(1_000)),
+            ..Default::default()
+        };
+        let chain_spec = crate::chain_spec::mainnet();
+        let everest = crate::chain_spec::MANTLE_MAINNET_EVEREST_TIMESTAMP;
+        let before = chain_spec.spec_id_at(1, everest - 1);
+        let after = chain_spec.spec_id_at(1, everest);
+        assert_eq!(before, MantleSpecId::REGOLITH);
+        assert_eq!(after, MantleSpecId::EVEREST);
+
+        // Everest keeps the Bedrock cost function, Fjord would compress the input.
+        let input = bytes!("FACADE");
+        assert_eq!(
+            l1_block_info.calculate_tx_l1_cost(&input, before),
+            U256::from(1_048_000)
+        );
+        assert_eq!(
+            l1_block_info.calculate_tx_l1_cost(&input, after),
+            U256::from(1_048_000)
+        );
+        assert_ne!(
+            l1_block_info.calculate_tx_l1_cost(&input, MantleSpecId::FJORD),
+            U256::from(1_048_000)
+        );
+    }
+
     #[test]
     fn test_calculate_tx_l1_cost_ecotone() {
         let mut l1_block_info = L1BlockInfo {
@@ -524,7 +878,7 @@ mod tests {
 
         assert_eq!(gas_used, expected_l1_gas_used);
 
-        let l1_fee = l1_block_info.calculate_tx_l1_cost_ecotone(TX, MantleSpecId::ECOTONE);
+        let l1_fee = l1_block_info.calculate_tx_l1_cost(TX, MantleSpecId::ECOTONE);
 
         assert_eq!(l1_fee, expected_l1_fee)
     }
@@ -606,8 +960,151 @@ mod tests {
 
         assert_eq!(data_gas, expected_data_gas);
 
-        let l1_fee = l1_block_info.calculate_tx_l1_cost_fjord(TX);
+        let l1_fee = l1_block_info.calculate_tx_l1_cost(TX, MantleSpecId::FJORD);
 
         assert_eq!(l1_fee, expected_l1_fee)
     }
+
+    fn template() -> TxEnv {
+        TxEnv {
+            tx_type: TransactionType::Eip1559,
+            chain_id: Some(5000),
+            nonce: 7,
+            gas_limit: 100_000,
+            gas_price: U256::from(20_000_000),
+            gas_priority_fee: Some(U256::from(0)),
+            transact_to: TxKind::Call(address!("deaddeaddeaddeaddeaddeaddeaddeaddead1111")),
+            data: bytes!("a9059cbb000000000000000000000000000000000000000000000000000000000000c0de0000000000000000000000000000000000000000000000000de0b6b3a7640000"),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_unsigned_tx_l1_fee_matches_signed() {
+        let l1_block_info = L1BlockInfo {
+            l1_base_fee: U256::from(7_789_722_029u64),
+            l1_fee_overhead: Some(U256::from(188)),
+            l1_base_fee_scalar: U256::from(10_000),
+            l1_blob_base_fee: Some(U256::from(1_000)),
+            l1_blob_base_fee_scalar: Some(U256::from(810_949)),
+            token_ratio: Some(U256::from(4368)),
+            ..Default::default()
+        };
+        let unsigned_tx = encode_unsigned(&template());
+        // A signature made only of non-zero bytes.
+        let mut signed_tx = unsigned_tx.clone();
+        signed_tx.extend_from_slice(&[0xff; SIGNATURE_OVERHEAD]);
+
+        for spec_id in [
+            MantleSpecId::BEDROCK,
+            MantleSpecId::REGOLITH,
+            MantleSpecId::ECOTONE,
+        ] {
+            assert_eq!(
+                l1_block_info.unsigned_tx_l1_fee(&unsigned_tx, spec_id),
+                l1_block_info.calculate_tx_l1_cost(&signed_tx, MantleSpecId::REGOLITH.max(spec_id)),
+                "{spec_id:?}"
+            );
+        }
+
+        // getL1GasUsed includes the overhead before Ecotone only.
+        let data_gas = l1_block_info.data_gas(&signed_tx, MantleSpecId::REGOLITH);
+        assert_eq!(
+            l1_block_info.unsigned_tx_l1_gas_used(&unsigned_tx, MantleSpecId::REGOLITH),
+            data_gas + U256::from(188)
+        );
+        assert_eq!(
+            l1_block_info.unsigned_tx_l1_gas_used(&unsigned_tx, MantleSpecId::ECOTONE),
+            data_gas
+        );
+    }
+
+    #[test]
+    fn test_unsigned_tx_l1_fee_token_ratio() {
+        let mut l1_block_info = L1BlockInfo {
+            l1_base_fee: U256::from(1_000),
+            l1_fee_overhead: Some(U256::from(1_000)),
+            l1_base_fee_scalar: U256::from(1_000),
+            token_ratio: Some(U256::from(1)),
+            ..Default::default()
+        };
+        let unsigned_tx = encode_unsigned(&template());
+        let l1_fee = l1_block_info.unsigned_tx_l1_fee(&unsigned_tx, MantleSpecId::REGOLITH);
+
+        l1_block_info.token_ratio = Some(U256::from(4368));
+        assert_eq!(
+            l1_block_info.unsigned_tx_l1_fee(&unsigned_tx, MantleSpecId::REGOLITH),
+            l1_fee * U256::from(4368)
+        );
+    }
+
+    #[test]
+    fn test_unsigned_tx_l1_fee_fjord() {
+        // <https://optimistic.etherscan.io/block/124665056>
+        let l1_block_info = L1BlockInfo {
+            l1_base_fee: U256::from(1055991687),
+            l1_base_fee_scalar: U256::from(5227),
+            l1_blob_base_fee_scalar: Some(U256::from(1014213)),
+            l1_blob_base_fee: Some(U256::from(1)),
+            ..Default::default()
+        };
+
+        // Small transactions are charged the minimum size of 100 bytes:
+        // l1GasUsed = 100e6 * 16 / 1e6 = 1600
+        // l1Fee = 100e6 * (1055991687 * 16 * 5227 + 1 * 1014213) / 1e12 = 8831469778
+        let unsigned_tx = encode_unsigned(&template());
+        assert_eq!(
+            l1_block_info.unsigned_tx_l1_gas_used(&unsigned_tx, MantleSpecId::FJORD),
+            U256::from(1600)
+        );
+        assert_eq!(
+            l1_block_info.unsigned_tx_l1_fee(&unsigned_tx, MantleSpecId::FJORD),
+            U256::from(8_831_469_778u64)
+        );
+
+        // txSize = 10_000 + 68, flzUpperBound = txSize + txSize / 255 + 16 = 10_123
+        // estimatedSize = 836_500 * 10_123 - 42_585_600 = 8_425_303_900
+        // l1Fee = 8_425_303_900 * 88_314_697_781_397 / 1e12 = 744_078_167_644
+        assert_eq!(
+            l1_block_info.unsigned_tx_l1_fee_upper_bound(10_000, MantleSpecId::FJORD),
+            U256::from(744_078_167_644u64)
+        );
+    }
+
+    #[rstest::rstest]
+    fn test_estimate_tx_l1_fee_upper_bound(
+        #[values(MantleSpecId::REGOLITH, MantleSpecId::ECOTONE, MantleSpecId::FJORD)]
+        spec_id: MantleSpecId,
+        #[values(0, 1, 1_000)] data_len: usize,
+        #[values(false, true)] with_access_list: bool,
+    ) {
+        let l1_block_info = L1BlockInfo {
+            l1_base_fee: U256::from(7_789_722_029u64),
+            l1_fee_overhead: Some(U256::from(188)),
+            l1_base_fee_scalar: U256::from(10_000),
+            l1_blob_base_fee: Some(U256::from(1_000)),
+            l1_blob_base_fee_scalar: Some(U256::from(810_949)),
+            token_ratio: Some(U256::from(4368)),
+            ..Default::default()
+        };
+        let mut tx = template();
+        tx.data = (0..data_len)
+            .map(|i| (i % 7) as u8)
+            .collect::<Vec<_>>()
+            .into();
+        if with_access_list {
+            tx.access_list = AccessList(std::vec![AccessListItem {
+                address: BVM_ETH_ADDRESS,
+                storage_keys: std::vec![Default::default(); 3],
+            }]);
+        }
+
+        let estimate = l1_block_info.estimate_tx_l1_fee(&tx, spec_id);
+        assert!(estimate.l1_fee > U256::ZERO);
+        assert!(estimate.l1_fee <= estimate.l1_fee_upper_bound);
+        assert_eq!(
+            estimate.l1_fee,
+            l1_block_info.unsigned_tx_l1_fee(&encode_unsigned(&tx), spec_id)
+        );
+    }
 }
@@ -5,21 +5,43 @@
 #[cfg(not(feature = "std"))]
 extern crate alloc as std;
 
-pub mod bn128;
-pub mod fast_lz;
+pub mod bvm_eth;
+pub mod chain_spec;
+pub mod executor;
 pub mod handler_register;
 pub mod l1block;
+pub mod modexp;
+pub mod predeploys;
 pub mod result;
 pub mod spec;
+pub mod token_ratio;
 pub mod transaction;
 pub mod wiring;
 
+pub use bvm_eth::BVM_ETH_ADDRESS;
+pub use chain_spec::{ForkCondition, MantleChainSpec};
+pub use executor::{
+    MantleBlock, MantleBlockExecutionError, MantleBlockExecutionOutput, MantleBlockExecutor,
+    MantleReceipt,
+};
 pub use handler_register::{
     deduct_caller, end, last_frame_return, load_precompiles, mantle_handle_register, output,
     refund, reimburse_caller, reward_beneficiary, validate_env, validate_initial_tx_gas,
     validate_tx_against_state,
 };
-pub use l1block::{L1BlockInfo, BASE_FEE_RECIPIENT, L1_BLOCK_CONTRACT};
-pub use result::MantleHaltReason;
+pub use l1block::{
+    L1BlockInfo, L1BlockInfoError, L1FeeEstimate, BASE_FEE_RECIPIENT, L1_BLOCK_CONTRACT,
+    L1_FEE_RECIPIENT, L1_INFO_DEPOSITOR,
+};
+pub use op_stack::{bn128, fast_lz};
+pub use predeploys::Predeploys;
+pub use result::{
+    MantleDepositReceipt, MantleFeeReceipt, MantleHaltReason, MantleResultAndState,
+    DEPOSIT_RECEIPT_VERSION,
+};
 pub use spec::*;
-pub use transaction::{error::OpTransactionError, OpTransaction, OpTransactionType};
+pub use token_ratio::TokenRatioGasModel;
+pub use transaction::{
+    error::OpTransactionError, EnvelopeError, OpTransaction, OpTransactionType,
+    TransactionEnvelope, DEPOSIT_TX_TYPE,
+};
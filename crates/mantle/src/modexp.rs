@@ -0,0 +1,71 @@
+//! Modexp precompile of the Limb hardfork.
+//!
+//! Limb bounds the length of the base, exponent and modulus to 1024 bytes each (EIP-7823),
+//! inputs over the limit fail instead of being priced.
+use precompile::{
+    modexp, utilities::right_pad_with_offset, Precompile, PrecompileError, PrecompileResult,
+    PrecompileWithAddress,
+};
+use revm::primitives::{Bytes, U256};
+
+/// Maximum length in bytes of the base, exponent and modulus (EIP-7823).
+const LIMB_MAX_INPUT_SIZE: usize = 1024;
+
+pub(crate) const LIMB: PrecompileWithAddress =
+    PrecompileWithAddress(modexp::BERLIN.0, Precompile::Standard(run_limb));
+
+pub(crate) fn run_limb(input: &Bytes, gas_limit: u64) -> PrecompileResult {
+    let len = |offset| U256::from_be_bytes(right_pad_with_offset::<32>(input, offset).into_owned());
+    let max = U256::from(LIMB_MAX_INPUT_SIZE);
+    if len(0) > max {
+        return Err(PrecompileError::ModexpBaseOverflow.into());
+    }
+    if len(32) > max {
+        return Err(PrecompileError::ModexpExpOverflow.into());
+    }
+    if len(64) > max {
+        return Err(PrecompileError::ModexpModOverflow.into());
+    }
+    modexp::berlin_run(input, gas_limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::{precompile::PrecompileErrors, primitives::hex};
+
+    #[test]
+    fn test_modexp_limb() {
+        // 3 ** 5 % 7 == 5
+        let input = Bytes::from(
+            hex::decode(
+                "\
+      0000000000000000000000000000000000000000000000000000000000000001\
+      0000000000000000000000000000000000000000000000000000000000000001\
+      0000000000000000000000000000000000000000000000000000000000000001\
+      030507",
+            )
+            .unwrap(),
+        );
+        let outcome = run_limb(&input, 1_000).unwrap();
+        assert_eq!(outcome.bytes, Bytes::from_static(&[5]));
+
+        for (offset, error) in [
+            (0, PrecompileError::ModexpBaseOverflow),
+            (32, PrecompileError::ModexpExpOverflow),
+            (64, PrecompileError::ModexpModOverflow),
+        ] {
+            let mut input = [0u8; 96];
+            input[offset + 30..offset + 32].copy_from_slice(&1025u16.to_be_bytes());
+            assert!(matches!(
+                run_limb(&Bytes::copy_from_slice(&input), u64::MAX),
+                Err(PrecompileErrors::Error(e)) if e == error
+            ));
+        }
+
+        // inputs at the bound are still accepted.
+        let mut input = [0u8; 96];
+        input[30..32].copy_from_slice(&1024u16.to_be_bytes());
+        assert!(run_limb(&Bytes::copy_from_slice(&input), u64::MAX).is_ok());
+    }
+}
@@ -0,0 +1,376 @@
+//! Genesis state of the Mantle predeploys.
+//!
+//! Populates a database with the storage read by the Mantle handler, so the full
+//! [L1BlockInfo::try_fetch] path runs without a live node.
+//!
+//! The handler only reads the predeploy storage, so the contracts have no code by default. On
+//! Mantle the predeploys are proxies to implementation contracts that are not part of this
+//! crate, the code to call can be installed with [Predeploys::with_code].
+use crate::{
+    bvm_eth::{self, BVM_ETH_ADDRESS},
+    l1block::{
+        BASE_FEE_SCALAR_OFFSET, BLOB_BASE_FEE_SCALAR_OFFSET, ECOTONE_L1_BLOB_BASE_FEE_SLOT,
+        ECOTONE_L1_FEE_SCALARS_SLOT, GAS_ORACLE_CONTRACT, L1_BASE_FEE_SLOT, L1_OVERHEAD_SLOT,
+        L1_SCALAR_SLOT, TOKEN_RATIO_SLOT,
+    },
+    L1BlockInfo, MantleSpecId, BASE_FEE_RECIPIENT, L1_BLOCK_CONTRACT, L1_FEE_RECIPIENT,
+};
+use op_stack::{OpStackHardfork, OpStackUpgrade};
+use revm::{
+    bytecode::Bytecode,
+    database_interface::{Database, DatabaseCommit},
+    primitives::{Address, HashMap, U256},
+    state::{Account, AccountInfo, EvmStorageSlot},
+};
+use std::vec::Vec;
+
+/// Builder of the predeploy genesis state for a given spec.
+///
+/// The L1Block storage follows the layout of the spec: the Bedrock fee overhead and scalar,
+/// and from Ecotone on the packed fee scalars and the blob base fee.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Predeploys {
+    spec_id: MantleSpecId,
+    l1_block_info: L1BlockInfo,
+    fee_vaults: Vec<Address>,
+    bvm_eth_balances: Vec<(Address, U256)>,
+    codes: Vec<(Address, Bytecode)>,
+}
+
+impl Predeploys {
+    /// Creates the predeploys of the given spec, with zero L1 fee parameters, a token ratio of
+    /// one and the default fee vaults.
+    pub fn new(spec_id: MantleSpecId) -> Self {
+        Self {
+            spec_id,
+            l1_block_info: L1BlockInfo::default().with_token_ratio(U256::from(1)),
+            fee_vaults: std::vec![BASE_FEE_RECIPIENT, L1_FEE_RECIPIENT],
+            bvm_eth_balances: Vec::new(),
+            codes: Vec::new(),
+        }
+    }
+
+    /// Sets the L1 fee parameters and the token ratio.
+    ///
+    /// A missing token ratio is stored as one, and a missing L1 fee overhead as zero.
+    pub fn with_l1_block_info(mut self, l1_block_info: L1BlockInfo) -> Self {
+        self.l1_block_info = l1_block_info;
+        self
+    }
+
+    /// Sets the token ratio of the GasPriceOracle.
+    pub fn with_token_ratio(mut self, token_ratio: U256) -> Self {
+        self.l1_block_info.token_ratio = Some(token_ratio);
+        self
+    }
+
+    /// Sets the fee vault accounts to create, replacing the default ones.
+    pub fn with_fee_vaults(mut self, fee_vaults: impl IntoIterator<Item = Address>) -> Self {
+        self.fee_vaults = fee_vaults.into_iter().collect();
+        self
+    }
+
+    /// Adds a BVM_ETH balance, also accounted in the total supply.
+    pub fn with_bvm_eth_balance(mut self, owner: Address, amount: U256) -> Self {
+        self.bvm_eth_balances.push((owner, amount));
+        self
+    }
+
+    /// Installs `code` at the predeploy or fee vault `address`, replacing a previous code.
+    ///
+    /// An address that is not a predeploy nor a fee vault is created with the code.
+    pub fn with_code(mut self, address: Address, code: Bytecode) -> Self {
+        self.codes
+            .retain(|(code_address, _)| *code_address != address);
+        self.codes.push((address, code));
+        self
+    }
+
+    /// Returns the storage of the L1Block predeploy.
+    pub fn l1_block_storage(&self) -> Vec<(U256, U256)> {
+        let info = &self.l1_block_info;
+        let mut storage = std::vec![
+            (L1_BASE_FEE_SLOT, info.l1_base_fee),
+            (L1_OVERHEAD_SLOT, info.l1_fee_overhead.unwrap_or_default()),
+            (L1_SCALAR_SLOT, info.l1_base_fee_scalar),
+        ];
+        if self.spec_id.is_upgrade_enabled(OpStackUpgrade::Ecotone) {
+            let mut scalars = [0u8; 32];
+            scalars[BASE_FEE_SCALAR_OFFSET..BASE_FEE_SCALAR_OFFSET + 4]
+                .copy_from_slice(&info.l1_base_fee_scalar.saturating_to::<u32>().to_be_bytes());
+            scalars[BLOB_BASE_FEE_SCALAR_OFFSET..BLOB_BASE_FEE_SCALAR_OFFSET + 4].copy_from_slice(
+                &info
+                    .l1_blob_base_fee_scalar
+                    .unwrap_or_default()
+                    .saturating_to::<u32>()
+                    .to_be_bytes(),
+            );
+            storage.push((ECOTONE_L1_FEE_SCALARS_SLOT, U256::from_be_bytes(scalars)));
+            storage.push((
+                ECOTONE_L1_BLOB_BASE_FEE_SLOT,
+                info.l1_blob_base_fee.unwrap_or_default(),
+            ));
+        }
+        storage
+    }
+
+    /// Returns the storage of the GasPriceOracle predeploy.
+    pub fn gas_oracle_storage(&self) -> Vec<(U256, U256)> {
+        std::vec![(TOKEN_RATIO_SLOT, self.l1_block_info.get_token_ratio())]
+    }
+
+    /// Returns the storage of the BVM_ETH predeploy.
+    pub fn bvm_eth_storage(&self) -> Vec<(U256, U256)> {
+        let mut balances: HashMap<Address, U256> = HashMap::default();
+        for (owner, amount) in &self.bvm_eth_balances {
+            let balance = balances.entry(*owner).or_default();
+            *balance = balance.saturating_add(*amount);
+        }
+        let total_supply = balances
+            .values()
+            .fold(U256::ZERO, |total, balance| total.saturating_add(*balance));
+
+        let mut storage: Vec<_> = balances
+            .into_iter()
+            .map(|(owner, balance)| (bvm_eth::balance_slot(owner), balance))
+            .collect();
+        storage.push((bvm_eth::total_supply_slot(), total_supply));
+        storage
+    }
+
+    /// Returns the predeploy accounts, with their storage marked as changed.
+    ///
+    /// The accounts have a default balance and nonce, committing them replaces the existing
+    /// accounts. See [Predeploys::commit] to keep them.
+    pub fn into_state(self) -> HashMap<Address, Account> {
+        let mut state = HashMap::default();
+        for (address, storage) in [
+            (L1_BLOCK_CONTRACT, self.l1_block_storage()),
+            (GAS_ORACLE_CONTRACT, self.gas_oracle_storage()),
+            (BVM_ETH_ADDRESS, self.bvm_eth_storage()),
+        ] {
+            state.insert(address, account(storage));
+        }
+        for fee_vault in self.fee_vaults {
+            state
+                .entry(fee_vault)
+                .or_insert_with(|| account(Vec::new()));
+        }
+        for (address, code) in self.codes {
+            let account = state.entry(address).or_insert_with(|| account(Vec::new()));
+            account.info = AccountInfo {
+                code_hash: code.hash_slow(),
+                code: Some(code),
+                ..account.info.clone()
+            };
+        }
+        state
+    }
+
+    /// Commits the predeploy accounts to the database.
+    ///
+    /// Accounts that already exist keep their balance, nonce and code, unless the code is set
+    /// with [Predeploys::with_code], and only the predeploy storage slots are overwritten.
+    pub fn commit<DB: Database + DatabaseCommit>(self, db: &mut DB) -> Result<(), DB::Error> {
+        let with_code: Vec<_> = self.codes.iter().map(|(address, _)| *address).collect();
+        let mut state = self.into_state();
+        for (address, account) in &mut state {
+            if let Some(mut info) = db.basic(*address)? {
+                if with_code.contains(address) {
+                    info.code_hash = account.info.code_hash;
+                    info.code = account.info.code.take();
+                }
+                account.info = info;
+            }
+            for (slot, value) in &mut account.storage {
+                value.original_value = db.storage(*address, *slot)?;
+            }
+        }
+        db.commit(state);
+        Ok(())
+    }
+}
+
+fn account(storage: Vec<(U256, U256)>) -> Account {
+    let mut account = Account {
+        storage: storage
+            .into_iter()
+            .map(|(slot, value)| (slot, EvmStorageSlot::new_changed(U256::ZERO, value)))
+            .collect(),
+        ..Default::default()
+    };
+    account.mark_touch();
+    account
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{transaction::deposit::TxDeposit, wiring::MantleEvmWiring, OpTransaction};
+    use database::InMemoryDB;
+    use revm::{
+        bytecode::opcode::*,
+        database_interface::Database,
+        primitives::{address, TxKind},
+        Evm,
+    };
+
+    #[test]
+    fn test_try_fetch_bedrock() {
+        let l1_block_info = L1BlockInfo {
+            l1_base_fee: U256::from(7_789_722_029u64),
+            l1_fee_overhead: Some(U256::from(188)),
+            l1_base_fee_scalar: U256::from(10_000),
+            token_ratio: Some(U256::from(4368)),
+            ..Default::default()
+        };
+        let mut db = InMemoryDB::default();
+        Predeploys::new(MantleSpecId::BEDROCK)
+            .with_l1_block_info(l1_block_info.clone())
+            .commit(&mut db)
+            .unwrap();
+
+        assert_eq!(
+            L1BlockInfo::try_fetch(&mut db, MantleSpecId::BEDROCK).unwrap(),
+            l1_block_info
+        );
+    }
+
+    #[test]
+    fn test_try_fetch_ecotone() {
+        let l1_block_info = L1BlockInfo {
+            l1_base_fee: U256::from(1_000),
+            l1_base_fee_scalar: U256::from(1_368),
+            l1_blob_base_fee: Some(U256::from(2_000)),
+            l1_blob_base_fee_scalar: Some(U256::from(810_949)),
+            token_ratio: Some(U256::from(20)),
+            ..Default::default()
+        };
+        for spec_id in [
+            MantleSpecId::ECOTONE,
+            MantleSpecId::FJORD,
+            MantleSpecId::LATEST,
+        ] {
+            let mut db = InMemoryDB::default();
+            Predeploys::new(spec_id)
+                .with_l1_block_info(l1_block_info.clone())
+                .commit(&mut db)
+                .unwrap();
+
+            assert_eq!(
+                L1BlockInfo::try_fetch(&mut db, spec_id).unwrap(),
+                l1_block_info,
+                "{spec_id:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_bvm_eth_and_fee_vaults() {
+        let alice = address!("0000000000000000000000000000000000000a11");
+        let bob = address!("0000000000000000000000000000000000000b0b");
+        let mut db = InMemoryDB::default();
+        Predeploys::new(MantleSpecId::LATEST)
+            .with_token_ratio(U256::from(3))
+            .with_bvm_eth_balance(alice, U256::from(10))
+            .with_bvm_eth_balance(bob, U256::from(5))
+            .with_bvm_eth_balance(alice, U256::from(1))
+            .commit(&mut db)
+            .unwrap();
+
+        let storage = |db: &mut InMemoryDB, slot| db.storage(BVM_ETH_ADDRESS, slot).unwrap();
+        assert_eq!(
+            storage(&mut db, bvm_eth::balance_slot(alice)),
+            U256::from(11)
+        );
+        assert_eq!(storage(&mut db, bvm_eth::balance_slot(bob)), U256::from(5));
+        assert_eq!(
+            storage(&mut db, bvm_eth::total_supply_slot()),
+            U256::from(16)
+        );
+        assert_eq!(
+            db.storage(GAS_ORACLE_CONTRACT, TOKEN_RATIO_SLOT).unwrap(),
+            U256::from(3)
+        );
+        assert!(db.accounts.contains_key(&BASE_FEE_RECIPIENT));
+        assert!(db.accounts.contains_key(&L1_FEE_RECIPIENT));
+    }
+
+    #[test]
+    fn test_commit_keeps_existing_accounts() {
+        let code = Bytecode::new_raw(std::vec![STOP].into());
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(
+            BASE_FEE_RECIPIENT,
+            AccountInfo {
+                balance: U256::from(100),
+                nonce: 2,
+                ..Default::default()
+            },
+        );
+        db.insert_account_info(
+            GAS_ORACLE_CONTRACT,
+            AccountInfo {
+                balance: U256::from(5),
+                code_hash: code.hash_slow(),
+                code: Some(code.clone()),
+                ..Default::default()
+            },
+        );
+        db.insert_account_storage(GAS_ORACLE_CONTRACT, U256::from(100), U256::from(7))
+            .unwrap();
+        Predeploys::new(MantleSpecId::LATEST)
+            .with_token_ratio(U256::from(3))
+            .commit(&mut db)
+            .unwrap();
+
+        let vault = db.basic(BASE_FEE_RECIPIENT).unwrap().unwrap();
+        assert_eq!((vault.balance, vault.nonce), (U256::from(100), 2));
+        let oracle = db.basic(GAS_ORACLE_CONTRACT).unwrap().unwrap();
+        assert_eq!(oracle.balance, U256::from(5));
+        assert_eq!(oracle.code_hash, code.hash_slow());
+        assert_eq!(
+            db.storage(GAS_ORACLE_CONTRACT, TOKEN_RATIO_SLOT).unwrap(),
+            U256::from(3)
+        );
+        // Only the predeploy slots are overwritten.
+        assert_eq!(
+            db.storage(GAS_ORACLE_CONTRACT, U256::from(100)).unwrap(),
+            U256::from(7)
+        );
+    }
+
+    #[test]
+    fn test_call_reaches_code() {
+        // Returns the token ratio read from storage.
+        let mut code = std::vec![PUSH32];
+        code.extend_from_slice(&TOKEN_RATIO_SLOT.to_be_bytes::<32>());
+        code.extend([SLOAD, PUSH1, 0, MSTORE, PUSH1, 32, PUSH1, 0, RETURN]);
+        let code = Bytecode::new_raw(code.into());
+
+        let mut db = InMemoryDB::default();
+        Predeploys::new(MantleSpecId::LATEST)
+            .with_token_ratio(U256::from(3))
+            .with_code(GAS_ORACLE_CONTRACT, code.clone())
+            .commit(&mut db)
+            .unwrap();
+        let info = db.basic(GAS_ORACLE_CONTRACT).unwrap().unwrap();
+        assert_eq!(info.code_hash, code.hash_slow());
+
+        let mut evm = Evm::<MantleEvmWiring<InMemoryDB, ()>>::builder()
+            .with_db(db)
+            .with_default_ext_ctx()
+            .with_spec_id(MantleSpecId::LATEST)
+            .with_tx_env(OpTransaction::Deposit(TxDeposit {
+                to: TxKind::Call(GAS_ORACLE_CONTRACT),
+                gas_limit: 100_000,
+                ..Default::default()
+            }))
+            .build();
+        let result = evm.transact().unwrap().result;
+        assert_eq!(
+            result.output().unwrap().as_ref(),
+            U256::from(3).to_be_bytes::<32>()
+        );
+    }
+}
@@ -1,4 +1,13 @@
-use revm::wiring::result::HaltReason;
+use crate::{
+    wiring::{MantleContextTrait, MantleWiring},
+    MantleSpecId,
+};
+use revm::{
+    primitives::U256,
+    state::EvmState,
+    wiring::result::{EVMResultGeneric, ExecutionResult, HaltReason, ResultAndState},
+    Evm,
+};
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -12,3 +21,98 @@ impl From<HaltReason> for MantleHaltReason {
         Self::Base(value)
     }
 }
+
+/// Fee breakdown of a non-deposit transaction, similar to the L1 fields of OP Stack receipts.
+///
+/// The fees are the amounts actually credited to the fee vaults and the beneficiary.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MantleFeeReceipt {
+    /// The L1 data fee, paid to the L1 fee vault.
+    pub l1_fee: U256,
+    /// The L1 gas used to post the transaction.
+    pub l1_gas_used: U256,
+    /// The base fee of the L1 origin block.
+    pub l1_gas_price: U256,
+    /// The L1 fee scalar, the base fee scalar after Ecotone.
+    pub l1_fee_scalar: U256,
+    /// The L1 blob base fee. None before Ecotone.
+    pub l1_blob_base_fee: Option<U256>,
+    /// The L1 blob base fee scalar. None before Ecotone.
+    pub l1_blob_base_fee_scalar: Option<U256>,
+    /// The token ratio applied to the intrinsic gas.
+    pub token_ratio: U256,
+    /// The L2 base fee portion, paid to the base fee vault.
+    pub base_fee: U256,
+    /// The priority fee, paid to the beneficiary.
+    pub priority_fee: U256,
+}
+
+/// Version of the deposit receipts since Canyon.
+pub const DEPOSIT_RECEIPT_VERSION: u64 = 1;
+
+/// Deposit fields of the receipt of a deposit transaction.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MantleDepositReceipt {
+    /// The nonce of the sender before the deposit was executed. None before Regolith.
+    pub deposit_nonce: Option<u64>,
+    /// The version of the deposit receipt. None before Canyon.
+    pub deposit_receipt_version: Option<u64>,
+}
+
+impl MantleDepositReceipt {
+    /// Creates the deposit receipt of a sender with the given nonce before execution.
+    pub fn new(spec_id: MantleSpecId, deposit_nonce: u64) -> Self {
+        Self {
+            deposit_nonce: spec_id
+                .is_enabled_in(MantleSpecId::REGOLITH)
+                .then_some(deposit_nonce),
+            deposit_receipt_version: spec_id
+                .is_enabled_in(MantleSpecId::CANYON)
+                .then_some(DEPOSIT_RECEIPT_VERSION),
+        }
+    }
+}
+
+/// [ResultAndState] extended with the fee breakdown of the transaction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MantleResultAndState {
+    /// Status of execution
+    pub result: ExecutionResult<MantleHaltReason>,
+    /// State that got updated
+    pub state: EvmState,
+    /// Fee breakdown, None for deposit transactions.
+    pub fee_receipt: Option<MantleFeeReceipt>,
+    /// Deposit fields of the receipt, None for non-deposit transactions.
+    pub deposit_receipt: Option<MantleDepositReceipt>,
+}
+
+impl MantleResultAndState {
+    /// Executes the transaction of the [Evm] without committing the state, and returns the
+    /// result with the fee and deposit receipts.
+    ///
+    /// The receipts are taken out of the chain context, which is left without them.
+    pub fn transact<EvmWiringT: MantleWiring>(
+        evm: &mut Evm<'_, EvmWiringT>,
+    ) -> EVMResultGeneric<Self, EvmWiringT> {
+        *evm.context.evm.chain.fee_receipt_mut() = None;
+        *evm.context.evm.chain.deposit_receipt_mut() = None;
+        let ResultAndState { result, state } = evm.transact()?;
+        Ok(Self {
+            result,
+            state,
+            fee_receipt: evm.context.evm.chain.fee_receipt_mut().take(),
+            deposit_receipt: evm.context.evm.chain.deposit_receipt_mut().take(),
+        })
+    }
+}
+
+impl From<MantleResultAndState> for ResultAndState<MantleHaltReason> {
+    fn from(value: MantleResultAndState) -> Self {
+        Self {
+            result: value.result,
+            state: value.state,
+        }
+    }
+}
@@ -1,11 +1,15 @@
+use op_stack::{OpStackHardfork, OpStackUpgrade};
 use revm::{
     precompile::PrecompileSpecId,
     specification::hardfork::{Spec, SpecId},
 };
 
 /// Specification IDs for the mantle blockchain.
+///
+/// The discriminants are stable, Mantle hardforks get new values instead of renumbering the
+/// existing specs. Specs are ordered by activation, see [MantleSpecId::activation_order].
 #[repr(u8)]
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, enumn::N)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, enumn::N)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(non_camel_case_types)]
 pub enum MantleSpecId {
@@ -33,7 +37,13 @@ pub enum MantleSpecId {
     ECOTONE = 21,
     FJORD = 22,
     GRANITE = 23,
+    /// Mantle Everest, aligns the EVM with Cancun.
+    EVEREST = 26,
     PRAGUE = 24,
+    /// Mantle Skadi, aligns the EVM with Prague.
+    SKADI = 27,
+    /// Mantle Limb, bounds the input size of the modexp precompile (EIP-7823).
+    LIMB = 28,
     PRAGUE_EOF = 25,
     #[default]
     LATEST = u8::MAX,
@@ -55,7 +65,35 @@ impl MantleSpecId {
     /// Returns `true` if the given specification ID is enabled in this spec.
     #[inline]
     pub const fn enabled(our: Self, other: Self) -> bool {
-        our as u8 >= other as u8
+        our.activation_order() >= other.activation_order()
+    }
+
+    /// Returns the position of the spec in the activation order.
+    ///
+    /// This differs from the discriminant for the Mantle hardforks, which are activated
+    /// in between the Ethereum ones.
+    #[inline]
+    pub const fn activation_order(self) -> u8 {
+        match self {
+            Self::EVEREST => 24,
+            Self::PRAGUE => 25,
+            Self::SKADI => 26,
+            Self::LIMB => 27,
+            Self::PRAGUE_EOF => 28,
+            spec_id => spec_id as u8,
+        }
+    }
+
+    /// Returns the latest spec activated at `timestamp` in the given activation table.
+    ///
+    /// The table lists `(spec, activation timestamp)` pairs in activation order. Returns `None`
+    /// if no spec is active yet.
+    pub fn from_activation_table(activations: &[(Self, u64)], timestamp: u64) -> Option<Self> {
+        activations
+            .iter()
+            .rev()
+            .find(|(_, activation)| *activation <= timestamp)
+            .map(|(spec_id, _)| *spec_id)
     }
 
     /// Converts the `MantleSpecId` into a `SpecId`.
@@ -81,14 +119,27 @@ impl MantleSpecId {
             MantleSpecId::CANCUN
             | MantleSpecId::ECOTONE
             | MantleSpecId::FJORD
-            | MantleSpecId::GRANITE => SpecId::CANCUN,
-            MantleSpecId::PRAGUE => SpecId::PRAGUE,
+            | MantleSpecId::GRANITE
+            | MantleSpecId::EVEREST => SpecId::CANCUN,
+            MantleSpecId::PRAGUE | MantleSpecId::SKADI | MantleSpecId::LIMB => SpecId::PRAGUE,
             MantleSpecId::PRAGUE_EOF => SpecId::PRAGUE_EOF,
             MantleSpecId::LATEST => SpecId::LATEST,
         }
     }
 }
 
+impl PartialOrd for MantleSpecId {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for MantleSpecId {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.activation_order().cmp(&other.activation_order())
+    }
+}
+
 impl From<MantleSpecId> for SpecId {
     fn from(value: MantleSpecId) -> Self {
         value.into_eth_spec_id()
@@ -129,6 +180,41 @@ impl From<MantleSpecId> for PrecompileSpecId {
     }
 }
 
+impl From<OpStackUpgrade> for MantleSpecId {
+    fn from(upgrade: OpStackUpgrade) -> Self {
+        match upgrade {
+            OpStackUpgrade::Bedrock => Self::BEDROCK,
+            OpStackUpgrade::Regolith => Self::REGOLITH,
+            OpStackUpgrade::Canyon => Self::CANYON,
+            OpStackUpgrade::Ecotone => Self::ECOTONE,
+            OpStackUpgrade::Fjord => Self::FJORD,
+            OpStackUpgrade::Granite => Self::GRANITE,
+        }
+    }
+}
+
+impl OpStackHardfork for MantleSpecId {
+    /// The Mantle hardforks only change the EVM, they carry no OP stack upgrade after Regolith,
+    /// the last one activated by the Mantle networks. So they keep the Bedrock L1 cost function
+    /// and enable neither P256VERIFY nor the Granite pairing limit, even though they are ordered
+    /// after Granite.
+    fn op_stack_upgrade(self) -> Option<OpStackUpgrade> {
+        match self {
+            Self::BEDROCK => Some(OpStackUpgrade::Bedrock),
+            Self::REGOLITH | Self::SHANGHAI | Self::EVEREST | Self::SKADI | Self::LIMB => {
+                Some(OpStackUpgrade::Regolith)
+            }
+            Self::CANYON | Self::CANCUN => Some(OpStackUpgrade::Canyon),
+            Self::ECOTONE => Some(OpStackUpgrade::Ecotone),
+            Self::FJORD => Some(OpStackUpgrade::Fjord),
+            Self::GRANITE | Self::PRAGUE | Self::PRAGUE_EOF | Self::LATEST => {
+                Some(OpStackUpgrade::Granite)
+            }
+            _ => None,
+        }
+    }
+}
+
 /// String identifiers for Mantle hardforks.
 pub mod id {
     // Re-export the Ethereum hardforks.
@@ -140,6 +226,9 @@ pub mod id {
     pub const ECOTONE: &str = "Ecotone";
     pub const FJORD: &str = "Fjord";
     pub const GRANITE: &str = "Granite";
+    pub const EVEREST: &str = "Everest";
+    pub const SKADI: &str = "Skadi";
+    pub const LIMB: &str = "Limb";
 }
 
 impl From<&str> for MantleSpecId {
@@ -170,6 +259,10 @@ impl From<&str> for MantleSpecId {
             id::CANYON => Self::CANYON,
             id::ECOTONE => Self::ECOTONE,
             id::FJORD => Self::FJORD,
+            id::GRANITE => Self::GRANITE,
+            id::EVEREST => Self::EVEREST,
+            id::SKADI => Self::SKADI,
+            id::LIMB => Self::LIMB,
             id::LATEST => Self::LATEST,
             _ => Self::LATEST,
         }
@@ -205,6 +298,9 @@ impl From<MantleSpecId> for &'static str {
             MantleSpecId::ECOTONE => id::ECOTONE,
             MantleSpecId::FJORD => id::FJORD,
             MantleSpecId::GRANITE => id::GRANITE,
+            MantleSpecId::EVEREST => id::EVEREST,
+            MantleSpecId::SKADI => id::SKADI,
+            MantleSpecId::LIMB => id::LIMB,
             MantleSpecId::LATEST => id::LATEST,
         }
     }
@@ -266,6 +362,9 @@ spec!(CANYON, CanyonSpec);
 spec!(ECOTONE, EcotoneSpec);
 spec!(FJORD, FjordSpec);
 spec!(GRANITE, GraniteSpec);
+spec!(EVEREST, EverestSpec);
+spec!(SKADI, SkadiSpec);
+spec!(LIMB, LimbSpec);
 
 #[macro_export]
 macro_rules! mantle_spec_to_generic {
@@ -358,6 +457,18 @@ macro_rules! mantle_spec_to_generic {
                 use $crate::FjordSpec as SPEC;
                 $e
             }
+            $crate::MantleSpecId::EVEREST => {
+                use $crate::EverestSpec as SPEC;
+                $e
+            }
+            $crate::MantleSpecId::SKADI => {
+                use $crate::SkadiSpec as SPEC;
+                $e
+            }
+            $crate::MantleSpecId::LIMB => {
+                use $crate::LimbSpec as SPEC;
+                $e
+            }
         }
     }};
 }
@@ -460,10 +571,26 @@ mod tests {
             MantleSpecId::FJORD,
             assert_eq!(SPEC::SPEC_ID, SpecId::CANCUN)
         );
+        mantle_spec_to_generic!(
+            MantleSpecId::GRANITE,
+            assert_eq!(SPEC::SPEC_ID, SpecId::CANCUN)
+        );
+        mantle_spec_to_generic!(
+            MantleSpecId::EVEREST,
+            assert_eq!(SPEC::SPEC_ID, SpecId::CANCUN)
+        );
         mantle_spec_to_generic!(
             MantleSpecId::PRAGUE,
             assert_eq!(SPEC::SPEC_ID, SpecId::PRAGUE)
         );
+        mantle_spec_to_generic!(
+            MantleSpecId::SKADI,
+            assert_eq!(SPEC::SPEC_ID, SpecId::PRAGUE)
+        );
+        mantle_spec_to_generic!(
+            MantleSpecId::LIMB,
+            assert_eq!(SPEC::SPEC_ID, SpecId::PRAGUE)
+        );
         mantle_spec_to_generic!(
             MantleSpecId::LATEST,
             assert_eq!(SPEC::SPEC_ID, SpecId::LATEST)
@@ -564,10 +691,22 @@ mod tests {
             MantleSpecId::GRANITE,
             assert_eq!(SPEC::MANTLE_SPEC_ID, MantleSpecId::GRANITE)
         );
+        mantle_spec_to_generic!(
+            MantleSpecId::EVEREST,
+            assert_eq!(SPEC::MANTLE_SPEC_ID, MantleSpecId::EVEREST)
+        );
         mantle_spec_to_generic!(
             MantleSpecId::PRAGUE,
             assert_eq!(SPEC::MANTLE_SPEC_ID, MantleSpecId::PRAGUE)
         );
+        mantle_spec_to_generic!(
+            MantleSpecId::SKADI,
+            assert_eq!(SPEC::MANTLE_SPEC_ID, MantleSpecId::SKADI)
+        );
+        mantle_spec_to_generic!(
+            MantleSpecId::LIMB,
+            assert_eq!(SPEC::MANTLE_SPEC_ID, MantleSpecId::LIMB)
+        );
         mantle_spec_to_generic!(
             MantleSpecId::PRAGUE_EOF,
             assert_eq!(SPEC::MANTLE_SPEC_ID, MantleSpecId::PRAGUE_EOF)
@@ -797,4 +936,126 @@ mod tests {
             MantleSpecId::FJORD
         ));
     }
+
+    #[test]
+    fn test_skadi_post_merge_hardforks() {
+        assert!(SkadiSpec::mantle_enabled(MantleSpecId::CANCUN));
+        assert!(SkadiSpec::mantle_enabled(MantleSpecId::GRANITE));
+        assert!(SkadiSpec::mantle_enabled(MantleSpecId::EVEREST));
+        assert!(SkadiSpec::mantle_enabled(MantleSpecId::PRAGUE));
+        assert!(SkadiSpec::mantle_enabled(MantleSpecId::SKADI));
+        assert!(!SkadiSpec::mantle_enabled(MantleSpecId::LIMB));
+        assert!(!SkadiSpec::mantle_enabled(MantleSpecId::LATEST));
+        assert!(SkadiSpec::enabled(SpecId::PRAGUE));
+    }
+
+    #[test]
+    fn test_limb_post_merge_hardforks() {
+        assert!(LimbSpec::mantle_enabled(MantleSpecId::EVEREST));
+        assert!(LimbSpec::mantle_enabled(MantleSpecId::SKADI));
+        assert!(LimbSpec::mantle_enabled(MantleSpecId::LIMB));
+        assert!(!LimbSpec::mantle_enabled(MantleSpecId::PRAGUE_EOF));
+        assert!(!LimbSpec::mantle_enabled(MantleSpecId::LATEST));
+    }
+
+    #[test]
+    fn test_mantle_hardfork_names() {
+        for spec_id in [
+            MantleSpecId::GRANITE,
+            MantleSpecId::EVEREST,
+            MantleSpecId::SKADI,
+            MantleSpecId::LIMB,
+        ] {
+            let name: &'static str = spec_id.into();
+            assert_eq!(MantleSpecId::from(name), spec_id);
+        }
+    }
+
+    #[test]
+    fn test_discriminants() {
+        assert_eq!(MantleSpecId::GRANITE as u8, 23);
+        assert_eq!(MantleSpecId::PRAGUE as u8, 24);
+        assert_eq!(MantleSpecId::PRAGUE_EOF as u8, 25);
+        assert_eq!(MantleSpecId::EVEREST as u8, 26);
+        assert_eq!(MantleSpecId::SKADI as u8, 27);
+        assert_eq!(MantleSpecId::LIMB as u8, 28);
+        assert_eq!(MantleSpecId::LATEST as u8, u8::MAX);
+        assert_eq!(MantleSpecId::try_from_u8(24), Some(MantleSpecId::PRAGUE));
+    }
+
+    #[test]
+    fn test_activation_order() {
+        let mut spec_ids: Vec<_> = (0..=u8::MAX)
+            .filter_map(MantleSpecId::try_from_u8)
+            .collect();
+        spec_ids.sort();
+        assert_eq!(
+            spec_ids[23..],
+            [
+                MantleSpecId::GRANITE,
+                MantleSpecId::EVEREST,
+                MantleSpecId::PRAGUE,
+                MantleSpecId::SKADI,
+                MantleSpecId::LIMB,
+                MantleSpecId::PRAGUE_EOF,
+                MantleSpecId::LATEST,
+            ]
+        );
+        assert!(MantleSpecId::PRAGUE_EOF.is_enabled_in(MantleSpecId::LIMB));
+        assert!(!MantleSpecId::EVEREST.is_enabled_in(MantleSpecId::PRAGUE));
+    }
+
+    #[test]
+    fn test_from_activation_table() {
+        let activations = [
+            (MantleSpecId::REGOLITH, 0),
+            (MantleSpecId::EVEREST, 100),
+            (MantleSpecId::SKADI, 200),
+            (MantleSpecId::LIMB, 300),
+        ];
+        let spec_at = |timestamp| MantleSpecId::from_activation_table(&activations, timestamp);
+        assert_eq!(spec_at(0), Some(MantleSpecId::REGOLITH));
+        assert_eq!(spec_at(99), Some(MantleSpecId::REGOLITH));
+        assert_eq!(spec_at(100), Some(MantleSpecId::EVEREST));
+        assert_eq!(spec_at(299), Some(MantleSpecId::SKADI));
+        assert_eq!(spec_at(u64::MAX), Some(MantleSpecId::LIMB));
+        assert_eq!(
+            MantleSpecId::from_activation_table(&activations[1..], 0),
+            None
+        );
+    }
+
+    #[test]
+    fn test_op_stack_upgrade() {
+        let upgrades = [
+            (OpStackUpgrade::Bedrock, MantleSpecId::BEDROCK),
+            (OpStackUpgrade::Regolith, MantleSpecId::REGOLITH),
+            (OpStackUpgrade::Canyon, MantleSpecId::CANYON),
+            (OpStackUpgrade::Ecotone, MantleSpecId::ECOTONE),
+            (OpStackUpgrade::Fjord, MantleSpecId::FJORD),
+            (OpStackUpgrade::Granite, MantleSpecId::GRANITE),
+        ];
+        let mantle_hardforks = [
+            MantleSpecId::EVEREST,
+            MantleSpecId::SKADI,
+            MantleSpecId::LIMB,
+        ];
+        for spec_id in (0..=u8::MAX).filter_map(MantleSpecId::try_from_u8) {
+            if mantle_hardforks.contains(&spec_id) {
+                assert_eq!(
+                    spec_id.op_stack_upgrade(),
+                    Some(OpStackUpgrade::Regolith),
+                    "{spec_id:?}"
+                );
+                continue;
+            }
+            for (upgrade, upgrade_spec_id) in upgrades {
+                assert_eq!(
+                    spec_id.is_upgrade_enabled(upgrade),
+                    spec_id.is_enabled_in(upgrade_spec_id),
+                    "{spec_id:?} {upgrade:?}"
+                );
+            }
+        }
+    }
 }
@@ -0,0 +1,131 @@
+//! Token ratio gas model.
+//!
+//! Mantle uses MNT as its gas token while the L1 data fee is paid in ETH. The `tokenRatio`
+//! stored in the GasPriceOracle predeploy converts between the two, and it is applied once,
+//! to the intrinsic gas of non-deposit transactions. From then on every step of the fee
+//! accounting works in (already scaled) gas units:
+//!
+//! - validation charges `intrinsic_gas * token_ratio` against the gas limit,
+//! - the caller is charged `gas_limit * gas_price` up front, as on mainnet,
+//! - unused gas is reimbursed at `gas_price` without scaling it again, as on mainnet,
+//! - the beneficiary and the base fee vault are paid for the reported `gas_used`.
+//!
+//! As the ratio is applied only once, the amount taken from the caller equals the amount
+//! credited to the coinbase and the fee vaults.
+use crate::MantleSpecId;
+use revm::{interpreter::Gas, primitives::U256, wiring::result::InvalidTransaction};
+
+/// Token ratio aware gas accounting for a single transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TokenRatioGasModel {
+    spec_id: MantleSpecId,
+    token_ratio: U256,
+}
+
+impl TokenRatioGasModel {
+    /// Creates the gas model for the given spec and token ratio.
+    pub const fn new(spec_id: MantleSpecId, token_ratio: U256) -> Self {
+        Self {
+            spec_id,
+            token_ratio,
+        }
+    }
+
+    /// Creates the gas model of a deposit transaction.
+    ///
+    /// Deposits are prepaid on L1, so their gas is never scaled by the token ratio.
+    pub const fn deposit(spec_id: MantleSpecId) -> Self {
+        Self::new(spec_id, U256::from_limbs([1, 0, 0, 0]))
+    }
+
+    /// Returns `true` if the token ratio is applied in this spec.
+    ///
+    /// The token ratio was introduced together with the L1 block info, at Bedrock.
+    pub const fn is_enabled(&self) -> bool {
+        self.spec_id.is_enabled_in(MantleSpecId::BEDROCK)
+    }
+
+    /// Returns the token ratio in effect, `1` if the model is not enabled.
+    pub fn token_ratio(&self) -> U256 {
+        if self.is_enabled() {
+            self.token_ratio
+        } else {
+            U256::from(1)
+        }
+    }
+
+    /// Returns the initial gas spend of a transaction with the given intrinsic gas.
+    ///
+    /// Fails if the scaled intrinsic gas does not fit in the gas limit.
+    pub fn initial_gas(
+        &self,
+        intrinsic_gas: u64,
+        gas_limit: u64,
+    ) -> Result<u64, InvalidTransaction> {
+        let token_ratio = u64::try_from(self.token_ratio())
+            .map_err(|_| InvalidTransaction::CallGasCostMoreThanGasLimit)?;
+        intrinsic_gas
+            .checked_mul(token_ratio)
+            .filter(|initial_gas| *initial_gas <= gas_limit)
+            .ok_or(InvalidTransaction::CallGasCostMoreThanGasLimit)
+    }
+
+    /// Returns the gas used by the transaction, as reported in the execution result.
+    pub fn gas_used(&self, gas: &Gas) -> u64 {
+        gas.spent() - gas.refunded() as u64
+    }
+
+    /// Returns the fee paid for the used gas at the given price.
+    ///
+    /// This is used for both the beneficiary tip and the base fee vault.
+    pub fn gas_fee(&self, gas: &Gas, price: U256) -> U256 {
+        price.saturating_mul(U256::from(self.gas_used(gas)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_initial_gas() {
+        let model = TokenRatioGasModel::new(MantleSpecId::BEDROCK, U256::from(10));
+        assert_eq!(model.initial_gas(21_000, 210_000), Ok(210_000));
+        assert_eq!(
+            model.initial_gas(21_000, 209_999),
+            Err(InvalidTransaction::CallGasCostMoreThanGasLimit)
+        );
+
+        let model = TokenRatioGasModel::new(MantleSpecId::BEDROCK, U256::MAX);
+        assert_eq!(
+            model.initial_gas(21_000, u64::MAX),
+            Err(InvalidTransaction::CallGasCostMoreThanGasLimit)
+        );
+    }
+
+    #[test]
+    fn test_disabled_before_bedrock() {
+        let model = TokenRatioGasModel::new(MantleSpecId::MERGE, U256::from(10));
+        assert!(!model.is_enabled());
+        assert_eq!(model.token_ratio(), U256::from(1));
+        assert_eq!(model.initial_gas(21_000, 21_000), Ok(21_000));
+    }
+
+    #[test]
+    fn test_deposit() {
+        let model = TokenRatioGasModel::deposit(MantleSpecId::LATEST);
+        assert_eq!(model.token_ratio(), U256::from(1));
+    }
+
+    #[test]
+    fn test_gas_fee() {
+        let gas_price = U256::from(7);
+        let model = TokenRatioGasModel::new(MantleSpecId::BEDROCK, U256::from(4));
+        let mut gas = Gas::new(100_000);
+        assert!(gas.record_cost(60_000));
+        gas.record_refund(5_000);
+
+        assert_eq!(model.gas_used(&gas), 55_000);
+        assert_eq!(model.gas_fee(&gas, gas_price), U256::from(55_000 * 7));
+    }
+}
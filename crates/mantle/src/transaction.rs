@@ -1,5 +1,8 @@
-pub mod abstraction;
 pub mod deposit;
-pub mod error;
+pub mod envelope;
 
-pub use abstraction::{OpTransaction, OpTransactionType, OpTxTrait};
+pub use envelope::{EnvelopeError, TransactionEnvelope, DEPOSIT_TX_TYPE};
+pub use op_stack::transaction::{abstraction, error, OpTransactionType, OpTxTrait};
+
+/// Transaction of the Mantle chain.
+pub type OpTransaction<T> = op_stack::transaction::OpTransaction<T, deposit::TxDeposit>;
@@ -3,15 +3,10 @@ use revm::{
     transaction::CommonTxFields,
 };
 
-pub trait DepositTransaction: CommonTxFields {
-    fn source_hash(&self) -> B256;
-
-    fn to(&self) -> TxKind;
-
-    fn mint(&self) -> Option<u128>;
-
-    fn is_system_transaction(&self) -> bool;
+pub use op_stack::transaction::DepositTransaction;
 
+/// Mantle deposit transaction, which also bridges ETH as BVM_ETH.
+pub trait MantleDepositTransaction: DepositTransaction {
     fn eth_value(&self) -> Option<u128>;
 
     fn eth_tx_hash(&self) -> Option<u128>;
@@ -61,6 +56,8 @@ impl CommonTxFields for TxDeposit {
         &self.input
     }
 
+    /// Deposits have no nonce, the nonce of the sender before execution is reported in
+    /// [MantleDepositReceipt](crate::MantleDepositReceipt).
     fn nonce(&self) -> u64 {
         panic!("There is no nonce in a deposit transaction");
     }
@@ -82,7 +79,9 @@ impl DepositTransaction for TxDeposit {
     fn is_system_transaction(&self) -> bool {
         self.is_system_transaction
     }
+}
 
+impl MantleDepositTransaction for TxDeposit {
     fn eth_value(&self) -> Option<u128> {
         self.eth_value
     }
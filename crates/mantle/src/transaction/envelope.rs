@@ -0,0 +1,468 @@
+//! Decoding and encoding of EIP-2718 typed transaction envelopes.
+//!
+//! Besides the Ethereum transaction types, Mantle blocks contain deposit transactions of type
+//! [DEPOSIT_TX_TYPE]. Their RLP payload extends the OP Stack deposit with the BVM_ETH
+//! `ethValue` and an optional trailing `ethTxValue`:
+//!
+//! `rlp([source_hash, from, to, mint, value, gas, is_system_tx, eth_value, data, eth_tx_value?])`
+use super::{deposit::TxDeposit, OpTransaction};
+use alloy_consensus::{
+    SignableTransaction, Transaction as _, TxEip1559, TxEip2930, TxEip4844, TxEip7702, TxEnvelope,
+    TxLegacy, TxType,
+};
+use alloy_eips::eip2718::{Decodable2718, Eip2718Error};
+use alloy_rlp::{Buf, BufMut, Decodable, Encodable, Header, EMPTY_STRING_CODE};
+use core::fmt::Display;
+use revm::{
+    primitives::{Bytes, U256},
+    specification::eip7702::{AuthorizationList, SignedAuthorization},
+    transaction::TransactionType,
+    wiring::default::TxEnv,
+};
+use std::vec::Vec;
+
+/// The EIP-2718 type of deposit transactions.
+pub const DEPOSIT_TX_TYPE: u8 = 0x7E;
+
+/// Error returned when decoding a transaction envelope.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EnvelopeError {
+    /// The RLP payload is malformed.
+    Rlp(alloy_rlp::Error),
+    /// The envelope has an unknown transaction type.
+    UnexpectedType(u8),
+    /// The sender can not be recovered from the signature.
+    InvalidSignature,
+}
+
+impl Display for EnvelopeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Rlp(error) => write!(f, "invalid transaction envelope: {error}"),
+            Self::UnexpectedType(ty) => write!(f, "unexpected transaction type: {ty:#x}"),
+            Self::InvalidSignature => write!(f, "invalid transaction signature"),
+        }
+    }
+}
+
+impl core::error::Error for EnvelopeError {}
+
+impl From<alloy_rlp::Error> for EnvelopeError {
+    fn from(value: alloy_rlp::Error) -> Self {
+        Self::Rlp(value)
+    }
+}
+
+impl From<Eip2718Error> for EnvelopeError {
+    fn from(value: Eip2718Error) -> Self {
+        match value {
+            Eip2718Error::RlpError(error) => Self::Rlp(error),
+            Eip2718Error::UnexpectedType(ty) => Self::UnexpectedType(ty),
+            _ => Self::Rlp(alloy_rlp::Error::Custom("unknown EIP-2718 error")),
+        }
+    }
+}
+
+/// Decoding and encoding of [OpTransaction] as raw EIP-2718 envelopes.
+pub trait TransactionEnvelope: Sized {
+    /// Decodes a raw EIP-2718 transaction, as found in a block body.
+    ///
+    /// The sender of signed transactions is recovered from the signature, and the raw bytes are
+    /// kept as the envelope used for the L1 cost.
+    fn decode_enveloped(bytes: &[u8]) -> Result<Self, EnvelopeError>;
+
+    /// Encodes the transaction as a raw EIP-2718 envelope.
+    ///
+    /// Signed transactions can not be re-encoded from [TxEnv] as it does not carry the
+    /// signature, their stored envelope is returned instead, `None` if there is none.
+    fn encode_enveloped(&self) -> Option<Bytes>;
+}
+
+impl TransactionEnvelope for OpTransaction<TxEnv> {
+    fn decode_enveloped(mut bytes: &[u8]) -> Result<Self, EnvelopeError> {
+        let raw = Bytes::copy_from_slice(bytes);
+        if bytes.first() == Some(&DEPOSIT_TX_TYPE) {
+            bytes.advance(1);
+            let deposit = TxDeposit::rlp_decode(&mut bytes)?;
+            ensure_consumed(bytes)?;
+            return Ok(Self::Deposit(deposit));
+        }
+        // Legacy transactions start with an RLP list header, typed ones with their type.
+        if let Some(&ty) = bytes.first().filter(|ty| **ty <= 0x7F) {
+            TxType::try_from(ty).map_err(|_| EnvelopeError::UnexpectedType(ty))?;
+        }
+
+        let envelope = TxEnvelope::decode_2718(&mut bytes)?;
+        ensure_consumed(bytes)?;
+        let caller = envelope
+            .recover_signer()
+            .map_err(|_| EnvelopeError::InvalidSignature)?;
+
+        let tx = TxEnv {
+            tx_type: match envelope.tx_type() {
+                TxType::Legacy => TransactionType::Legacy,
+                TxType::Eip2930 => TransactionType::Eip2930,
+                TxType::Eip1559 => TransactionType::Eip1559,
+                TxType::Eip4844 => TransactionType::Eip4844,
+                TxType::Eip7702 => TransactionType::Eip7702,
+            },
+            caller,
+            gas_limit: envelope.gas_limit(),
+            gas_price: U256::from(
+                envelope
+                    .gas_price()
+                    .unwrap_or_else(|| envelope.max_fee_per_gas()),
+            ),
+            transact_to: envelope.to(),
+            value: envelope.value(),
+            data: Bytes::copy_from_slice(envelope.input()),
+            nonce: envelope.nonce(),
+            chain_id: envelope.chain_id(),
+            access_list: envelope.access_list().cloned().unwrap_or_default(),
+            gas_priority_fee: envelope
+                .gas_price()
+                .is_none()
+                .then(|| envelope.max_priority_fee_per_gas().map(U256::from))
+                .flatten(),
+            blob_hashes: envelope
+                .blob_versioned_hashes()
+                .map(<[_]>::to_vec)
+                .unwrap_or_default(),
+            max_fee_per_blob_gas: envelope.max_fee_per_blob_gas().map(U256::from),
+            authorization_list: envelope
+                .authorization_list()
+                .map(<[_]>::to_vec)
+                .unwrap_or_default()
+                .into(),
+        };
+
+        Ok(Self::Base {
+            tx,
+            enveloped_tx: Some(raw),
+        })
+    }
+
+    fn encode_enveloped(&self) -> Option<Bytes> {
+        match self {
+            Self::Base { enveloped_tx, .. } => enveloped_tx.clone(),
+            Self::Deposit(deposit) => Some(deposit.encoded_2718()),
+        }
+    }
+}
+
+impl TxDeposit {
+    /// Encodes the deposit as a raw EIP-2718 envelope.
+    pub fn encoded_2718(&self) -> Bytes {
+        let mut out = Vec::with_capacity(1 + self.rlp_encoded_length());
+        out.put_u8(DEPOSIT_TX_TYPE);
+        self.rlp_encode(&mut out);
+        out.into()
+    }
+
+    /// Decodes the RLP list of a deposit, without the type byte.
+    pub fn rlp_decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let header = Header::decode(buf)?;
+        if !header.list {
+            return Err(alloy_rlp::Error::UnexpectedString);
+        }
+        if buf.len() < header.payload_length {
+            return Err(alloy_rlp::Error::InputTooShort);
+        }
+        let (mut payload, rest) = buf.split_at(header.payload_length);
+        *buf = rest;
+        let payload = &mut payload;
+
+        let deposit = Self {
+            source_hash: Decodable::decode(payload)?,
+            from: Decodable::decode(payload)?,
+            to: Decodable::decode(payload)?,
+            mint: decode_nil(payload)?,
+            value: Decodable::decode(payload)?,
+            gas_limit: Decodable::decode(payload)?,
+            is_system_transaction: Decodable::decode(payload)?,
+            eth_value: decode_nil(payload)?,
+            input: Decodable::decode(payload)?,
+            eth_tx_hash: if payload.is_empty() {
+                None
+            } else {
+                Some(Decodable::decode(payload)?)
+            },
+        };
+        if !payload.is_empty() {
+            return Err(alloy_rlp::Error::ListLengthMismatch {
+                expected: header.payload_length - payload.len(),
+                got: header.payload_length,
+            });
+        }
+        Ok(deposit)
+    }
+
+    /// Encodes the RLP list of a deposit, without the type byte.
+    pub fn rlp_encode(&self, out: &mut dyn BufMut) {
+        Header {
+            list: true,
+            payload_length: self.rlp_payload_length(),
+        }
+        .encode(out);
+        self.source_hash.encode(out);
+        self.from.encode(out);
+        self.to.encode(out);
+        encode_nil(self.mint, out);
+        self.value.encode(out);
+        self.gas_limit.encode(out);
+        self.is_system_transaction.encode(out);
+        encode_nil(self.eth_value, out);
+        self.input.encode(out);
+        if let Some(eth_tx_value) = self.eth_tx_hash {
+            eth_tx_value.encode(out);
+        }
+    }
+
+    /// Returns the length of the RLP list of a deposit, without the type byte.
+    pub fn rlp_encoded_length(&self) -> usize {
+        let payload_length = self.rlp_payload_length();
+        Header {
+            list: true,
+            payload_length,
+        }
+        .length()
+            + payload_length
+    }
+
+    fn rlp_payload_length(&self) -> usize {
+        self.source_hash.length()
+            + self.from.length()
+            + self.to.length()
+            + self.mint.map_or(1, |mint| mint.length())
+            + self.value.length()
+            + self.gas_limit.length()
+            + self.is_system_transaction.length()
+            + self.eth_value.map_or(1, |eth_value| eth_value.length())
+            + self.input.length()
+            + self
+                .eth_tx_hash
+                .map_or(0, |eth_tx_value| eth_tx_value.length())
+    }
+}
+
+/// Encodes a transaction template as it is signed, in its EIP-2718 envelope without signature.
+///
+/// This is the input of the GasPriceOracle `getL1Fee`. Custom transaction types are encoded as
+/// EIP-1559 transactions.
+pub fn encode_unsigned(tx: &TxEnv) -> Vec<u8> {
+    let gas_price = tx.gas_price.saturating_to();
+    let max_priority_fee_per_gas = tx.gas_priority_fee.unwrap_or_default().saturating_to();
+    let chain_id = tx.chain_id.unwrap_or_default();
+    let to = tx.transact_to.to().copied().unwrap_or_default();
+
+    match tx.tx_type {
+        TransactionType::Legacy => TxLegacy {
+            chain_id: tx.chain_id,
+            nonce: tx.nonce,
+            gas_price,
+            gas_limit: tx.gas_limit,
+            to: tx.transact_to,
+            value: tx.value,
+            input: tx.data.clone(),
+        }
+        .encoded_for_signing(),
+        TransactionType::Eip2930 => TxEip2930 {
+            chain_id,
+            nonce: tx.nonce,
+            gas_price,
+            gas_limit: tx.gas_limit,
+            to: tx.transact_to,
+            value: tx.value,
+            access_list: tx.access_list.clone(),
+            input: tx.data.clone(),
+        }
+        .encoded_for_signing(),
+        TransactionType::Eip4844 => TxEip4844 {
+            chain_id,
+            nonce: tx.nonce,
+            gas_limit: tx.gas_limit,
+            max_fee_per_gas: gas_price,
+            max_priority_fee_per_gas,
+            to,
+            value: tx.value,
+            access_list: tx.access_list.clone(),
+            blob_versioned_hashes: tx.blob_hashes.clone(),
+            max_fee_per_blob_gas: tx.max_fee_per_blob_gas.unwrap_or_default().saturating_to(),
+            input: tx.data.clone(),
+        }
+        .encoded_for_signing(),
+        TransactionType::Eip7702 => TxEip7702 {
+            chain_id,
+            nonce: tx.nonce,
+            gas_limit: tx.gas_limit,
+            max_fee_per_gas: gas_price,
+            max_priority_fee_per_gas,
+            to,
+            value: tx.value,
+            access_list: tx.access_list.clone(),
+            authorization_list: signed_authorizations(&tx.authorization_list),
+            input: tx.data.clone(),
+        }
+        .encoded_for_signing(),
+        TransactionType::Eip1559 | TransactionType::Custom => TxEip1559 {
+            chain_id,
+            nonce: tx.nonce,
+            gas_limit: tx.gas_limit,
+            max_fee_per_gas: gas_price,
+            max_priority_fee_per_gas,
+            to: tx.transact_to,
+            value: tx.value,
+            access_list: tx.access_list.clone(),
+            input: tx.data.clone(),
+        }
+        .encoded_for_signing(),
+    }
+}
+
+/// Returns the signed authorizations of the list.
+fn signed_authorizations(authorization_list: &AuthorizationList) -> Vec<SignedAuthorization> {
+    match authorization_list {
+        AuthorizationList::Signed(signed) => signed.clone(),
+        AuthorizationList::Recovered(recovered) => recovered
+            .iter()
+            .map(|authorization| authorization.inner().clone())
+            .collect(),
+    }
+}
+
+/// Decodes a `rlp:"nil"` integer, where the empty string stands for `None`.
+fn decode_nil(buf: &mut &[u8]) -> alloy_rlp::Result<Option<u128>> {
+    if buf.first() == Some(&EMPTY_STRING_CODE) {
+        buf.advance(1);
+        return Ok(None);
+    }
+    Decodable::decode(buf).map(Some)
+}
+
+/// Encodes a `rlp:"nil"` integer, where `None` is the empty string.
+fn encode_nil(value: Option<u128>, out: &mut dyn BufMut) {
+    match value {
+        Some(value) => value.encode(out),
+        None => out.put_u8(EMPTY_STRING_CODE),
+    }
+}
+
+fn ensure_consumed(bytes: &[u8]) -> Result<(), EnvelopeError> {
+    if bytes.is_empty() {
+        Ok(())
+    } else {
+        Err(alloy_rlp::Error::UnexpectedLength.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_eips::eip2718::Encodable2718;
+    use k256::ecdsa::SigningKey;
+    use revm::primitives::{address, b256, hex, Address, TxKind};
+
+    #[test]
+    fn test_deposit_roundtrip() {
+        let deposit = TxDeposit {
+            source_hash: b256!("7113be8bbb6ff4bb99fae05639cf76cdecf5a1afbc033b9a01d8bb16b00b9a80"),
+            from: address!("deaddeaddeaddeaddeaddeaddeaddeaddead0001"),
+            to: TxKind::Call(address!("4200000000000000000000000000000000000015")),
+            mint: None,
+            value: U256::ZERO,
+            gas_limit: 1_000_000,
+            is_system_transaction: false,
+            input: Bytes::from_static(&[0x01, 0x5d, 0x8e, 0xb9]),
+            eth_value: Some(1_000),
+            eth_tx_hash: Some(400),
+        };
+
+        let encoded = deposit.encoded_2718();
+        assert_eq!(encoded[0], DEPOSIT_TX_TYPE);
+        assert_eq!(encoded.len(), 1 + deposit.rlp_encoded_length());
+
+        let decoded = OpTransaction::decode_enveloped(&encoded).unwrap();
+        assert_eq!(decoded, OpTransaction::Deposit(deposit));
+        assert_eq!(decoded.encode_enveloped(), Some(encoded));
+    }
+
+    #[test]
+    fn test_deposit_without_optional_fields() {
+        let deposit = TxDeposit {
+            from: address!("deaddeaddeaddeaddeaddeaddeaddeaddead0001"),
+            to: TxKind::Create,
+            mint: Some(10),
+            gas_limit: 21_000,
+            ..Default::default()
+        };
+
+        let encoded = deposit.encoded_2718();
+        let OpTransaction::Deposit(decoded) = OpTransaction::decode_enveloped(&encoded).unwrap()
+        else {
+            panic!("expected a deposit");
+        };
+        assert_eq!(decoded, deposit);
+        assert_eq!(decoded.eth_value, None);
+        assert_eq!(decoded.eth_tx_hash, None);
+    }
+
+    #[test]
+    fn test_decode_eip1559() {
+        let signing_key = SigningKey::from_slice(&[0x11; 32]).unwrap();
+        let caller = Address::from_public_key(signing_key.verifying_key());
+        let unsigned = TxEip1559 {
+            chain_id: 5000,
+            nonce: 2,
+            gas_limit: 39_152,
+            max_fee_per_gas: 11_248_804_566,
+            max_priority_fee_per_gas: 1_000_000_000,
+            to: TxKind::Call(address!("d9e1459a7a482635700cbc20bbaf52d495ab9c96")),
+            value: U256::from(1),
+            input: Bytes::from_static(&hex!("1b55ba3a")),
+            ..Default::default()
+        };
+        let signature = signing_key
+            .sign_prehash_recoverable(unsigned.signature_hash().as_slice())
+            .unwrap();
+        let unsigned_encoding = unsigned.encoded_for_signing();
+        let raw = TxEnvelope::from(unsigned.into_signed(signature.into())).encoded_2718();
+
+        let OpTransaction::Base {
+            tx,
+            enveloped_tx: Some(enveloped_tx),
+        } = OpTransaction::decode_enveloped(&raw).unwrap()
+        else {
+            panic!("expected a base transaction");
+        };
+        assert_eq!(enveloped_tx.as_ref(), raw.as_slice());
+        assert_eq!(tx.tx_type, TransactionType::Eip1559);
+        assert_eq!(tx.caller, caller);
+        assert_eq!(tx.chain_id, Some(5000));
+        assert_eq!(tx.nonce, 2);
+        assert_eq!(tx.gas_limit, 39_152);
+        assert_eq!(tx.gas_price, U256::from(11_248_804_566u64));
+        assert_eq!(tx.gas_priority_fee, Some(U256::from(1_000_000_000)));
+        assert_eq!(
+            tx.transact_to,
+            TxKind::Call(address!("d9e1459a7a482635700cbc20bbaf52d495ab9c96"))
+        );
+        assert_eq!(tx.value, U256::from(1));
+        assert_eq!(tx.data.as_ref(), hex!("1b55ba3a"));
+        assert_eq!(encode_unsigned(&tx), unsigned_encoding);
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert_eq!(
+            OpTransaction::decode_enveloped(&[0x7F, 0xc0]),
+            Err(EnvelopeError::UnexpectedType(0x7F))
+        );
+
+        let mut encoded = TxDeposit::default().encoded_2718().to_vec();
+        encoded.push(0x00);
+        assert_eq!(
+            OpTransaction::decode_enveloped(&encoded),
+            Err(EnvelopeError::Rlp(alloy_rlp::Error::UnexpectedLength))
+        );
+    }
+}
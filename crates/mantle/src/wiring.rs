@@ -1,12 +1,14 @@
 use crate::{
     mantle_handle_register,
-    transaction::{OpTransaction, OpTransactionType, OpTxTrait},
-    L1BlockInfo, MantleHaltReason, MantleSpecId, OpTransactionError,
+    transaction::{deposit::MantleDepositTransaction, OpTransaction, OpTransactionType, OpTxTrait},
+    L1BlockInfo, MantleDepositReceipt, MantleFeeReceipt, MantleHaltReason, MantleSpecId,
+    OpTransactionError, BASE_FEE_RECIPIENT, L1_FEE_RECIPIENT,
 };
 use core::marker::PhantomData;
 use revm::{
     database_interface::Database,
     handler::register::HandleRegisters,
+    primitives::Address,
     wiring::default::{block::BlockEnv, TxEnv},
     wiring::EvmWiring,
     EvmHandler,
@@ -18,6 +20,32 @@ pub trait MantleContextTrait {
 
     /// A mutable reference to the cached L1 block info.
     fn l1_block_info_mut(&mut self) -> &mut Option<L1BlockInfo>;
+
+    /// Returns `true` if the cached L1 block info was provided up front, for example decoded
+    /// from the L1 attributes deposit, and must not be read again from the predeploy storage.
+    fn is_l1_block_info_fixed(&self) -> bool {
+        false
+    }
+
+    /// The fee receipt of the last executed transaction, None for deposits.
+    fn fee_receipt(&self) -> Option<&MantleFeeReceipt>;
+
+    /// A mutable reference to the fee receipt of the last executed transaction.
+    fn fee_receipt_mut(&mut self) -> &mut Option<MantleFeeReceipt>;
+
+    /// The deposit fields of the receipt of the last executed transaction, None for
+    /// non-deposits.
+    fn deposit_receipt(&self) -> Option<&MantleDepositReceipt>;
+
+    /// A mutable reference to the deposit fields of the receipt of the last executed
+    /// transaction.
+    fn deposit_receipt_mut(&mut self) -> &mut Option<MantleDepositReceipt>;
+
+    /// The vault that receives the base fee of non-deposit transactions.
+    fn base_fee_recipient(&self) -> Address;
+
+    /// The vault that receives the L1 data fee of non-deposit transactions.
+    fn l1_fee_recipient(&self) -> Address;
 }
 
 /// Trait for an Mantle chain spec.
@@ -29,6 +57,7 @@ pub trait MantleWiring:
     Transaction: OpTxTrait<
         TransactionType = OpTransactionType,
         TransactionError = OpTransactionError,
+        DepositTx: MantleDepositTransaction,
     >,
 >
 {
@@ -42,6 +71,7 @@ impl<EvmWiringT> MantleWiring for EvmWiringT where
         Transaction: OpTxTrait<
             TransactionType = OpTransactionType,
             TransactionError = OpTransactionError,
+            DepositTx: MantleDepositTransaction,
         >,
     >
 {
@@ -76,9 +106,51 @@ impl<DB: Database, EXT> revm::EvmWiring for MantleEvmWiring<DB, EXT> {
 }
 
 /// Context for the Mantle chain.
-#[derive(Clone, Default, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Context {
     l1_block_info: Option<L1BlockInfo>,
+    l1_block_info_fixed: bool,
+    fee_receipt: Option<MantleFeeReceipt>,
+    deposit_receipt: Option<MantleDepositReceipt>,
+    base_fee_recipient: Address,
+    l1_fee_recipient: Address,
+}
+
+impl Context {
+    /// Sets the L1 block info used by all transactions, instead of reading it from the L1Block
+    /// and GasPriceOracle storage before each one.
+    ///
+    /// See [L1BlockInfo::try_from_calldata].
+    pub fn with_l1_block_info(mut self, l1_block_info: L1BlockInfo) -> Self {
+        self.l1_block_info = Some(l1_block_info);
+        self.l1_block_info_fixed = true;
+        self
+    }
+
+    /// Sets the vault that receives the base fee.
+    pub fn with_base_fee_recipient(mut self, base_fee_recipient: Address) -> Self {
+        self.base_fee_recipient = base_fee_recipient;
+        self
+    }
+
+    /// Sets the vault that receives the L1 data fee.
+    pub fn with_l1_fee_recipient(mut self, l1_fee_recipient: Address) -> Self {
+        self.l1_fee_recipient = l1_fee_recipient;
+        self
+    }
+}
+
+impl Default for Context {
+    fn default() -> Self {
+        Self {
+            l1_block_info: None,
+            l1_block_info_fixed: false,
+            fee_receipt: None,
+            deposit_receipt: None,
+            base_fee_recipient: BASE_FEE_RECIPIENT,
+            l1_fee_recipient: L1_FEE_RECIPIENT,
+        }
+    }
 }
 
 impl MantleContextTrait for Context {
@@ -89,4 +161,32 @@ impl MantleContextTrait for Context {
     fn l1_block_info_mut(&mut self) -> &mut Option<L1BlockInfo> {
         &mut self.l1_block_info
     }
+
+    fn is_l1_block_info_fixed(&self) -> bool {
+        self.l1_block_info_fixed
+    }
+
+    fn fee_receipt(&self) -> Option<&MantleFeeReceipt> {
+        self.fee_receipt.as_ref()
+    }
+
+    fn fee_receipt_mut(&mut self) -> &mut Option<MantleFeeReceipt> {
+        &mut self.fee_receipt
+    }
+
+    fn deposit_receipt(&self) -> Option<&MantleDepositReceipt> {
+        self.deposit_receipt.as_ref()
+    }
+
+    fn deposit_receipt_mut(&mut self) -> &mut Option<MantleDepositReceipt> {
+        &mut self.deposit_receipt
+    }
+
+    fn base_fee_recipient(&self) -> Address {
+        self.base_fee_recipient
+    }
+
+    fn l1_fee_recipient(&self) -> Address {
+        self.l1_fee_recipient
+    }
 }
@@ -1,17 +1,22 @@
+//! OP stack restrictions of the bn128 precompiles.
 use precompile::{
     bn128, {Precompile, PrecompileError, PrecompileResult, PrecompileWithAddress},
 };
 
-pub(crate) mod pair {
+/// The bn128 pairing precompile with the input size limit of Granite.
+pub mod pair {
     use super::*;
 
-    const GRANITE_MAX_INPUT_SIZE: usize = 112687;
-    pub(crate) const GRANITE: PrecompileWithAddress = PrecompileWithAddress(
+    /// The maximum input size of the pairing precompile since Granite.
+    pub const GRANITE_MAX_INPUT_SIZE: usize = 112687;
+    /// The bn128 pairing precompile limited to [GRANITE_MAX_INPUT_SIZE] bytes of input.
+    pub const GRANITE: PrecompileWithAddress = PrecompileWithAddress(
         bn128::pair::ADDRESS,
         Precompile::Standard(|input, gas_limit| run_pair(input, gas_limit)),
     );
 
-    pub(crate) fn run_pair(input: &[u8], gas_limit: u64) -> PrecompileResult {
+    /// Runs the pairing check, rejecting inputs larger than [GRANITE_MAX_INPUT_SIZE].
+    pub fn run_pair(input: &[u8], gas_limit: u64) -> PrecompileResult {
         if input.len() > GRANITE_MAX_INPUT_SIZE {
             return Err(PrecompileError::Bn128PairLength.into());
         }
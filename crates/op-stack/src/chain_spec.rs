@@ -0,0 +1,269 @@
+//! Hardfork activation schedule of an OP stack chain.
+use revm::{
+    primitives::U256,
+    specification::hardfork::SpecId,
+    transaction::Transaction,
+    wiring::{result::InvalidTransaction, Block},
+    EvmBuilder, EvmWiring,
+};
+use std::vec::Vec;
+
+#[cfg(feature = "serde")]
+use crate::OpStackUpgrade;
+
+/// Condition under which a hardfork is activated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ForkCondition {
+    /// Activated at the given block number.
+    Block(u64),
+    /// Activated at the given block timestamp.
+    Timestamp(u64),
+}
+
+impl ForkCondition {
+    /// Returns `true` if the fork is active at the given block number and timestamp.
+    pub const fn is_active_at(&self, block_number: u64, timestamp: u64) -> bool {
+        match *self {
+            Self::Block(block) => block_number >= block,
+            Self::Timestamp(time) => timestamp >= time,
+        }
+    }
+}
+
+/// Hardfork activation schedule of an OP stack chain.
+///
+/// Maps a block number and timestamp to the hardfork active in that block. Rollups use it with
+/// their own hardfork type, e.g. the Mantle and Optimism spec ids.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChainSpec<HardforkT> {
+    chain_id: u64,
+    /// Hardforks sorted by activation.
+    hardforks: Vec<(HardforkT, ForkCondition)>,
+}
+
+impl<HardforkT> ChainSpec<HardforkT>
+where
+    HardforkT: Copy + Ord + From<SpecId>,
+{
+    /// Creates a chain spec without any hardforks, everything runs as [SpecId::FRONTIER].
+    pub fn new(chain_id: u64) -> Self {
+        Self {
+            chain_id,
+            hardforks: Vec::new(),
+        }
+    }
+
+    /// Activates `hardfork` under the given condition, replacing a previous condition.
+    pub fn with_hardfork(mut self, hardfork: HardforkT, condition: ForkCondition) -> Self {
+        match self
+            .hardforks
+            .binary_search_by_key(&hardfork, |(hardfork, _)| *hardfork)
+        {
+            Ok(index) => self.hardforks[index].1 = condition,
+            Err(index) => self.hardforks.insert(index, (hardfork, condition)),
+        }
+        self
+    }
+
+    /// Activates every hardfork under its condition, see [ChainSpec::with_hardfork].
+    pub fn with_hardforks(
+        self,
+        hardforks: impl IntoIterator<Item = (HardforkT, ForkCondition)>,
+    ) -> Self {
+        hardforks
+            .into_iter()
+            .fold(self, |spec, (hardfork, condition)| {
+                spec.with_hardfork(hardfork, condition)
+            })
+    }
+
+    /// Returns the chain ID.
+    pub const fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    /// Returns the hardforks and their activation conditions, sorted by activation.
+    pub fn hardforks(&self) -> &[(HardforkT, ForkCondition)] {
+        &self.hardforks
+    }
+
+    /// Returns the hardfork active at the given block number and timestamp.
+    pub fn spec_id_at(&self, block_number: u64, timestamp: u64) -> HardforkT {
+        self.hardforks
+            .iter()
+            .rev()
+            .find(|(_, condition)| condition.is_active_at(block_number, timestamp))
+            .map(|(hardfork, _)| *hardfork)
+            .unwrap_or_else(|| SpecId::FRONTIER.into())
+    }
+
+    /// Returns the hardfork active in the given block.
+    pub fn spec_id_for_block<B: Block>(&self, block: &B) -> HardforkT {
+        self.spec_id_at(
+            saturating_to_u64(block.number()),
+            saturating_to_u64(block.timestamp()),
+        )
+    }
+
+    /// Configures the builder to execute transactions in `block`.
+    ///
+    /// Sets the block environment, the chain ID and the hardfork active in the block.
+    pub fn configure<'a, BuilderStage, EvmWiringT>(
+        &self,
+        builder: EvmBuilder<'a, BuilderStage, EvmWiringT>,
+        block: EvmWiringT::Block,
+    ) -> EvmBuilder<'a, BuilderStage, EvmWiringT>
+    where
+        EvmWiringT: EvmWiring<
+            Hardfork = HardforkT,
+            Transaction: Transaction<TransactionError: From<InvalidTransaction>>,
+        >,
+    {
+        let spec_id = self.spec_id_for_block(&block);
+        builder
+            .with_block_env(block)
+            .modify_cfg_env(|cfg| cfg.chain_id = self.chain_id)
+            .with_spec_id(spec_id)
+    }
+}
+
+/// Subset of the geth chain config of an OP stack chain, the `config` of a genesis JSON file.
+///
+/// Holds the activations of the Ethereum hardforks and OP stack upgrades up to Prague. Rollups
+/// with their own hardforks flatten it into their chain config.
+#[cfg(feature = "serde")]
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainConfig {
+    pub chain_id: u64,
+    pub homestead_block: Option<u64>,
+    pub dao_fork_block: Option<u64>,
+    pub eip150_block: Option<u64>,
+    pub eip158_block: Option<u64>,
+    pub byzantium_block: Option<u64>,
+    pub constantinople_block: Option<u64>,
+    pub petersburg_block: Option<u64>,
+    pub istanbul_block: Option<u64>,
+    pub muir_glacier_block: Option<u64>,
+    pub berlin_block: Option<u64>,
+    pub london_block: Option<u64>,
+    pub arrow_glacier_block: Option<u64>,
+    pub gray_glacier_block: Option<u64>,
+    pub merge_netsplit_block: Option<u64>,
+    pub bedrock_block: Option<u64>,
+    pub regolith_time: Option<u64>,
+    pub shanghai_time: Option<u64>,
+    pub canyon_time: Option<u64>,
+    pub cancun_time: Option<u64>,
+    pub ecotone_time: Option<u64>,
+    pub fjord_time: Option<u64>,
+    pub granite_time: Option<u64>,
+    pub prague_time: Option<u64>,
+}
+
+#[cfg(feature = "serde")]
+impl ChainConfig {
+    /// Returns the activated hardforks and their conditions.
+    pub fn hardforks<HardforkT>(&self) -> Vec<(HardforkT, ForkCondition)>
+    where
+        HardforkT: From<SpecId> + From<OpStackUpgrade>,
+    {
+        use ForkCondition::{Block, Timestamp};
+        let eth = |spec_id: SpecId, condition| (spec_id.into(), condition);
+        let op = |upgrade: OpStackUpgrade, condition| (upgrade.into(), condition);
+        [
+            eth(SpecId::HOMESTEAD, self.homestead_block.map(Block)),
+            eth(SpecId::DAO_FORK, self.dao_fork_block.map(Block)),
+            eth(SpecId::TANGERINE, self.eip150_block.map(Block)),
+            eth(SpecId::SPURIOUS_DRAGON, self.eip158_block.map(Block)),
+            eth(SpecId::BYZANTIUM, self.byzantium_block.map(Block)),
+            eth(SpecId::CONSTANTINOPLE, self.constantinople_block.map(Block)),
+            eth(SpecId::PETERSBURG, self.petersburg_block.map(Block)),
+            eth(SpecId::ISTANBUL, self.istanbul_block.map(Block)),
+            eth(SpecId::MUIR_GLACIER, self.muir_glacier_block.map(Block)),
+            eth(SpecId::BERLIN, self.berlin_block.map(Block)),
+            eth(SpecId::LONDON, self.london_block.map(Block)),
+            eth(SpecId::ARROW_GLACIER, self.arrow_glacier_block.map(Block)),
+            eth(SpecId::GRAY_GLACIER, self.gray_glacier_block.map(Block)),
+            eth(SpecId::MERGE, self.merge_netsplit_block.map(Block)),
+            op(OpStackUpgrade::Bedrock, self.bedrock_block.map(Block)),
+            op(OpStackUpgrade::Regolith, self.regolith_time.map(Timestamp)),
+            eth(SpecId::SHANGHAI, self.shanghai_time.map(Timestamp)),
+            op(OpStackUpgrade::Canyon, self.canyon_time.map(Timestamp)),
+            eth(SpecId::CANCUN, self.cancun_time.map(Timestamp)),
+            op(OpStackUpgrade::Ecotone, self.ecotone_time.map(Timestamp)),
+            op(OpStackUpgrade::Fjord, self.fjord_time.map(Timestamp)),
+            op(OpStackUpgrade::Granite, self.granite_time.map(Timestamp)),
+            eth(SpecId::PRAGUE, self.prague_time.map(Timestamp)),
+        ]
+        .into_iter()
+        .filter_map(|(hardfork, condition)| Some((hardfork, condition?)))
+        .collect()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<HardforkT> From<&ChainConfig> for ChainSpec<HardforkT>
+where
+    HardforkT: Copy + Ord + From<SpecId> + From<OpStackUpgrade>,
+{
+    fn from(config: &ChainConfig) -> Self {
+        Self::new(config.chain_id).with_hardforks(config.hardforks())
+    }
+}
+
+fn saturating_to_u64(value: &U256) -> u64 {
+    value.try_into().unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spec_id_at() {
+        let spec = ChainSpec::new(1)
+            .with_hardfork(SpecId::LONDON, ForkCondition::Block(10))
+            .with_hardfork(SpecId::MERGE, ForkCondition::Block(20))
+            .with_hardfork(SpecId::CANCUN, ForkCondition::Timestamp(300))
+            .with_hardfork(SpecId::SHANGHAI, ForkCondition::Timestamp(200));
+
+        assert_eq!(spec.spec_id_at(0, 0), SpecId::FRONTIER);
+        assert_eq!(spec.spec_id_at(10, 0), SpecId::LONDON);
+        assert_eq!(spec.spec_id_at(20, 0), SpecId::MERGE);
+        assert_eq!(spec.spec_id_at(20, 299), SpecId::SHANGHAI);
+        assert_eq!(spec.spec_id_at(20, 300), SpecId::CANCUN);
+        assert_eq!(
+            spec.hardforks().last(),
+            Some(&(SpecId::CANCUN, ForkCondition::Timestamp(300)))
+        );
+    }
+
+    #[test]
+    fn test_with_hardfork_replaces_condition() {
+        let spec = ChainSpec::new(1)
+            .with_hardfork(SpecId::CANCUN, ForkCondition::Timestamp(300))
+            .with_hardfork(SpecId::CANCUN, ForkCondition::Timestamp(400));
+        assert_eq!(
+            spec.hardforks(),
+            &[(SpecId::CANCUN, ForkCondition::Timestamp(400))]
+        );
+    }
+
+    #[test]
+    fn test_with_hardforks() {
+        let spec = ChainSpec::new(1).with_hardforks([
+            (SpecId::CANCUN, ForkCondition::Timestamp(300)),
+            (SpecId::LONDON, ForkCondition::Block(0)),
+        ]);
+        assert_eq!(
+            spec.hardforks(),
+            &[
+                (SpecId::LONDON, ForkCondition::Block(0)),
+                (SpecId::CANCUN, ForkCondition::Timestamp(300))
+            ]
+        );
+    }
+}
@@ -1,6 +1,6 @@
 /// Returns the length of the data after compression through FastLZ, based on
 // https://github.com/Vectorized/solady/blob/5315d937d79b335c668896d7533ac603adac5315/js/solady.js
-pub(crate) fn flz_compress_len(input: &[u8]) -> u32 {
+pub fn flz_compress_len(input: &[u8]) -> u32 {
     let mut idx: u32 = 2;
 
     let idx_limit: u32 = if input.len() < 13 {
@@ -105,9 +105,6 @@ fn u24(input: &[u8], idx: u32) -> u32 {
 
 #[cfg(test)]
 mod tests {
-    use crate::wiring::MantleEvmWiring;
-    use crate::OpTransaction;
-
     use super::*;
     use alloy_sol_types::sol;
     use alloy_sol_types::SolCall;
@@ -115,6 +112,7 @@ mod tests {
     use revm::{
         bytecode::Bytecode,
         primitives::{address, bytes, Bytes, TxKind, U256},
+        wiring::EthereumWiring,
         Evm,
     };
     use std::vec::Vec;
@@ -166,18 +164,14 @@ mod tests {
 
         let native_val = flz_compress_len(&input);
 
-        let mut evm = Evm::<MantleEvmWiring<BenchmarkDB, ()>>::builder()
+        let mut evm = Evm::<EthereumWiring<BenchmarkDB, ()>>::builder()
             .with_db(BenchmarkDB::new_bytecode(contract_bytecode.clone()))
             .with_default_ext_ctx()
             .modify_tx_env(|tx| {
-                let OpTransaction::Base { tx, enveloped_tx } = tx else {
-                    panic!("Default is base tx");
-                };
                 tx.caller = address!("1000000000000000000000000000000000000000");
                 tx.transact_to = TxKind::Call(address!("0000000000000000000000000000000000000000"));
                 tx.data = FastLz::fastLzCall::new((input,)).abi_encode().into();
                 tx.gas_limit = 300_000;
-                *enveloped_tx = Some(Bytes::default());
             })
             .build();
 
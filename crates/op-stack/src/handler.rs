@@ -0,0 +1,316 @@
+//! Handler hooks shared by the OP stack chains.
+//!
+//! The hooks only depend on the hardfork and on the deposit fields of the transaction, so every
+//! chain can call them from its own handler.
+use crate::{OpStackHardfork, OpStackUpgrade};
+use revm::{
+    database_interface::Database,
+    interpreter::{return_ok, return_revert, Gas, InstructionResult},
+    precompile::secp256r1,
+    primitives::{Address, U256},
+    specification::hardfork::SpecId,
+    state::Account,
+    wiring::{result::InvalidTransaction, EvmWiring},
+    ContextPrecompiles, JournaledState,
+};
+
+/// Deposit fields used by the gas accounting of the handler.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DepositGas {
+    /// Whether the deposit is a Bedrock system transaction.
+    pub is_system_transaction: bool,
+}
+
+/// Adjusts the gas of the last frame.
+///
+/// The gas limit is spent and the remaining gas and refunds are given back depending on the
+/// result, the transaction type and the hardfork. `deposit` is `None` for regular transactions.
+pub fn last_frame_gas<H: OpStackHardfork>(
+    spec_id: H,
+    gas: &mut Gas,
+    instruction_result: InstructionResult,
+    tx_gas_limit: u64,
+    deposit: Option<DepositGas>,
+) {
+    let is_regolith = spec_id.is_upgrade_enabled(OpStackUpgrade::Regolith);
+    let remaining = gas.remaining();
+    let refunded = gas.refunded();
+    // Spend the gas limit. Gas is reimbursed when the tx returns successfully.
+    *gas = Gas::new_spent(tx_gas_limit);
+
+    match instruction_result {
+        return_ok!() => {
+            // Deposit transactions report gas usage uniquely to other transactions due to
+            // them being pre-paid on L1.
+            //
+            // Hardfork Behavior:
+            // - Bedrock (success path):
+            //   - Deposit transactions (non-system) report their gas limit as the usage.
+            //     No refunds.
+            //   - Deposit transactions (system) report 0 gas used. No refunds.
+            //   - Regular transactions report gas usage as normal.
+            // - Regolith (success path):
+            //   - Deposit transactions (all) report their gas used as normal. Refunds
+            //     enabled.
+            //   - Regular transactions report their gas used as normal.
+            match deposit {
+                // System transactions were a special type of deposit transaction in
+                // the Bedrock hardfork that did not incur any gas costs.
+                Some(deposit) if !is_regolith => {
+                    if deposit.is_system_transaction {
+                        gas.erase_cost(tx_gas_limit);
+                    }
+                }
+                // For regular transactions prior to Regolith and all transactions after
+                // Regolith, gas is reported as normal.
+                _ => {
+                    gas.erase_cost(remaining);
+                    gas.record_refund(refunded);
+                }
+            }
+        }
+        return_revert!() => {
+            // Hardfork Behavior:
+            // - Bedrock (revert path):
+            //   - Deposit transactions (all) report the gas limit as the amount of gas
+            //     used on failure. No refunds.
+            //   - Regular transactions receive a refund on remaining gas as normal.
+            // - Regolith (revert path):
+            //   - Deposit transactions (all) report the actual gas used as the amount of
+            //     gas used on failure. Refunds on remaining gas enabled.
+            //   - Regular transactions receive a refund on remaining gas as normal.
+            if deposit.is_none() || is_regolith {
+                gas.erase_cost(remaining);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Calculates the final refund, unless refunds are disabled.
+///
+/// Prior to Regolith, deposit transactions did not receive gas refunds.
+pub fn final_refund<H: OpStackHardfork>(
+    spec_id: H,
+    gas: &mut Gas,
+    is_deposit: bool,
+    is_gas_refund_disabled: bool,
+) {
+    let is_regolith = spec_id.is_upgrade_enabled(OpStackUpgrade::Regolith);
+    if !is_gas_refund_disabled && (!is_deposit || is_regolith) {
+        gas.set_final_refund(spec_id.is_eth_enabled(SpecId::LONDON));
+    }
+}
+
+/// Adds the `mint` value of a deposit to the caller balance.
+///
+/// This is persisted even if the deposit reverts.
+pub fn mint_deposit(caller_account: &mut Account, mint: Option<u128>) {
+    if let Some(mint) = mint {
+        caller_account.info.balance += U256::from(mint);
+    }
+}
+
+/// Deducts the L1 data fee of a non-deposit transaction from the caller balance.
+///
+/// This is done after the gas fee is deducted, as the L1 data fee is not checked during
+/// validation.
+pub fn deduct_l1_cost(
+    caller_account: &mut Account,
+    l1_cost: U256,
+) -> Result<(), InvalidTransaction> {
+    if l1_cost > caller_account.info.balance {
+        return Err(InvalidTransaction::LackOfFundForMaxFee {
+            fee: l1_cost.into(),
+            balance: caller_account.info.balance.into(),
+        });
+    }
+    caller_account.info.balance -= l1_cost;
+    Ok(())
+}
+
+/// Credits `amount` to the fee vault at `address`.
+pub fn pay_fee_vault<DB: Database>(
+    journaled_state: &mut JournaledState,
+    db: &mut DB,
+    address: Address,
+    amount: U256,
+) -> Result<(), DB::Error> {
+    let mut vault = journaled_state.load_account(address, db)?;
+    vault.mark_touch();
+    vault.info.balance = vault.info.balance.saturating_add(amount);
+    Ok(())
+}
+
+/// Returns the caller account after a failed deposit, read from the database, and its nonce
+/// before the deposit.
+///
+/// The state changes of a failed deposit are discarded, but the caller nonce is bumped and the
+/// `mint` value is persisted.
+pub fn failed_deposit_caller<DB: Database>(
+    db: &mut DB,
+    caller: Address,
+    mint: Option<u128>,
+) -> Result<(Account, u64), DB::Error> {
+    let mut account = Account::from(db.basic(caller)?.unwrap_or_default());
+    let nonce = account.info.nonce;
+    account.info.nonce = nonce.saturating_add(1);
+    account.info.balance = account
+        .info
+        .balance
+        .saturating_add(U256::from(mint.unwrap_or_default()));
+    account.mark_touch();
+    Ok((account, nonce))
+}
+
+/// Returns the gas used by a failed deposit.
+///
+/// Post-Regolith, it is the gas limit of the transaction. Pre-Regolith, it is the gas limit
+/// for non system transactions and 0 for system transactions.
+pub fn failed_deposit_gas_used<H: OpStackHardfork>(
+    spec_id: H,
+    gas_limit: u64,
+    is_system_transaction: bool,
+) -> u64 {
+    if spec_id.is_upgrade_enabled(OpStackUpgrade::Regolith) || !is_system_transaction {
+        gas_limit
+    } else {
+        0
+    }
+}
+
+/// Returns `true` if a halted deposit is reported as an error, so the mint value is persisted
+/// and the caller nonce bumped by the `end` handler.
+pub fn is_halted_deposit_error<H: OpStackHardfork>(spec_id: H) -> bool {
+    spec_id.is_upgrade_enabled(OpStackUpgrade::Regolith)
+}
+
+/// Adds the precompiles introduced by the OP stack upgrades.
+pub fn extend_precompiles<EvmWiringT: EvmWiring, H: OpStackHardfork>(
+    precompiles: &mut ContextPrecompiles<EvmWiringT>,
+    spec_id: H,
+) {
+    if spec_id.is_upgrade_enabled(OpStackUpgrade::Fjord) {
+        precompiles.extend([
+            // EIP-7212: secp256r1 P256verify
+            secp256r1::P256VERIFY,
+        ])
+    }
+
+    if spec_id.is_upgrade_enabled(OpStackUpgrade::Granite) {
+        precompiles.extend([
+            // Restrict bn256Pairing input size
+            crate::bn128::pair::GRANITE,
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Copy)]
+    struct TestSpec(OpStackUpgrade);
+
+    impl From<TestSpec> for SpecId {
+        fn from(_: TestSpec) -> Self {
+            SpecId::CANCUN
+        }
+    }
+
+    impl OpStackHardfork for TestSpec {
+        fn op_stack_upgrade(self) -> Option<OpStackUpgrade> {
+            Some(self.0)
+        }
+    }
+
+    const BEDROCK: TestSpec = TestSpec(OpStackUpgrade::Bedrock);
+    const REGOLITH: TestSpec = TestSpec(OpStackUpgrade::Regolith);
+    const DEPOSIT: Option<DepositGas> = Some(DepositGas {
+        is_system_transaction: false,
+    });
+    const SYSTEM_DEPOSIT: Option<DepositGas> = Some(DepositGas {
+        is_system_transaction: true,
+    });
+
+    fn last_frame(
+        spec_id: TestSpec,
+        instruction_result: InstructionResult,
+        deposit: Option<DepositGas>,
+    ) -> Gas {
+        let mut gas = Gas::new(100);
+        assert!(gas.record_cost(10));
+        gas.record_refund(2);
+        last_frame_gas(spec_id, &mut gas, instruction_result, 100, deposit);
+        gas
+    }
+
+    #[test]
+    fn test_last_frame_gas_success() {
+        for (spec_id, deposit, spent, refunded) in [
+            (BEDROCK, None, 10, 2),
+            (BEDROCK, DEPOSIT, 100, 0),
+            (BEDROCK, SYSTEM_DEPOSIT, 0, 0),
+            (REGOLITH, None, 10, 2),
+            (REGOLITH, DEPOSIT, 10, 2),
+        ] {
+            let gas = last_frame(spec_id, InstructionResult::Stop, deposit);
+            assert_eq!(gas.spent(), spent);
+            assert_eq!(gas.refunded(), refunded);
+        }
+    }
+
+    #[test]
+    fn test_last_frame_gas_revert() {
+        for (spec_id, deposit, spent) in [
+            (BEDROCK, None, 10),
+            (BEDROCK, SYSTEM_DEPOSIT, 100),
+            (REGOLITH, DEPOSIT, 10),
+        ] {
+            let gas = last_frame(spec_id, InstructionResult::Revert, deposit);
+            assert_eq!(gas.spent(), spent);
+            assert_eq!(gas.refunded(), 0);
+        }
+    }
+
+    #[test]
+    fn test_deduct_l1_cost() {
+        let mut account = Account::default();
+        account.info.balance = U256::from(10);
+        assert_eq!(
+            deduct_l1_cost(&mut account, U256::from(11)),
+            Err(InvalidTransaction::LackOfFundForMaxFee {
+                fee: U256::from(11).into(),
+                balance: U256::from(10).into(),
+            })
+        );
+        assert_eq!(deduct_l1_cost(&mut account, U256::from(4)), Ok(()));
+        assert_eq!(account.info.balance, U256::from(6));
+    }
+
+    #[test]
+    fn test_failed_deposit_caller() {
+        let caller = Address::with_last_byte(1);
+        let mut db = database::InMemoryDB::default();
+        db.insert_account_info(
+            caller,
+            revm::state::AccountInfo {
+                nonce: 7,
+                balance: U256::from(1),
+                ..Default::default()
+            },
+        );
+        let (account, nonce) = failed_deposit_caller(&mut db, caller, Some(10)).unwrap();
+        assert_eq!(nonce, 7);
+        assert_eq!(account.info.nonce, 8);
+        assert_eq!(account.info.balance, U256::from(11));
+        assert!(account.is_touched());
+    }
+
+    #[test]
+    fn test_failed_deposit_gas_used() {
+        assert_eq!(failed_deposit_gas_used(BEDROCK, 100, false), 100);
+        assert_eq!(failed_deposit_gas_used(BEDROCK, 100, true), 0);
+        assert_eq!(failed_deposit_gas_used(REGOLITH, 100, true), 100);
+    }
+}
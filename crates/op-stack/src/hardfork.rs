@@ -0,0 +1,44 @@
+//! OP stack upgrades and their mapping from the hardforks of a rollup.
+use revm::specification::hardfork::SpecId;
+
+/// Upgrades of the OP stack that change the execution of a rollup.
+///
+/// Ordered by activation, an upgrade implies all the previous ones.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OpStackUpgrade {
+    /// Deposit transactions and the L1 data fee.
+    Bedrock,
+    /// Deposit gas accounting and removal of system transactions.
+    Regolith,
+    /// Shanghai on L2.
+    Canyon,
+    /// Cancun on L2 and the blob aware L1 cost function.
+    Ecotone,
+    /// FastLZ based L1 cost function and the P256VERIFY precompile.
+    Fjord,
+    /// Input size limit of the bn128 pairing precompile.
+    Granite,
+}
+
+/// Hardfork of a rollup built on the OP stack.
+///
+/// Maps every hardfork of the rollup to the latest OP stack upgrade it includes, which is how
+/// the shared building blocks decide which rules apply.
+pub trait OpStackHardfork: Copy + Into<SpecId> {
+    /// Returns the latest OP stack upgrade included in this hardfork, `None` before Bedrock.
+    fn op_stack_upgrade(self) -> Option<OpStackUpgrade>;
+
+    /// Returns `true` if the given OP stack upgrade is included in this hardfork.
+    #[inline]
+    fn is_upgrade_enabled(self, upgrade: OpStackUpgrade) -> bool {
+        self.op_stack_upgrade()
+            .is_some_and(|enabled| enabled >= upgrade)
+    }
+
+    /// Returns `true` if the given Ethereum hardfork is enabled in this hardfork.
+    #[inline]
+    fn is_eth_enabled(self, spec_id: SpecId) -> bool {
+        SpecId::enabled(self.into(), spec_id)
+    }
+}
@@ -0,0 +1,327 @@
+//! L1Block predeploy layout and the L1 cost functions of the OP stack.
+use crate::{fast_lz::flz_compress_len, OpStackHardfork, OpStackUpgrade};
+use revm::primitives::{address, Address, U256};
+
+/// Gas cost of a zero byte of calldata posted on L1.
+pub const ZERO_BYTE_COST: u64 = 4;
+/// Gas cost of a non-zero byte of calldata posted on L1.
+pub const NON_ZERO_BYTE_COST: u64 = 16;
+
+/// The two 4-byte Ecotone fee scalar values are packed into the same storage slot as the 8-byte sequence number.
+/// Byte offset within the storage slot of the 4-byte baseFeeScalar attribute.
+pub const BASE_FEE_SCALAR_OFFSET: usize = 16;
+/// The two 4-byte Ecotone fee scalar values are packed into the same storage slot as the 8-byte sequence number.
+/// Byte offset within the storage slot of the 4-byte blobBaseFeeScalar attribute.
+pub const BLOB_BASE_FEE_SCALAR_OFFSET: usize = 20;
+
+/// Storage slot of the L1 base fee.
+pub const L1_BASE_FEE_SLOT: U256 = U256::from_limbs([1u64, 0, 0, 0]);
+/// Storage slot of the L1 fee overhead, deprecated in Ecotone.
+pub const L1_OVERHEAD_SLOT: U256 = U256::from_limbs([5u64, 0, 0, 0]);
+/// Storage slot of the L1 fee scalar, deprecated in Ecotone.
+pub const L1_SCALAR_SLOT: U256 = U256::from_limbs([6u64, 0, 0, 0]);
+
+/// [ECOTONE_L1_BLOB_BASE_FEE_SLOT] was added in the Ecotone upgrade and stores the L1 blobBaseFee attribute.
+pub const ECOTONE_L1_BLOB_BASE_FEE_SLOT: U256 = U256::from_limbs([7u64, 0, 0, 0]);
+
+/// As of the ecotone upgrade, this storage slot stores the 32-bit basefeeScalar and blobBaseFeeScalar attributes at
+/// offsets [BASE_FEE_SCALAR_OFFSET] and [BLOB_BASE_FEE_SCALAR_OFFSET] respectively.
+pub const ECOTONE_L1_FEE_SCALARS_SLOT: U256 = U256::from_limbs([3u64, 0, 0, 0]);
+
+/// The address of L1 fee recipient.
+pub const L1_FEE_RECIPIENT: Address = address!("420000000000000000000000000000000000001A");
+
+/// The address of the base fee recipient.
+pub const BASE_FEE_RECIPIENT: Address = address!("4200000000000000000000000000000000000019");
+
+/// The address of the L1Block contract.
+pub const L1_BLOCK_CONTRACT: Address = address!("4200000000000000000000000000000000000015");
+
+/// The sender of the L1 attributes deposit, the first transaction of every block.
+pub const L1_INFO_DEPOSITOR: Address = address!("deaddeaddeaddeaddeaddeaddeaddeaddead0001");
+
+/// The address of the GasPriceOracle contract.
+pub const GAS_ORACLE_CONTRACT: Address = address!("420000000000000000000000000000000000000F");
+
+/// Calculate the data gas for posting the transaction on L1. Calldata costs 16 gas per byte
+/// after compression.
+///
+/// Prior to fjord, calldata costs 16 gas per non-zero byte and 4 gas per zero byte.
+///
+/// Prior to regolith, an extra 68 non-zero bytes were included in the rollup data costs to
+/// account for the empty signature.
+pub fn data_gas<H: OpStackHardfork>(input: &[u8], spec_id: H) -> U256 {
+    if spec_id.is_upgrade_enabled(OpStackUpgrade::Fjord) {
+        return tx_estimated_size_fjord(input)
+            .saturating_mul(U256::from(NON_ZERO_BYTE_COST))
+            .wrapping_div(U256::from(1_000_000));
+    };
+
+    let mut rollup_data_gas_cost = U256::from(input.iter().fold(0, |acc, byte| {
+        acc + if *byte == 0x00 {
+            ZERO_BYTE_COST
+        } else {
+            NON_ZERO_BYTE_COST
+        }
+    }));
+
+    // Prior to regolith, an extra 68 non zero bytes were included in the rollup data costs.
+    if !spec_id.is_upgrade_enabled(OpStackUpgrade::Regolith) {
+        rollup_data_gas_cost += U256::from(NON_ZERO_BYTE_COST * 68);
+    }
+
+    rollup_data_gas_cost
+}
+
+/// Calculate the estimated compressed transaction size in bytes, scaled by 1e6.
+pub fn tx_estimated_size_fjord(input: &[u8]) -> U256 {
+    estimated_size_fjord(U256::from(flz_compress_len(input)))
+}
+
+/// Calculate the estimated compressed size in bytes from the FastLZ size, scaled by 1e6.
+///
+/// This value is computed based on the following formula:
+/// `max(minTransactionSize, intercept + fastlzCoef*fastlzSize)`
+pub fn estimated_size_fjord(fastlz_size: U256) -> U256 {
+    fastlz_size
+        .saturating_mul(U256::from(836_500))
+        .saturating_sub(U256::from(42_585_600))
+        .max(U256::from(100_000_000))
+}
+
+/// L1 fee parameters read from the L1Block predeploy.
+///
+/// Implementors get the L1 cost functions of every OP stack upgrade. A rollup that scales the
+/// Bedrock L1 cost, like Mantle with its token ratio, overrides [Self::bedrock_cost_multiplier].
+pub trait L1CostParams {
+    /// The base fee of the L1 origin block.
+    fn l1_base_fee(&self) -> U256;
+
+    /// The L1 fee overhead. None if Ecotone is activated.
+    fn l1_fee_overhead(&self) -> Option<U256>;
+
+    /// The L1 fee scalar.
+    fn l1_base_fee_scalar(&self) -> U256;
+
+    /// The L1 blob base fee. None if Ecotone is not activated.
+    fn l1_blob_base_fee(&self) -> Option<U256>;
+
+    /// The L1 blob base fee scalar. None if Ecotone is not activated.
+    fn l1_blob_base_fee_scalar(&self) -> Option<U256>;
+
+    /// True if Ecotone is activated, but the L1 fee scalars have not yet been set.
+    fn empty_scalars(&self) -> bool;
+
+    /// Multiplier applied to the Bedrock L1 cost, one by default.
+    #[inline]
+    fn bedrock_cost_multiplier(&self) -> U256 {
+        U256::from(1)
+    }
+
+    /// Calculate the gas cost of a transaction based on L1 block data posted on L2, depending on the hardfork passed.
+    fn calculate_tx_l1_cost<H: OpStackHardfork>(&self, input: &[u8], spec_id: H) -> U256 {
+        // If the input is a deposit transaction or empty, the default value is zero.
+        if input.is_empty() || input.first() == Some(&0x7F) {
+            return U256::ZERO;
+        }
+
+        if spec_id.is_upgrade_enabled(OpStackUpgrade::Fjord) {
+            self.l1_cost_fjord(tx_estimated_size_fjord(input))
+        } else if spec_id.is_upgrade_enabled(OpStackUpgrade::Ecotone) && !self.empty_scalars() {
+            self.l1_cost_ecotone(data_gas(input, spec_id))
+        } else {
+            // There is an edgecase where, for the very first Ecotone block (unless it is activated at Genesis), we must
+            // use the Bedrock cost function. To determine if this is the case, we can check if the Ecotone parameters are
+            // unset.
+            self.l1_cost_bedrock(data_gas(input, spec_id))
+        }
+    }
+
+    /// Returns the L1 gas used to post the transaction, as reported in receipts.
+    ///
+    /// This includes the L1 fee overhead as long as the Bedrock cost function is used.
+    fn tx_l1_gas_used<H: OpStackHardfork>(&self, input: &[u8], spec_id: H) -> U256 {
+        if input.is_empty() || input.first() == Some(&0x7F) {
+            return U256::ZERO;
+        }
+        let data_gas = data_gas(input, spec_id);
+        if spec_id.is_upgrade_enabled(OpStackUpgrade::Ecotone) && !self.empty_scalars() {
+            return data_gas;
+        }
+        data_gas.saturating_add(self.l1_fee_overhead().unwrap_or_default())
+    }
+
+    /// Calculate the L1 cost of the given data gas, pre-Ecotone.
+    fn l1_cost_bedrock(&self, rollup_data_gas_cost: U256) -> U256 {
+        rollup_data_gas_cost
+            .saturating_add(self.l1_fee_overhead().unwrap_or_default())
+            .saturating_mul(self.l1_base_fee())
+            .saturating_mul(self.l1_base_fee_scalar())
+            .saturating_mul(self.bedrock_cost_multiplier())
+            .wrapping_div(U256::from(1_000_000))
+    }
+
+    /// Calculate the L1 cost of the given data gas, post-Ecotone.
+    ///
+    /// Ecotone L1 cost function:
+    /// `(calldataGas/16)*(l1BaseFee*16*l1BaseFeeScalar + l1BlobBaseFee*l1BlobBaseFeeScalar)/1e6`
+    ///
+    /// We divide "calldataGas" by 16 to change from units of calldata gas to "estimated # of bytes when compressed".
+    /// Known as "compressedTxSize" in the spec.
+    ///
+    /// Function is actually computed as follows for better precision under integer arithmetic:
+    /// `calldataGas*(l1BaseFee*16*l1BaseFeeScalar + l1BlobBaseFee*l1BlobBaseFeeScalar)/16e6`
+    fn l1_cost_ecotone(&self, rollup_data_gas_cost: U256) -> U256 {
+        self.l1_fee_scaled_ecotone()
+            .saturating_mul(rollup_data_gas_cost)
+            .wrapping_div(U256::from(1_000_000 * NON_ZERO_BYTE_COST))
+    }
+
+    /// Calculate the L1 cost of the given estimated size, post-Fjord.
+    ///
+    /// Fjord L1 cost function:
+    /// `estimatedSize*(baseFeeScalar*l1BaseFee*16 + blobFeeScalar*l1BlobBaseFee)/1e12`
+    fn l1_cost_fjord(&self, estimated_size: U256) -> U256 {
+        estimated_size
+            .saturating_mul(self.l1_fee_scaled_ecotone())
+            .wrapping_div(U256::from(1_000_000_000_000u64))
+    }
+
+    /// Returns `l1BaseFee*16*l1BaseFeeScalar + l1BlobBaseFee*l1BlobBaseFeeScalar`.
+    fn l1_fee_scaled_ecotone(&self) -> U256 {
+        let calldata_cost_per_byte = self
+            .l1_base_fee()
+            .saturating_mul(U256::from(NON_ZERO_BYTE_COST))
+            .saturating_mul(self.l1_base_fee_scalar());
+        let blob_cost_per_byte = self
+            .l1_blob_base_fee()
+            .unwrap_or_default()
+            .saturating_mul(self.l1_blob_base_fee_scalar().unwrap_or_default());
+
+        calldata_cost_per_byte.saturating_add(blob_cost_per_byte)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::{primitives::bytes, specification::hardfork::SpecId};
+
+    #[derive(Clone, Copy)]
+    struct TestSpec(Option<OpStackUpgrade>);
+
+    impl From<TestSpec> for SpecId {
+        fn from(_: TestSpec) -> Self {
+            SpecId::CANCUN
+        }
+    }
+
+    impl OpStackHardfork for TestSpec {
+        fn op_stack_upgrade(self) -> Option<OpStackUpgrade> {
+            self.0
+        }
+    }
+
+    #[derive(Default)]
+    struct TestParams {
+        l1_base_fee: U256,
+        l1_fee_overhead: Option<U256>,
+        l1_base_fee_scalar: U256,
+        l1_blob_base_fee: Option<U256>,
+        l1_blob_base_fee_scalar: Option<U256>,
+        empty_scalars: bool,
+        multiplier: Option<U256>,
+    }
+
+    impl L1CostParams for TestParams {
+        fn l1_base_fee(&self) -> U256 {
+            self.l1_base_fee
+        }
+        fn l1_fee_overhead(&self) -> Option<U256> {
+            self.l1_fee_overhead
+        }
+        fn l1_base_fee_scalar(&self) -> U256 {
+            self.l1_base_fee_scalar
+        }
+        fn l1_blob_base_fee(&self) -> Option<U256> {
+            self.l1_blob_base_fee
+        }
+        fn l1_blob_base_fee_scalar(&self) -> Option<U256> {
+            self.l1_blob_base_fee_scalar
+        }
+        fn empty_scalars(&self) -> bool {
+            self.empty_scalars
+        }
+        fn bedrock_cost_multiplier(&self) -> U256 {
+            self.multiplier.unwrap_or(U256::from(1))
+        }
+    }
+
+    const BEDROCK: TestSpec = TestSpec(Some(OpStackUpgrade::Bedrock));
+    const REGOLITH: TestSpec = TestSpec(Some(OpStackUpgrade::Regolith));
+    const ECOTONE: TestSpec = TestSpec(Some(OpStackUpgrade::Ecotone));
+    const FJORD: TestSpec = TestSpec(Some(OpStackUpgrade::Fjord));
+
+    #[test]
+    fn test_data_gas() {
+        let input = bytes!("FACADE00");
+        assert_eq!(data_gas(&input, BEDROCK), U256::from(3 * 16 + 4 + 68 * 16));
+        assert_eq!(data_gas(&input, REGOLITH), U256::from(3 * 16 + 4));
+        assert_eq!(data_gas(&input, ECOTONE), U256::from(3 * 16 + 4));
+        // Fjord has a minimum compressed size of 100 bytes
+        assert_eq!(data_gas(&input, FJORD), U256::from(1600));
+    }
+
+    #[test]
+    fn test_bedrock_cost_multiplier() {
+        let mut params = TestParams {
+            l1_base_fee: U256::from(1_000),
+            l1_fee_overhead: Some(U256::from(1_000)),
+            l1_base_fee_scalar: U256::from(1_000),
+            ..Default::default()
+        };
+        let input = bytes!("FACADE");
+
+        // (48 + 1000) * 1000 * 1000 / 1e6
+        let cost = params.calculate_tx_l1_cost(&input, REGOLITH);
+        assert_eq!(cost, U256::from(1048));
+
+        params.multiplier = Some(U256::from(3));
+        assert_eq!(
+            params.calculate_tx_l1_cost(&input, REGOLITH),
+            U256::from(3 * 1048)
+        );
+    }
+
+    #[test]
+    fn test_empty_scalars_use_bedrock_cost() {
+        let params = TestParams {
+            l1_base_fee: U256::from(1_000),
+            l1_fee_overhead: Some(U256::from(1_000)),
+            l1_base_fee_scalar: U256::from(1_000),
+            l1_blob_base_fee: Some(U256::ZERO),
+            l1_blob_base_fee_scalar: Some(U256::ZERO),
+            empty_scalars: true,
+            ..Default::default()
+        };
+        let input = bytes!("FACADE");
+
+        assert_eq!(
+            params.calculate_tx_l1_cost(&input, ECOTONE),
+            params.calculate_tx_l1_cost(&input, REGOLITH)
+        );
+        assert_eq!(params.tx_l1_gas_used(&input, ECOTONE), U256::from(1048));
+    }
+
+    #[test]
+    fn test_deposit_and_empty_input_cost_nothing() {
+        let params = TestParams {
+            l1_base_fee: U256::from(1_000),
+            l1_base_fee_scalar: U256::from(1_000),
+            ..Default::default()
+        };
+        assert_eq!(params.calculate_tx_l1_cost(&[], FJORD), U256::ZERO);
+        assert_eq!(params.calculate_tx_l1_cost(&[0x7F, 1], FJORD), U256::ZERO);
+        assert_eq!(params.tx_l1_gas_used(&[], BEDROCK), U256::ZERO);
+    }
+}
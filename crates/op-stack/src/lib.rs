@@ -0,0 +1,25 @@
+//! Building blocks shared by the rollups built on the OP stack.
+//!
+//! A rollup is expressed as a configuration on top of these blocks: its hardfork type implements
+//! [OpStackHardfork] to tell which OP stack upgrades are active, its L1 block info implements
+//! [L1CostParams] to get the L1 cost functions, and its handler calls the deposit hooks in
+//! [handler].
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc as std;
+
+pub mod bn128;
+pub mod chain_spec;
+pub mod fast_lz;
+pub mod handler;
+pub mod hardfork;
+pub mod l1block;
+pub mod transaction;
+
+pub use chain_spec::{ChainSpec, ForkCondition};
+pub use hardfork::{OpStackHardfork, OpStackUpgrade};
+pub use l1block::{
+    L1CostParams, BASE_FEE_RECIPIENT, GAS_ORACLE_CONTRACT, L1_BLOCK_CONTRACT, L1_FEE_RECIPIENT,
+};
@@ -0,0 +1,7 @@
+pub mod abstraction;
+pub mod deposit;
+pub mod error;
+
+pub use abstraction::{OpTransaction, OpTransactionType, OpTxTrait};
+pub use deposit::DepositTransaction;
+pub use error::OpTransactionError;
@@ -1,5 +1,4 @@
-use super::deposit::{DepositTransaction, TxDeposit};
-use crate::OpTransactionError;
+use super::{deposit::DepositTransaction, error::OpTransactionError};
 use revm::{
     primitives::Bytes,
     transaction::{CommonTxFields, Transaction, TransactionType},
@@ -19,7 +18,7 @@ pub trait OpTxTrait: Transaction {
 pub enum OpTransactionType {
     /// Base transaction type supported on Ethereum mainnet.
     Base(TransactionType),
-    /// Optimism-specific deposit transaction type.
+    /// OP stack deposit transaction type.
     Deposit,
 }
 
@@ -32,9 +31,10 @@ impl From<OpTransactionType> for TransactionType {
     }
 }
 
+/// Transaction of an OP stack chain, generic over the deposit transaction of the chain.
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub enum OpTransaction<T: Transaction> {
+pub enum OpTransaction<T: Transaction, D> {
     Base {
         tx: T,
         /// An enveloped EIP-2718 typed transaction. This is used
@@ -43,10 +43,10 @@ pub enum OpTransaction<T: Transaction> {
         /// externally.
         enveloped_tx: Option<Bytes>,
     },
-    Deposit(TxDeposit),
+    Deposit(D),
 }
 
-impl Default for OpTransaction<TxEnv> {
+impl<D> Default for OpTransaction<TxEnv, D> {
     fn default() -> Self {
         Self::Base {
             tx: TxEnv::default(),
@@ -55,7 +55,7 @@ impl Default for OpTransaction<TxEnv> {
     }
 }
 
-impl<T: Transaction> Transaction for OpTransaction<T> {
+impl<T: Transaction, D: DepositTransaction> Transaction for OpTransaction<T, D> {
     // TODO
     type TransactionError = OpTransactionError;
     type TransactionType = OpTransactionType;
@@ -89,7 +89,7 @@ impl<T: Transaction> Transaction for OpTransaction<T> {
     fn kind(&self) -> revm::primitives::TxKind {
         match self {
             Self::Base { tx, .. } => tx.kind(),
-            Self::Deposit(deposit) => deposit.to,
+            Self::Deposit(deposit) => deposit.to(),
         }
     }
 
@@ -107,6 +107,13 @@ impl<T: Transaction> Transaction for OpTransaction<T> {
         }
     }
 
+    fn access_list(&self) -> Option<&Self::AccessList> {
+        match self {
+            Self::Base { tx, .. } => tx.access_list(),
+            Self::Deposit(_) => None,
+        }
+    }
+
     fn legacy(&self) -> &Self::Legacy {
         let Self::Base { tx, .. } = self else {
             panic!("Not a legacy transaction")
@@ -143,8 +150,8 @@ impl<T: Transaction> Transaction for OpTransaction<T> {
     }
 }
 
-impl<T: Transaction> OpTxTrait for OpTransaction<T> {
-    type DepositTx = TxDeposit;
+impl<T: Transaction, D: DepositTransaction> OpTxTrait for OpTransaction<T, D> {
+    type DepositTx = D;
 
     fn deposit(&self) -> &Self::DepositTx {
         match self {
@@ -0,0 +1,15 @@
+use revm::{
+    primitives::{TxKind, B256},
+    transaction::CommonTxFields,
+};
+
+/// Fields of a deposit transaction, shared by the deposit types of the OP stack chains.
+pub trait DepositTransaction: CommonTxFields {
+    fn source_hash(&self) -> B256;
+
+    fn to(&self) -> TxKind;
+
+    fn mint(&self) -> Option<u128>;
+
+    fn is_system_transaction(&self) -> bool;
+}
@@ -4,7 +4,7 @@ use revm::{
     wiring::result::{EVMError, InvalidTransaction},
 };
 
-/// Mantle transaction validation error.
+/// OP stack transaction validation error.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum OpTransactionError {
@@ -16,27 +16,27 @@ pub enum OpTransactionError {
     /// was deprecated in the Regolith hardfork, and this error is thrown if a `Deposit` transaction
     /// is found with this field set to `true` after the hardfork activation.
     ///
-    /// In addition, this error is internal, and bubbles up into a [crate::MantleHaltReason::FailedDeposit] error
+    /// In addition, this error is internal, and bubbles up into a `FailedDeposit` halt reason
     /// in the `revm` handler for the consumer to easily handle. This is due to a state transition
     /// rule on OP Stack chains where, if for any reason a deposit transaction fails, the transaction
     /// must still be included in the block, the sender nonce is bumped, the `mint` value persists, and
     /// special gas accounting rules are applied. Normally on L1, [EVMError::Transaction] errors
-    /// are cause for non-inclusion, so a special [crate::MantleHaltReason] variant was introduced to handle this
+    /// are cause for non-inclusion, so a special halt reason was introduced to handle this
     /// case for failed deposit transactions.
     DepositSystemTxPostRegolith,
     /// Deposit transaction haults bubble up to the global main return handler, wiping state and
     /// only increasing the nonce + persisting the mint value.
     ///
-    /// This is a catch-all error for any deposit transaction that is results in a [crate::MantleHaltReason] error
+    /// This is a catch-all error for any deposit transaction that is results in a `FailedDeposit` halt reason
     /// post-regolith hardfork. This allows for a consumer to easily handle special cases where
     /// a deposit transaction fails during validation, but must still be included in the block.
     ///
-    /// In addition, this error is internal, and bubbles up into a [crate::MantleHaltReason::FailedDeposit] error
+    /// In addition, this error is internal, and bubbles up into a `FailedDeposit` halt reason
     /// in the `revm` handler for the consumer to easily handle. This is due to a state transition
     /// rule on OP Stack chains where, if for any reason a deposit transaction fails, the transaction
     /// must still be included in the block, the sender nonce is bumped, the `mint` value persists, and
     /// special gas accounting rules are applied. Normally on L1, [EVMError::Transaction] errors
-    /// are cause for non-inclusion, so a special [crate::MantleHaltReason] variant was introduced to handle this
+    /// are cause for non-inclusion, so a special halt reason was introduced to handle this
     /// case for failed deposit transactions.
     HaltedDepositPostRegolith,
 }
@@ -0,0 +1,76 @@
+//! Hardfork activation schedule of a Optimism chain.
+use crate::OptimismSpecId;
+
+pub use op_stack::{ChainSpec, ForkCondition};
+
+/// Hardfork activation schedule of a Optimism chain.
+pub type OptimismChainSpec = ChainSpec<OptimismSpecId>;
+
+/// Loads the chain spec from the `config` of a genesis JSON file.
+#[cfg(feature = "serde-json")]
+pub fn from_genesis_json(json: &str) -> Result<OptimismChainSpec, serde_json::Error> {
+    #[derive(serde::Deserialize)]
+    struct Genesis {
+        config: op_stack::chain_spec::ChainConfig,
+    }
+
+    let genesis: Genesis = serde_json::from_str(json)?;
+    Ok((&genesis.config).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wiring::OptimismEvmWiring;
+    use database::InMemoryDB;
+    use revm::{primitives::U256, wiring::default::block::BlockEnv, Evm};
+
+    #[test]
+    fn test_configure() {
+        let spec = OptimismChainSpec::new(10)
+            .with_hardfork(OptimismSpecId::BEDROCK, ForkCondition::Block(0))
+            .with_hardfork(OptimismSpecId::FJORD, ForkCondition::Timestamp(100));
+        let block = BlockEnv {
+            number: U256::from(1),
+            timestamp: U256::from(100),
+            ..Default::default()
+        };
+        let evm = spec
+            .configure(
+                Evm::<OptimismEvmWiring<InMemoryDB, ()>>::builder()
+                    .with_default_db()
+                    .with_default_ext_ctx(),
+                block.clone(),
+            )
+            .build();
+
+        assert_eq!(evm.spec_id(), OptimismSpecId::FJORD);
+        assert_eq!(evm.context.evm.env.block, block);
+        assert_eq!(evm.context.evm.env.cfg.chain_id, 10);
+    }
+
+    #[cfg(feature = "serde-json")]
+    #[test]
+    fn test_from_genesis_json() {
+        let spec = from_genesis_json(
+            r#"{
+                "config": {
+                    "chainId": 10,
+                    "homesteadBlock": 0,
+                    "londonBlock": 0,
+                    "bedrockBlock": 0,
+                    "regolithTime": 0,
+                    "canyonTime": 100,
+                    "ecotoneTime": 200
+                },
+                "alloc": {}
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(spec.chain_id(), 10);
+        assert_eq!(spec.spec_id_at(0, 0), OptimismSpecId::REGOLITH);
+        assert_eq!(spec.spec_id_at(0, 100), OptimismSpecId::CANYON);
+        assert_eq!(spec.spec_id_at(0, 200), OptimismSpecId::ECOTONE);
+    }
+}
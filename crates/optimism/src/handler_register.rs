@@ -10,23 +10,19 @@ use crate::{
 };
 use crate::{BASE_FEE_RECIPIENT, L1_FEE_RECIPIENT};
 use core::ops::Mul;
+use op_stack::handler::{self, DepositGas};
 use revm::{
-    database_interface::Database,
     handler::{
         mainnet::{self, deduct_caller_inner, validate_block_env, validate_tx_env},
         register::EvmHandler,
     },
-    interpreter::{return_ok, return_revert, Gas},
-    precompile::{secp256r1, PrecompileSpecId},
+    interpreter::Gas,
+    precompile::PrecompileSpecId,
     primitives::{HashMap, U256},
-    state::Account,
     transaction::CommonTxFields,
     wiring::{
         default::EnvWiring,
-        result::{
-            EVMError, EVMResult, EVMResultGeneric, ExecutionResult, InvalidTransaction,
-            ResultAndState,
-        },
+        result::{EVMError, EVMResult, EVMResultGeneric, ExecutionResult, ResultAndState},
         Block, Transaction,
     },
     Context, ContextPrecompiles, FrameResult,
@@ -108,65 +104,19 @@ pub fn last_frame_return<EvmWiringT: OptimismWiring, SPEC: OptimismSpec>(
     frame_result: &mut FrameResult,
 ) -> EVMResultGeneric<(), EvmWiringT> {
     let env = context.evm.inner.env();
-    let is_deposit = env.tx.tx_type() == OpTransactionType::Deposit;
+    let deposit = (env.tx.tx_type() == OpTransactionType::Deposit).then(|| DepositGas {
+        is_system_transaction: env.tx.deposit().is_system_transaction(),
+    });
     let tx_gas_limit = env.tx.common_fields().gas_limit();
-    let is_regolith = SPEC::optimism_enabled(OptimismSpecId::REGOLITH);
 
     let instruction_result = frame_result.interpreter_result().result;
-    let gas = frame_result.gas_mut();
-    let remaining = gas.remaining();
-    let refunded = gas.refunded();
-    // Spend the gas limit. Gas is reimbursed when the tx returns successfully.
-    *gas = Gas::new_spent(tx_gas_limit);
-
-    match instruction_result {
-        return_ok!() => {
-            // On Optimism, deposit transactions report gas usage uniquely to other
-            // transactions due to them being pre-paid on L1.
-            //
-            // Hardfork Behavior:
-            // - Bedrock (success path):
-            //   - Deposit transactions (non-system) report their gas limit as the usage.
-            //     No refunds.
-            //   - Deposit transactions (system) report 0 gas used. No refunds.
-            //   - Regular transactions report gas usage as normal.
-            // - Regolith (success path):
-            //   - Deposit transactions (all) report their gas used as normal. Refunds
-            //     enabled.
-            //   - Regular transactions report their gas used as normal.
-            if !is_deposit || is_regolith {
-                // For regular transactions prior to Regolith and all transactions after
-                // Regolith, gas is reported as normal.
-                gas.erase_cost(remaining);
-                gas.record_refund(refunded);
-            } else if is_deposit {
-                let tx = env.tx.deposit();
-                if tx.is_system_transaction() {
-                    // System transactions were a special type of deposit transaction in
-                    // the Bedrock hardfork that did not incur any gas costs.
-                    gas.erase_cost(tx_gas_limit);
-                }
-            }
-        }
-        return_revert!() => {
-            // On Optimism, deposit transactions report gas usage uniquely to other
-            // transactions due to them being pre-paid on L1.
-            //
-            // Hardfork Behavior:
-            // - Bedrock (revert path):
-            //   - Deposit transactions (all) report the gas limit as the amount of gas
-            //     used on failure. No refunds.
-            //   - Regular transactions receive a refund on remaining gas as normal.
-            // - Regolith (revert path):
-            //   - Deposit transactions (all) report the actual gas used as the amount of
-            //     gas used on failure. Refunds on remaining gas enabled.
-            //   - Regular transactions receive a refund on remaining gas as normal.
-            if !is_deposit || is_regolith {
-                gas.erase_cost(remaining);
-            }
-        }
-        _ => {}
-    }
+    handler::last_frame_gas(
+        SPEC::OPTIMISM_SPEC_ID,
+        frame_result.gas_mut(),
+        instruction_result,
+        tx_gas_limit,
+        deposit,
+    );
     Ok(())
 }
 
@@ -180,14 +130,12 @@ pub fn refund<EvmWiringT: OptimismWiring, SPEC: OptimismSpec>(
     gas.record_refund(eip7702_refund);
 
     let env = context.evm.inner.env();
-    let is_deposit = env.tx.tx_type() == OpTransactionType::Deposit;
-    let is_regolith = SPEC::optimism_enabled(OptimismSpecId::REGOLITH);
-
-    // Prior to Regolith, deposit transactions did not receive gas refunds.
-    let is_gas_refund_disabled = env.cfg.is_gas_refund_disabled() || (is_deposit && !is_regolith);
-    if !is_gas_refund_disabled {
-        gas.set_final_refund(SPEC::OPTIMISM_SPEC_ID.is_enabled_in(OptimismSpecId::LONDON));
-    }
+    handler::final_refund(
+        SPEC::OPTIMISM_SPEC_ID,
+        gas,
+        env.tx.tx_type() == OpTransactionType::Deposit,
+        env.cfg.is_gas_refund_disabled(),
+    );
 }
 
 /// Load precompiles for Optimism chain.
@@ -195,21 +143,7 @@ pub fn refund<EvmWiringT: OptimismWiring, SPEC: OptimismSpec>(
 pub fn load_precompiles<EvmWiringT: OptimismWiring, SPEC: OptimismSpec>(
 ) -> ContextPrecompiles<EvmWiringT> {
     let mut precompiles = ContextPrecompiles::new(PrecompileSpecId::from_spec_id(SPEC::SPEC_ID));
-
-    if SPEC::optimism_enabled(OptimismSpecId::FJORD) {
-        precompiles.extend([
-            // EIP-7212: secp256r1 P256verify
-            secp256r1::P256VERIFY,
-        ])
-    }
-
-    if SPEC::optimism_enabled(OptimismSpecId::GRANITE) {
-        precompiles.extend([
-            // Restrict bn256Pairing input size
-            crate::bn128::pair::GRANITE,
-        ])
-    }
-
+    handler::extend_precompiles(&mut precompiles, SPEC::OPTIMISM_SPEC_ID);
     precompiles
 }
 
@@ -239,7 +173,7 @@ pub fn deduct_caller<EvmWiringT: OptimismWiring, SPEC: OptimismSpec>(
 ) -> EVMResultGeneric<(), EvmWiringT> {
     let caller = context.evm.inner.env.tx.common_fields().caller();
     // load caller's account.
-    let mut caller_account = context
+    let caller_account = context
         .evm
         .inner
         .journaled_state
@@ -252,10 +186,10 @@ pub fn deduct_caller<EvmWiringT: OptimismWiring, SPEC: OptimismSpec>(
     // in wei to the caller's balance. This should be persisted to the database
     // prior to the rest of execution.
     if is_deposit {
-        let tx = context.evm.inner.env.tx.deposit();
-        if let Some(mint) = tx.mint() {
-            caller_account.info.balance += U256::from(mint);
-        }
+        handler::mint_deposit(
+            caller_account.data,
+            context.evm.inner.env.tx.deposit().mint(),
+        );
     }
 
     // We deduct caller max balance after minting and before deducing the
@@ -281,16 +215,8 @@ pub fn deduct_caller<EvmWiringT: OptimismWiring, SPEC: OptimismSpec>(
             .l1_block_info()
             .expect("L1BlockInfo should be loaded")
             .calculate_tx_l1_cost(enveloped_tx, SPEC::OPTIMISM_SPEC_ID);
-        if tx_l1_cost.gt(&caller_account.info.balance) {
-            return Err(EVMError::Transaction(
-                InvalidTransaction::LackOfFundForMaxFee {
-                    fee: tx_l1_cost.into(),
-                    balance: caller_account.info.balance.into(),
-                }
-                .into(),
-            ));
-        }
-        caller_account.info.balance = caller_account.info.balance.saturating_sub(tx_l1_cost);
+        handler::deduct_l1_cost(caller_account.data, tx_l1_cost)
+            .map_err(|error| EVMError::Transaction(error.into()))?;
     }
     Ok(())
 }
@@ -325,31 +251,23 @@ pub fn reward_beneficiary<EvmWiringT: OptimismWiring, SPEC: OptimismSpec>(
 
         let l1_cost = l1_block_info.calculate_tx_l1_cost(enveloped_tx, SPEC::OPTIMISM_SPEC_ID);
 
-        // Send the L1 cost of the transaction to the L1 Fee Vault.
-        let mut l1_fee_vault_account = context
-            .evm
-            .inner
-            .journaled_state
-            .load_account(L1_FEE_RECIPIENT, &mut context.evm.inner.db)
-            .map_err(EVMError::Database)?;
-        l1_fee_vault_account.mark_touch();
-        l1_fee_vault_account.info.balance += l1_cost;
-
-        // Send the base fee of the transaction to the Base Fee Vault.
-        let mut base_fee_vault_account = context
-            .evm
-            .inner
-            .journaled_state
-            .load_account(BASE_FEE_RECIPIENT, &mut context.evm.inner.db)
-            .map_err(EVMError::Database)?;
-        base_fee_vault_account.mark_touch();
-        base_fee_vault_account.info.balance += context
+        let base_fee = context
             .evm
             .inner
             .env
             .block
             .basefee()
             .mul(U256::from(gas.spent() - gas.refunded() as u64));
+        let journaled_state = &mut context.evm.inner.journaled_state;
+        let db = &mut context.evm.inner.db;
+
+        // Send the L1 cost of the transaction to the L1 Fee Vault.
+        handler::pay_fee_vault(journaled_state, db, L1_FEE_RECIPIENT, l1_cost)
+            .map_err(EVMError::Database)?;
+
+        // Send the base fee of the transaction to the Base Fee Vault.
+        handler::pay_fee_vault(journaled_state, db, BASE_FEE_RECIPIENT, base_fee)
+            .map_err(EVMError::Database)?;
     }
     Ok(())
 }
@@ -367,7 +285,7 @@ pub fn output<EvmWiringT: OptimismWiring, SPEC: OptimismSpec>(
         // we bubble up to the global return handler. The mint value will be persisted
         // and the caller nonce will be incremented there.
         let is_deposit = context.evm.inner.env.tx.tx_type() == OpTransactionType::Deposit;
-        if is_deposit && SPEC::optimism_enabled(OptimismSpecId::REGOLITH) {
+        if is_deposit && handler::is_halted_deposit_error(SPEC::OPTIMISM_SPEC_ID) {
             return Err(EVMError::Transaction(
                 OpTransactionError::HaltedDepositPostRegolith,
             ));
@@ -395,37 +313,20 @@ pub fn end<EvmWiringT: OptimismWiring, SPEC: OptimismSpec>(
 
             // Increment sender nonce and account balance for the mint amount. Deposits
             // always persist the mint amount, even if the transaction fails.
-            let account = {
-                let mut acc = Account::from(
-                    context
-                        .evm
-                        .inner
-                        .db
-                        .basic(tx.caller())
-                        .unwrap_or_default()
-                        .unwrap_or_default(),
-                );
-                acc.info.nonce = acc.info.nonce.saturating_add(1);
-                acc.info.balance = acc
-                    .info
-                    .balance
-                    .saturating_add(U256::from(tx.mint().unwrap_or_default()));
-                acc.mark_touch();
-                acc
-            };
+            let (account, _) =
+                handler::failed_deposit_caller(&mut context.evm.inner.db, tx.caller(), tx.mint())
+                    .map_err(EVMError::Database)?;
             let state = HashMap::from_iter([(tx.caller(), account)]);
 
             // The gas used of a failed deposit post-regolith is the gas
             // limit of the transaction. pre-regolith, it is the gas limit
             // of the transaction for non system transactions and 0 for system
             // transactions.
-            let gas_used = if SPEC::optimism_enabled(OptimismSpecId::REGOLITH)
-                || !tx.is_system_transaction()
-            {
-                tx.gas_limit()
-            } else {
-                0
-            };
+            let gas_used = handler::failed_deposit_gas_used(
+                SPEC::OPTIMISM_SPEC_ID,
+                tx.gas_limit(),
+                tx.is_system_transaction(),
+            );
 
             Ok(ResultAndState {
                 result: ExecutionResult::Halt {
@@ -453,7 +354,10 @@ mod tests {
         interpreter::{CallOutcome, InstructionResult, InterpreterResult},
         primitives::{bytes, Address, Bytes, B256},
         state::AccountInfo,
-        wiring::default::{block::BlockEnv, Env, TxEnv},
+        wiring::{
+            default::{block::BlockEnv, Env, TxEnv},
+            result::InvalidTransaction,
+        },
     };
     use std::boxed::Box;
 
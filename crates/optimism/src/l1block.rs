@@ -1,45 +1,17 @@
-use crate::fast_lz::flz_compress_len;
-use core::ops::Mul;
-use revm::{
-    database_interface::Database,
-    primitives::{address, Address, U256},
+use op_stack::l1block::{
+    self, L1CostParams, BASE_FEE_SCALAR_OFFSET, BLOB_BASE_FEE_SCALAR_OFFSET,
+    ECOTONE_L1_BLOB_BASE_FEE_SLOT, ECOTONE_L1_FEE_SCALARS_SLOT, L1_BASE_FEE_SLOT, L1_OVERHEAD_SLOT,
+    L1_SCALAR_SLOT,
 };
+use revm::{database_interface::Database, primitives::U256};
 
 use super::OptimismSpecId;
 
-const ZERO_BYTE_COST: u64 = 4;
-const NON_ZERO_BYTE_COST: u64 = 16;
-
-/// The two 4-byte Ecotone fee scalar values are packed into the same storage slot as the 8-byte sequence number.
-/// Byte offset within the storage slot of the 4-byte baseFeeScalar attribute.
-const BASE_FEE_SCALAR_OFFSET: usize = 16;
-/// The two 4-byte Ecotone fee scalar values are packed into the same storage slot as the 8-byte sequence number.
-/// Byte offset within the storage slot of the 4-byte blobBaseFeeScalar attribute.
-const BLOB_BASE_FEE_SCALAR_OFFSET: usize = 20;
-
-const L1_BASE_FEE_SLOT: U256 = U256::from_limbs([1u64, 0, 0, 0]);
-const L1_OVERHEAD_SLOT: U256 = U256::from_limbs([5u64, 0, 0, 0]);
-const L1_SCALAR_SLOT: U256 = U256::from_limbs([6u64, 0, 0, 0]);
-
-/// [ECOTONE_L1_BLOB_BASE_FEE_SLOT] was added in the Ecotone upgrade and stores the L1 blobBaseFee attribute.
-const ECOTONE_L1_BLOB_BASE_FEE_SLOT: U256 = U256::from_limbs([7u64, 0, 0, 0]);
-
-/// As of the ecotone upgrade, this storage slot stores the 32-bit basefeeScalar and blobBaseFeeScalar attributes at
-/// offsets [BASE_FEE_SCALAR_OFFSET] and [BLOB_BASE_FEE_SCALAR_OFFSET] respectively.
-const ECOTONE_L1_FEE_SCALARS_SLOT: U256 = U256::from_limbs([3u64, 0, 0, 0]);
+pub use op_stack::l1block::{BASE_FEE_RECIPIENT, L1_BLOCK_CONTRACT, L1_FEE_RECIPIENT};
 
 /// An empty 64-bit set of scalar values.
 const EMPTY_SCALARS: [u8; 8] = [0u8; 8];
 
-/// The address of L1 fee recipient.
-pub const L1_FEE_RECIPIENT: Address = address!("420000000000000000000000000000000000001A");
-
-/// The address of the base fee recipient.
-pub const BASE_FEE_RECIPIENT: Address = address!("4200000000000000000000000000000000000019");
-
-/// The address of the L1Block contract.
-pub const L1_BLOCK_CONTRACT: Address = address!("4200000000000000000000000000000000000015");
-
 /// L1 block info
 ///
 /// We can extract L1 epoch data from each L2 block, by looking at the `setL1BlockValues`
@@ -133,119 +105,38 @@ impl L1BlockInfo {
     /// Prior to regolith, an extra 68 non-zero bytes were included in the rollup data costs to
     /// account for the empty signature.
     pub fn data_gas(&self, input: &[u8], spec_id: OptimismSpecId) -> U256 {
-        if spec_id.is_enabled_in(OptimismSpecId::FJORD) {
-            let estimated_size = self.tx_estimated_size_fjord(input);
-
-            return estimated_size
-                .saturating_mul(U256::from(NON_ZERO_BYTE_COST))
-                .wrapping_div(U256::from(1_000_000));
-        };
-
-        let mut rollup_data_gas_cost = U256::from(input.iter().fold(0, |acc, byte| {
-            acc + if *byte == 0x00 {
-                ZERO_BYTE_COST
-            } else {
-                NON_ZERO_BYTE_COST
-            }
-        }));
-
-        // Prior to regolith, an extra 68 non zero bytes were included in the rollup data costs.
-        if !spec_id.is_enabled_in(OptimismSpecId::REGOLITH) {
-            rollup_data_gas_cost += U256::from(NON_ZERO_BYTE_COST).mul(U256::from(68));
-        }
-
-        rollup_data_gas_cost
-    }
-
-    // Calculate the estimated compressed transaction size in bytes, scaled by 1e6.
-    // This value is computed based on the following formula:
-    // max(minTransactionSize, intercept + fastlzCoef*fastlzSize)
-    fn tx_estimated_size_fjord(&self, input: &[u8]) -> U256 {
-        let fastlz_size = U256::from(flz_compress_len(input));
-
-        fastlz_size
-            .saturating_mul(U256::from(836_500))
-            .saturating_sub(U256::from(42_585_600))
-            .max(U256::from(100_000_000))
+        l1block::data_gas(input, spec_id)
     }
 
     /// Calculate the gas cost of a transaction based on L1 block data posted on L2, depending on the [OptimismSpecId] passed.
     pub fn calculate_tx_l1_cost(&self, input: &[u8], spec_id: OptimismSpecId) -> U256 {
-        // If the input is a deposit transaction or empty, the default value is zero.
-        if input.is_empty() || input.first() == Some(&0x7F) {
-            return U256::ZERO;
-        }
-
-        if spec_id.is_enabled_in(OptimismSpecId::FJORD) {
-            self.calculate_tx_l1_cost_fjord(input)
-        } else if spec_id.is_enabled_in(OptimismSpecId::ECOTONE) {
-            self.calculate_tx_l1_cost_ecotone(input, spec_id)
-        } else {
-            self.calculate_tx_l1_cost_bedrock(input, spec_id)
-        }
+        L1CostParams::calculate_tx_l1_cost(self, input, spec_id)
     }
+}
 
-    /// Calculate the gas cost of a transaction based on L1 block data posted on L2, pre-Ecotone.
-    fn calculate_tx_l1_cost_bedrock(&self, input: &[u8], spec_id: OptimismSpecId) -> U256 {
-        let rollup_data_gas_cost = self.data_gas(input, spec_id);
-        rollup_data_gas_cost
-            .saturating_add(self.l1_fee_overhead.unwrap_or_default())
-            .saturating_mul(self.l1_base_fee)
-            .saturating_mul(self.l1_base_fee_scalar)
-            .wrapping_div(U256::from(1_000_000))
+impl L1CostParams for L1BlockInfo {
+    fn l1_base_fee(&self) -> U256 {
+        self.l1_base_fee
     }
 
-    /// Calculate the gas cost of a transaction based on L1 block data posted on L2, post-Ecotone.
-    ///
-    /// [OptimismSpecId::ECOTONE] L1 cost function:
-    /// `(calldataGas/16)*(l1BaseFee*16*l1BaseFeeScalar + l1BlobBaseFee*l1BlobBaseFeeScalar)/1e6`
-    ///
-    /// We divide "calldataGas" by 16 to change from units of calldata gas to "estimated # of bytes when compressed".
-    /// Known as "compressedTxSize" in the spec.
-    ///
-    /// Function is actually computed as follows for better precision under integer arithmetic:
-    /// `calldataGas*(l1BaseFee*16*l1BaseFeeScalar + l1BlobBaseFee*l1BlobBaseFeeScalar)/16e6`
-    fn calculate_tx_l1_cost_ecotone(&self, input: &[u8], spec_id: OptimismSpecId) -> U256 {
-        // There is an edgecase where, for the very first Ecotone block (unless it is activated at Genesis), we must
-        // use the Bedrock cost function. To determine if this is the case, we can check if the Ecotone parameters are
-        // unset.
-        if self.empty_scalars {
-            return self.calculate_tx_l1_cost_bedrock(input, spec_id);
-        }
+    fn l1_fee_overhead(&self) -> Option<U256> {
+        self.l1_fee_overhead
+    }
 
-        let rollup_data_gas_cost = self.data_gas(input, spec_id);
-        let l1_fee_scaled = self.calculate_l1_fee_scaled_ecotone();
+    fn l1_base_fee_scalar(&self) -> U256 {
+        self.l1_base_fee_scalar
+    }
 
-        l1_fee_scaled
-            .saturating_mul(rollup_data_gas_cost)
-            .wrapping_div(U256::from(1_000_000 * NON_ZERO_BYTE_COST))
+    fn l1_blob_base_fee(&self) -> Option<U256> {
+        self.l1_blob_base_fee
     }
 
-    /// Calculate the gas cost of a transaction based on L1 block data posted on L2, post-Fjord.
-    ///
-    /// [OptimismSpecId::FJORD] L1 cost function:
-    /// `estimatedSize*(baseFeeScalar*l1BaseFee*16 + blobFeeScalar*l1BlobBaseFee)/1e12`
-    fn calculate_tx_l1_cost_fjord(&self, input: &[u8]) -> U256 {
-        let l1_fee_scaled = self.calculate_l1_fee_scaled_ecotone();
-        let estimated_size = self.tx_estimated_size_fjord(input);
-
-        estimated_size
-            .saturating_mul(l1_fee_scaled)
-            .wrapping_div(U256::from(1_000_000_000_000u64))
+    fn l1_blob_base_fee_scalar(&self) -> Option<U256> {
+        self.l1_blob_base_fee_scalar
     }
 
-    // l1BaseFee*16*l1BaseFeeScalar + l1BlobBaseFee*l1BlobBaseFeeScalar
-    fn calculate_l1_fee_scaled_ecotone(&self) -> U256 {
-        let calldata_cost_per_byte = self
-            .l1_base_fee
-            .saturating_mul(U256::from(NON_ZERO_BYTE_COST))
-            .saturating_mul(self.l1_base_fee_scalar);
-        let blob_cost_per_byte = self
-            .l1_blob_base_fee
-            .unwrap_or_default()
-            .saturating_mul(self.l1_blob_base_fee_scalar.unwrap_or_default());
-
-        calldata_cost_per_byte.saturating_add(blob_cost_per_byte)
+    fn empty_scalars(&self) -> bool {
+        self.empty_scalars
     }
 }
 
@@ -412,7 +303,7 @@ mod tests {
 
         assert_eq!(gas_used, expected_l1_gas_used);
 
-        let l1_fee = l1_block_info.calculate_tx_l1_cost_ecotone(TX, OptimismSpecId::ECOTONE);
+        let l1_fee = l1_block_info.calculate_tx_l1_cost(TX, OptimismSpecId::ECOTONE);
 
         assert_eq!(l1_fee, expected_l1_fee)
     }
@@ -494,7 +385,7 @@ mod tests {
 
         assert_eq!(data_gas, expected_data_gas);
 
-        let l1_fee = l1_block_info.calculate_tx_l1_cost_fjord(TX);
+        let l1_fee = l1_block_info.calculate_tx_l1_cost(TX, OptimismSpecId::FJORD);
 
         assert_eq!(l1_fee, expected_l1_fee)
     }
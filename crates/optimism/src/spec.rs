@@ -1,3 +1,4 @@
+use op_stack::{OpStackHardfork, OpStackUpgrade};
 use revm::{
     precompile::PrecompileSpecId,
     specification::hardfork::{Spec, SpecId},
@@ -131,6 +132,35 @@ impl From<OptimismSpecId> for PrecompileSpecId {
     }
 }
 
+impl From<OpStackUpgrade> for OptimismSpecId {
+    fn from(upgrade: OpStackUpgrade) -> Self {
+        match upgrade {
+            OpStackUpgrade::Bedrock => Self::BEDROCK,
+            OpStackUpgrade::Regolith => Self::REGOLITH,
+            OpStackUpgrade::Canyon => Self::CANYON,
+            OpStackUpgrade::Ecotone => Self::ECOTONE,
+            OpStackUpgrade::Fjord => Self::FJORD,
+            OpStackUpgrade::Granite => Self::GRANITE,
+        }
+    }
+}
+
+impl OpStackHardfork for OptimismSpecId {
+    fn op_stack_upgrade(self) -> Option<OpStackUpgrade> {
+        match self {
+            Self::BEDROCK => Some(OpStackUpgrade::Bedrock),
+            Self::REGOLITH | Self::SHANGHAI => Some(OpStackUpgrade::Regolith),
+            Self::CANYON | Self::CANCUN => Some(OpStackUpgrade::Canyon),
+            Self::ECOTONE => Some(OpStackUpgrade::Ecotone),
+            Self::FJORD => Some(OpStackUpgrade::Fjord),
+            Self::GRANITE | Self::PRAGUE | Self::PRAGUE_EOF | Self::LATEST => {
+                Some(OpStackUpgrade::Granite)
+            }
+            _ => None,
+        }
+    }
+}
+
 /// String identifiers for Optimism hardforks.
 pub mod id {
     // Re-export the Ethereum hardforks.
@@ -799,4 +829,25 @@ mod tests {
             OptimismSpecId::FJORD
         ));
     }
+
+    #[test]
+    fn test_op_stack_upgrade() {
+        let upgrades = [
+            (OpStackUpgrade::Bedrock, OptimismSpecId::BEDROCK),
+            (OpStackUpgrade::Regolith, OptimismSpecId::REGOLITH),
+            (OpStackUpgrade::Canyon, OptimismSpecId::CANYON),
+            (OpStackUpgrade::Ecotone, OptimismSpecId::ECOTONE),
+            (OpStackUpgrade::Fjord, OptimismSpecId::FJORD),
+            (OpStackUpgrade::Granite, OptimismSpecId::GRANITE),
+        ];
+        for spec_id in (0..=u8::MAX).filter_map(OptimismSpecId::try_from_u8) {
+            for (upgrade, upgrade_spec_id) in upgrades {
+                assert_eq!(
+                    spec_id.is_upgrade_enabled(upgrade),
+                    spec_id.is_enabled_in(upgrade_spec_id),
+                    "{spec_id:?} {upgrade:?}"
+                );
+            }
+        }
+    }
 }
@@ -1,5 +1,6 @@
-pub mod abstraction;
 pub mod deposit;
-pub mod error;
 
-pub use abstraction::{OpTransaction, OpTransactionType, OpTxTrait};
+pub use op_stack::transaction::{abstraction, error, OpTransactionType, OpTxTrait};
+
+/// Transaction of the Optimism chain.
+pub type OpTransaction<T> = op_stack::transaction::OpTransaction<T, deposit::TxDeposit>;
@@ -3,15 +3,7 @@ use revm::{
     transaction::CommonTxFields,
 };
 
-pub trait DepositTransaction: CommonTxFields {
-    fn source_hash(&self) -> B256;
-
-    fn to(&self) -> TxKind;
-
-    fn mint(&self) -> Option<u128>;
-
-    fn is_system_transaction(&self) -> bool;
-}
+pub use op_stack::transaction::DepositTransaction;
 
 #[derive(Clone, Default, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
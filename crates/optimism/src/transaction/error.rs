@@ -1,78 +0,0 @@
-use core::fmt::Display;
-use revm::{
-    transaction::TransactionError,
-    wiring::result::{EVMError, InvalidTransaction},
-};
-
-/// Optimism transaction validation error.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub enum OpTransactionError {
-    Base(InvalidTransaction),
-    /// System transactions are not supported post-regolith hardfork.
-    ///
-    /// Before the Regolith hardfork, there was a special field in the `Deposit` transaction
-    /// type that differentiated between `system` and `user` deposit transactions. This field
-    /// was deprecated in the Regolith hardfork, and this error is thrown if a `Deposit` transaction
-    /// is found with this field set to `true` after the hardfork activation.
-    ///
-    /// In addition, this error is internal, and bubbles up into a [crate::OptimismHaltReason::FailedDeposit] error
-    /// in the `revm` handler for the consumer to easily handle. This is due to a state transition
-    /// rule on OP Stack chains where, if for any reason a deposit transaction fails, the transaction
-    /// must still be included in the block, the sender nonce is bumped, the `mint` value persists, and
-    /// special gas accounting rules are applied. Normally on L1, [EVMError::Transaction] errors
-    /// are cause for non-inclusion, so a special [crate::OptimismHaltReason] variant was introduced to handle this
-    /// case for failed deposit transactions.
-    DepositSystemTxPostRegolith,
-    /// Deposit transaction haults bubble up to the global main return handler, wiping state and
-    /// only increasing the nonce + persisting the mint value.
-    ///
-    /// This is a catch-all error for any deposit transaction that is results in a [crate::OptimismHaltReason] error
-    /// post-regolith hardfork. This allows for a consumer to easily handle special cases where
-    /// a deposit transaction fails during validation, but must still be included in the block.
-    ///
-    /// In addition, this error is internal, and bubbles up into a [crate::OptimismHaltReason::FailedDeposit] error
-    /// in the `revm` handler for the consumer to easily handle. This is due to a state transition
-    /// rule on OP Stack chains where, if for any reason a deposit transaction fails, the transaction
-    /// must still be included in the block, the sender nonce is bumped, the `mint` value persists, and
-    /// special gas accounting rules are applied. Normally on L1, [EVMError::Transaction] errors
-    /// are cause for non-inclusion, so a special [crate::OptimismHaltReason] variant was introduced to handle this
-    /// case for failed deposit transactions.
-    HaltedDepositPostRegolith,
-}
-
-impl TransactionError for OpTransactionError {}
-
-impl Display for OpTransactionError {
-    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        match self {
-            Self::Base(error) => error.fmt(f),
-            Self::DepositSystemTxPostRegolith => {
-                write!(
-                    f,
-                    "deposit system transactions post regolith hardfork are not supported"
-                )
-            }
-            Self::HaltedDepositPostRegolith => {
-                write!(
-                    f,
-                    "deposit transaction halted post-regolith; error will be bubbled up to main return handler"
-                )
-            }
-        }
-    }
-}
-
-impl core::error::Error for OpTransactionError {}
-
-impl From<InvalidTransaction> for OpTransactionError {
-    fn from(value: InvalidTransaction) -> Self {
-        Self::Base(value)
-    }
-}
-
-impl<DBError> From<OpTransactionError> for EVMError<DBError, OpTransactionError> {
-    fn from(value: OpTransactionError) -> Self {
-        Self::Transaction(value)
-    }
-}
@@ -0,0 +1,56 @@
+use crate::U256;
+use std::{format, string::String};
+
+/// Decodes the `Error(string)` or `Panic(uint256)` revert reason of the revert data.
+pub fn decode_revert_reason(output: &[u8]) -> Option<String> {
+    const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+    const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+    let (selector, data) = output.split_first_chunk::<4>()?;
+    let word = |offset: usize| data.get(offset..offset.checked_add(32)?);
+    match *selector {
+        ERROR_SELECTOR => {
+            let offset = usize::try_from(U256::from_be_slice(word(0)?)).ok()?;
+            let len = usize::try_from(U256::from_be_slice(word(offset)?)).ok()?;
+            let start = offset + 32;
+            let reason = data.get(start..start.checked_add(len)?)?;
+            String::from_utf8(reason.to_vec()).ok()
+        }
+        PANIC_SELECTOR => {
+            let code = U256::from_be_slice(word(0)?);
+            let reason = match code.try_into() {
+                Ok(0x00u8) => "generic panic",
+                Ok(0x01) => "assert(false)",
+                Ok(0x11) => "arithmetic underflow or overflow",
+                Ok(0x12) => "division or modulo by zero",
+                Ok(0x21) => "enum overflow",
+                Ok(0x22) => "invalid encoded storage byte array accessed",
+                Ok(0x31) => "out-of-bounds array access; popping on an empty array",
+                Ok(0x32) => "out-of-bounds access of an array or bytesN",
+                Ok(0x41) => "out of memory",
+                Ok(0x51) => "uninitialized function",
+                _ => return Some(format!("unknown panic code: {code:#x}")),
+            };
+            Some(reason.into())
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{bytes, keccak256};
+
+    #[test]
+    fn revert_reasons() {
+        let panic =
+            bytes!("4e487b710000000000000000000000000000000000000000000000000000000000000011");
+        assert_eq!(
+            decode_revert_reason(&panic).as_deref(),
+            Some("arithmetic underflow or overflow")
+        );
+        assert_eq!(decode_revert_reason(&keccak256("x")[..]), None);
+        assert_eq!(decode_revert_reason(&[0x08, 0xc3, 0x79, 0xa0]), None);
+    }
+}
@@ -0,0 +1,306 @@
+//! Gas estimation of a transaction, like `eth_estimateGas`.
+use crate::{Evm, EvmWiring};
+use core::fmt;
+use database_interface::Database;
+use interpreter::gas::{self, CALL_STIPEND};
+use primitives::{decode_revert_reason, Bytes};
+use std::string::String;
+use wiring::{
+    default::TxEnv,
+    result::{EVMError, EVMErrorForChain, ExecutionResult, HaltReason, OutOfGasError},
+    Block, HaltReasonTrait,
+};
+
+/// [EstimateGasError] of an [EvmWiring].
+pub type EstimateGasErrorForChain<EvmWiringT> =
+    EstimateGasError<<EvmWiringT as wiring::EvmWiring>::HaltReason, EVMErrorForChain<EvmWiringT>>;
+
+/// Error of [estimate_gas].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EstimateGasError<HaltReasonT, ErrorT> {
+    /// The transaction reverts with the highest gas limit.
+    Reverted {
+        /// Revert data.
+        output: Bytes,
+        /// Decoded `Error(string)` or `Panic(uint256)` revert reason.
+        reason: Option<String>,
+    },
+    /// The transaction runs out of gas with the highest gas limit.
+    OutOfGas(u64),
+    /// The transaction halts with the highest gas limit, for another reason than running out of
+    /// gas.
+    Halted(HaltReasonT),
+    /// The transaction is invalid or the database failed.
+    Evm(ErrorT),
+}
+
+#[cfg(feature = "std")]
+impl<HaltReasonT, ErrorT> std::error::Error for EstimateGasError<HaltReasonT, ErrorT>
+where
+    HaltReasonT: fmt::Debug,
+    ErrorT: std::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Evm(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl<HaltReasonT, ErrorT> fmt::Display for EstimateGasError<HaltReasonT, ErrorT>
+where
+    HaltReasonT: fmt::Debug,
+    ErrorT: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Reverted {
+                reason: Some(reason),
+                ..
+            } => write!(f, "execution reverted: {reason}"),
+            Self::Reverted { .. } => f.write_str("execution reverted"),
+            Self::OutOfGas(gas_limit) => write!(f, "gas required exceeds allowance ({gas_limit})"),
+            Self::Halted(reason) => write!(f, "execution halted: {reason:?}"),
+            Self::Evm(e) => e.fmt(f),
+        }
+    }
+}
+
+/// Estimates the gas limit needed by the transaction of the [Evm], like `eth_estimateGas`.
+///
+/// The transaction is first executed with the highest gas limit: the gas limit of the block,
+/// capped by the gas limit of the transaction and the gas the caller can pay for. If it succeeds,
+/// the lowest gas limit that succeeds is searched, starting from the gas used plus the 1/64 of
+/// the gas kept by calls.
+///
+/// The gas limit of the transaction is restored and the state changes are not committed, so the
+/// database can be a [DatabaseRef](crate::DatabaseRef) wrapped in a
+/// [WrapDatabaseRef](database_interface::WrapDatabaseRef).
+pub fn estimate_gas<EvmWiringT>(
+    evm: &mut Evm<'_, EvmWiringT>,
+) -> Result<u64, EstimateGasErrorForChain<EvmWiringT>>
+where
+    EvmWiringT: EvmWiring<Transaction = TxEnv>,
+{
+    let gas_limit = evm.tx().gas_limit;
+    let estimate = estimate_gas_inner(evm);
+    evm.tx_mut().gas_limit = gas_limit;
+    estimate
+}
+
+fn estimate_gas_inner<EvmWiringT>(
+    evm: &mut Evm<'_, EvmWiringT>,
+) -> Result<u64, EstimateGasErrorForChain<EvmWiringT>>
+where
+    EvmWiringT: EvmWiring<Transaction = TxEnv>,
+{
+    let tx = evm.tx();
+    let mut hi = tx.gas_limit.min(evm.block().gas_limit().saturating_to());
+    if !tx.gas_price.is_zero() {
+        let (caller, value, gas_price) = (tx.caller, tx.value, tx.gas_price);
+        let balance = evm
+            .db_mut()
+            .basic(caller)
+            .map_err(|e| EstimateGasError::Evm(EVMError::Database(e)))?
+            .map(|account| account.balance)
+            .unwrap_or_default();
+        let allowance = balance.saturating_sub(value) / gas_price;
+        hi = hi.min(allowance.saturating_to());
+    }
+
+    let (gas_used, gas_refunded) = match transact(evm, hi)? {
+        ExecutionResult::Success {
+            gas_used,
+            gas_refunded,
+            ..
+        } => (gas_used, gas_refunded),
+        ExecutionResult::Revert { output, .. } => {
+            return Err(EstimateGasError::Reverted {
+                reason: decode_revert_reason(&output),
+                output,
+            })
+        }
+        ExecutionResult::Halt { reason, .. } if is_out_of_gas(&reason) => {
+            return Err(EstimateGasError::OutOfGas(hi))
+        }
+        ExecutionResult::Halt { reason, .. } => return Err(EstimateGasError::Halted(reason)),
+    };
+
+    // The gas used is after the refund, so less gas always fails. Before London, the refund can
+    // bring it below the intrinsic gas, which is the least gas a transaction can have.
+    let tx = evm.tx();
+    let intrinsic_gas = gas::validate_initial_tx_gas(
+        evm.spec_id().into(),
+        &tx.data,
+        tx.transact_to.is_create(),
+        Some(&tx.access_list),
+        tx.authorization_list.len() as u64,
+    );
+    let mut lo = gas_used.max(intrinsic_gas) - 1;
+    // Calls keep 1/64 of the gas, a bit more than the gas spent is usually enough.
+    let optimistic = (gas_used + gas_refunded + CALL_STIPEND) * 64 / 63;
+    if optimistic < hi {
+        if transact(evm, optimistic)?.is_success() {
+            hi = optimistic;
+        } else {
+            lo = optimistic;
+        }
+    }
+    while lo + 1 < hi {
+        // The transaction most likely succeeds with twice the gas used, so the search is
+        // skewed towards the lowest gas limit.
+        let mid = ((lo + hi) / 2).min(lo.saturating_mul(2));
+        if transact(evm, mid)?.is_success() {
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+    }
+    Ok(hi)
+}
+
+fn transact<EvmWiringT>(
+    evm: &mut Evm<'_, EvmWiringT>,
+    gas_limit: u64,
+) -> Result<ExecutionResult<EvmWiringT::HaltReason>, EstimateGasErrorForChain<EvmWiringT>>
+where
+    EvmWiringT: EvmWiring<Transaction = TxEnv>,
+{
+    evm.tx_mut().gas_limit = gas_limit;
+    evm.transact()
+        .map(|output| output.result)
+        .map_err(EstimateGasError::Evm)
+}
+
+/// Returns `true` if more gas can fix the halt.
+fn is_out_of_gas<HaltReasonT: HaltReasonTrait>(reason: &HaltReasonT) -> bool {
+    [
+        OutOfGasError::Basic,
+        OutOfGasError::Memory,
+        OutOfGasError::Precompile,
+        OutOfGasError::ReentrancySentry,
+    ]
+    .into_iter()
+    .any(|error| *reason == HaltReason::OutOfGas(error).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{account, revert_with_no, CALLER, CALLER_A};
+    use bytecode::opcode::*;
+    use database::InMemoryDB;
+    use database_interface::WrapDatabaseRef;
+    use primitives::{address, Address, U256};
+    use specification::hardfork::SpecId;
+    use state::AccountInfo;
+    use wiring::EthereumWiring;
+
+    const STORER: Address = address!("00000000000000000000000000000000000000bb");
+    const REVERTER: Address = address!("00000000000000000000000000000000000000cc");
+    const LOOPER: Address = address!("00000000000000000000000000000000000000dd");
+    const HALTER: Address = address!("00000000000000000000000000000000000000ee");
+    const CLEARER: Address = address!("00000000000000000000000000000000000000ff");
+
+    fn db() -> InMemoryDB {
+        // Calls the storer with all the gas and reverts if the call fails.
+        let mut caller_a = vec![PUSH1, 0, PUSH1, 0, PUSH1, 0, PUSH1, 0, PUSH1, 0, PUSH20];
+        caller_a.extend_from_slice(STORER.as_slice());
+        caller_a.extend([GAS, CALL, PUSH1, 41, JUMPI, PUSH1, 0, PUSH1, 0, REVERT]);
+        caller_a.extend([JUMPDEST, STOP]);
+
+        // Reverts with `Error("no")`.
+        let reverter = revert_with_no();
+
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(CALLER, AccountInfo::from_balance(U256::from(100_000)));
+        db.insert_account_info(CALLER_A, account(caller_a));
+        db.insert_account_info(STORER, account(vec![PUSH1, 1, PUSH1, 0, SSTORE, STOP]));
+        db.insert_account_info(REVERTER, account(reverter));
+        db.insert_account_info(LOOPER, account(vec![JUMPDEST, PUSH1, 0, JUMP]));
+        db.insert_account_info(HALTER, account(vec![INVALID]));
+        db.insert_account_info(CLEARER, account(vec![PUSH1, 0, PUSH1, 0, SSTORE, STOP]));
+        db.insert_account_storage(CLEARER, U256::ZERO, U256::from(1))
+            .unwrap();
+        db
+    }
+
+    fn build<'a, DB: Database>(db: DB, to: Address) -> Evm<'a, EthereumWiring<DB, ()>> {
+        Evm::<EthereumWiring<DB, ()>>::builder()
+            .with_db(db)
+            .with_default_ext_ctx()
+            .modify_block_env(|block| block.gas_limit = U256::from(1_000_000))
+            .modify_tx_env(|tx| {
+                tx.caller = CALLER;
+                tx.transact_to = to.into();
+            })
+            .build()
+    }
+
+    #[test]
+    fn estimate() {
+        let mut evm = build(db(), CALLER_A);
+        let estimate = estimate_gas(&mut evm).unwrap();
+        assert_eq!(evm.tx().gas_limit, u64::MAX);
+
+        evm.tx_mut().gas_limit = estimate;
+        let result = evm.transact().unwrap().result;
+        assert!(result.is_success());
+        // The call keeps 1/64 of the gas.
+        assert!(estimate > result.gas_used());
+        evm.tx_mut().gas_limit = estimate - 1;
+        assert!(!evm.transact().unwrap().result.is_success());
+
+        // Nothing is committed, so the database can be shared.
+        let db = db();
+        let mut evm = build(WrapDatabaseRef(&db), CALLER_A);
+        assert_eq!(estimate_gas(&mut evm), Ok(estimate));
+    }
+
+    #[test]
+    fn refund_below_intrinsic_gas() {
+        // Before London, clearing the slot refunds half of the gas spent.
+        let mut evm = build(db(), CLEARER);
+        evm.modify_spec_id(SpecId::BERLIN);
+        evm.tx_mut().gas_limit = 100_000;
+        assert!(evm.transact().unwrap().result.gas_used() < 21_000);
+
+        let estimate = estimate_gas(&mut evm).unwrap();
+        evm.tx_mut().gas_limit = estimate;
+        assert!(evm.transact().unwrap().result.is_success());
+        evm.tx_mut().gas_limit = estimate - 1;
+        assert!(!evm.transact().unwrap().result.is_success());
+    }
+
+    #[test]
+    fn errors() {
+        let db = db();
+        let estimate = |to, gas_price| {
+            let mut evm = build(WrapDatabaseRef(&db), to);
+            evm.tx_mut().gas_price = U256::from(gas_price);
+            estimate_gas(&mut evm)
+        };
+
+        let Err(EstimateGasError::Reverted { output, reason }) = estimate(REVERTER, 0) else {
+            panic!("expected a revert");
+        };
+        assert_eq!(output.len(), 100);
+        assert_eq!(reason.as_deref(), Some("no"));
+
+        // The gas limit is capped by the block and by the balance of the caller.
+        assert_eq!(
+            estimate(LOOPER, 0),
+            Err(EstimateGasError::OutOfGas(1_000_000))
+        );
+        assert_eq!(estimate(LOOPER, 2), Err(EstimateGasError::OutOfGas(50_000)));
+        assert_eq!(
+            estimate(HALTER, 0),
+            Err(EstimateGasError::Halted(HaltReason::InvalidFEOpcode))
+        );
+        assert!(matches!(
+            estimate(LOOPER, 10),
+            Err(EstimateGasError::Evm(EVMError::Transaction(_)))
+        ));
+    }
+}
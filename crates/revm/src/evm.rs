@@ -1,12 +1,16 @@
 use crate::{
     builder::{EvmBuilder, SetGenericStage},
-    handler::Handler,
+    handler::{mainnet, Handler},
     Context, ContextWithEvmWiring, EvmContext, EvmWiring, Frame, FrameOrResult, FrameResult,
     InnerEvmContext,
 };
 use core::fmt::{self, Debug};
 use database_interface::{Database, DatabaseCommit};
-use interpreter::{Host, InterpreterAction, NewFrameAction, SharedMemory};
+use interpreter::{
+    CallInputs, CallScheme, CallValue, Host, InterpreterAction, NewFrameAction, SharedMemory,
+};
+use primitives::{Address, Bytes, U256};
+use specification::eip4788::{SYSTEM_ADDRESS, SYSTEM_CALL_GAS_LIMIT};
 use std::{boxed::Box, vec::Vec};
 use wiring::{
     default::{CfgEnv, EnvWiring},
@@ -49,6 +53,19 @@ impl<EvmWiringT: EvmWiring<Database: DatabaseCommit>> Evm<'_, EvmWiringT> {
         self.context.evm.db.commit(state);
         Ok(result)
     }
+
+    /// Executes a system call and commits the changes of the contract to the database.
+    ///
+    /// See [Evm::system_call].
+    pub fn system_call_commit(
+        &mut self,
+        contract: Address,
+        data: Bytes,
+    ) -> EVMResultGeneric<ExecutionResult<EvmWiringT::HaltReason>, EvmWiringT> {
+        let ResultAndState { result, state } = self.system_call(contract, data)?;
+        self.context.evm.db.commit(state);
+        Ok(result)
+    }
 }
 
 impl<'a, EvmWiringT: EvmWiring> Evm<'a, EvmWiringT>
@@ -265,6 +282,24 @@ impl<EvmWiringT: EvmWiring> Evm<'_, EvmWiringT> {
         output
     }
 
+    /// Executes a system call, like the beacon root (EIP-4788) and block hash (EIP-2935)
+    /// updates made before the transactions of a block.
+    ///
+    /// `contract` is called with `data` from [SYSTEM_ADDRESS] with [SYSTEM_CALL_GAS_LIMIT] gas.
+    /// The transaction of the environment is neither validated nor executed, so there is no
+    /// fee deduction, nonce check or block gas accounting. Only the changes of `contract` are
+    /// returned in the state.
+    ///
+    /// Opcodes that read the transaction environment, like `ORIGIN` and `GASPRICE`, still see
+    /// the transaction of the environment.
+    pub fn system_call(&mut self, contract: Address, data: Bytes) -> EVMResult<EvmWiringT> {
+        let output = self.system_call_inner(contract, data);
+        self.clear();
+        let mut output = output?;
+        output.state.retain(|address, _| *address == contract);
+        Ok(output)
+    }
+
     /// Returns the reference of Env configuration
     #[inline]
     pub fn cfg(&self) -> &CfgEnv {
@@ -347,6 +382,33 @@ impl<EvmWiringT: EvmWiring> Evm<'_, EvmWiringT> {
         ContextWithEvmWiring::new(self.context, self.handler.spec_id)
     }
 
+    /// Executes a system call without clearing the journal.
+    fn system_call_inner(&mut self, contract: Address, data: Bytes) -> EVMResult<EvmWiringT> {
+        let ctx = &mut self.context;
+        let precompiles = self.handler.pre_execution().load_precompiles();
+        ctx.evm.set_precompiles(precompiles);
+
+        let inputs = Box::new(CallInputs {
+            input: data,
+            gas_limit: SYSTEM_CALL_GAS_LIMIT,
+            target_address: contract,
+            bytecode_address: contract,
+            caller: SYSTEM_ADDRESS,
+            value: CallValue::Transfer(U256::ZERO),
+            scheme: CallScheme::Call,
+            is_static: false,
+            is_eof: false,
+            return_memory_offset: 0..0,
+        });
+        let result = match self.handler.execution().call(ctx, inputs)? {
+            FrameOrResult::Frame(first_frame) => self.run_the_loop(first_frame)?,
+            FrameOrResult::Result(result) => result,
+        };
+
+        // The output of the mainnet handler does not depend on the transaction.
+        mainnet::output(&mut self.context, result)
+    }
+
     /// Transact pre-verified transaction.
     fn transact_preverified_inner(&mut self, initial_gas_spend: u64) -> EVMResult<EvmWiringT> {
         let ctx = &mut self.context;
@@ -410,15 +472,17 @@ mod tests {
 
     use super::*;
     use bytecode::{
-        opcode::{PUSH1, SSTORE},
+        opcode::{BALANCE, CALLDATALOAD, PUSH1, PUSH20, SSTORE, STOP, TIMESTAMP},
         Bytecode,
     };
-    use database::BenchmarkDB;
-    use primitives::{address, TxKind, U256};
+    use database::{BenchmarkDB, InMemoryDB};
+    use primitives::{address, b256, TxKind, U256};
+    use specification::eip4788::BEACON_ROOTS_ADDRESS;
     use specification::{
         eip7702::{Authorization, RecoveredAuthorization, Signature},
         hardfork::SpecId,
     };
+    use state::AccountInfo;
     use transaction::TransactionType;
     use wiring::EthereumWiring;
 
@@ -462,4 +526,53 @@ mod tests {
             U256::from(1)
         );
     }
+
+    #[test]
+    fn system_call() {
+        let other = address!("0000000000000000000000000000000000000b0b");
+        let root = b256!("1111111111111111111111111111111111111111111111111111111111111111");
+        // Stores the calldata at the timestamp slot and loads another account.
+        let mut code = vec![PUSH1, 0x00, CALLDATALOAD, TIMESTAMP, SSTORE, PUSH20];
+        code.extend_from_slice(other.as_slice());
+        code.extend_from_slice(&[BALANCE, STOP]);
+        let bytecode = Bytecode::new_legacy(code.into());
+
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(
+            BEACON_ROOTS_ADDRESS,
+            AccountInfo {
+                code_hash: bytecode.hash_slow(),
+                code: Some(bytecode),
+                ..Default::default()
+            },
+        );
+
+        let mut evm = Evm::<EthereumWiring<InMemoryDB, ()>>::builder()
+            .with_spec_id(SpecId::CANCUN)
+            .with_db(db)
+            .with_default_ext_ctx()
+            .modify_block_env(|block| block.timestamp = U256::from(1234))
+            .modify_tx_env(|tx| {
+                // The caller can not pay for this transaction.
+                tx.gas_limit = 1_000_000;
+                tx.gas_price = U256::from(1);
+            })
+            .build();
+
+        let ResultAndState { result, state } =
+            evm.system_call(BEACON_ROOTS_ADDRESS, root.into()).unwrap();
+        assert!(result.is_success());
+        assert_eq!(state.keys().collect::<Vec<_>>(), [&BEACON_ROOTS_ADDRESS]);
+
+        let result = evm.system_call_commit(BEACON_ROOTS_ADDRESS, root.into());
+        assert!(result.unwrap().is_success());
+        assert_eq!(
+            evm.db_mut()
+                .storage(BEACON_ROOTS_ADDRESS, U256::from(1234))
+                .unwrap(),
+            U256::from_be_bytes(root.0)
+        );
+        assert!(!evm.db().accounts.contains_key(&SYSTEM_ADDRESS));
+        assert!(evm.transact().is_err());
+    }
 }
@@ -0,0 +1,973 @@
+//! Execution of whole Ethereum blocks.
+use crate::Evm;
+use core::fmt;
+use database::{
+    states::bundle_state::BundleRetention, trie::ordered_trie_root, BundleState, State,
+};
+use database_interface::Database;
+use primitives::{alloy_primitives::Bloom, Address, Bytes, Log, B256, U256};
+use sha2::{Digest, Sha256};
+use specification::{
+    eip2935::HISTORY_STORAGE_ADDRESS,
+    eip4788::BEACON_ROOTS_ADDRESS,
+    eip6110::{
+        DEPOSIT_EVENT_SIGNATURE_HASH, DEPOSIT_REQUEST_TYPE, MAINNET_DEPOSIT_CONTRACT_ADDRESS,
+    },
+    eip7002::{WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS, WITHDRAWAL_REQUEST_TYPE},
+    eip7251::{CONSOLIDATION_REQUEST_PREDEPLOY_ADDRESS, CONSOLIDATION_REQUEST_TYPE},
+    hardfork::SpecId,
+};
+use std::{format, vec::Vec};
+use transaction::TransactionType;
+use wiring::{
+    default::{block::BlockEnv, CfgEnv, TxEnv},
+    result::{EVMError, ExecutionResult, InvalidTransaction},
+    EthereumWiring,
+};
+
+/// Block reward before Byzantium, 5 ether.
+pub const FRONTIER_BLOCK_REWARD: u128 = 5_000_000_000_000_000_000;
+
+/// Block reward from Byzantium, 3 ether.
+pub const BYZANTIUM_BLOCK_REWARD: u128 = 3_000_000_000_000_000_000;
+
+/// Block reward from Constantinople until the merge, 2 ether.
+pub const CONSTANTINOPLE_BLOCK_REWARD: u128 = 2_000_000_000_000_000_000;
+
+/// Returns the block reward of the spec, None after the merge.
+pub const fn block_reward(spec_id: SpecId) -> Option<u128> {
+    if SpecId::enabled(spec_id, SpecId::MERGE) {
+        None
+    } else if SpecId::enabled(spec_id, SpecId::CONSTANTINOPLE) {
+        Some(CONSTANTINOPLE_BLOCK_REWARD)
+    } else if SpecId::enabled(spec_id, SpecId::BYZANTIUM) {
+        Some(BYZANTIUM_BLOCK_REWARD)
+    } else {
+        Some(FRONTIER_BLOCK_REWARD)
+    }
+}
+
+/// Header of an ommer, as far as rewards are concerned.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ommer {
+    /// Number of the ommer.
+    pub number: u64,
+    /// Beneficiary of the ommer.
+    pub beneficiary: Address,
+}
+
+/// Withdrawal from the beacon chain (EIP-4895).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Withdrawal {
+    /// Monotonically increasing index of the withdrawal.
+    pub index: u64,
+    /// Index of the validator.
+    pub validator_index: u64,
+    /// Recipient of the withdrawal.
+    pub address: Address,
+    /// Amount in gwei.
+    pub amount: u64,
+}
+
+impl Withdrawal {
+    /// Returns the amount in wei.
+    pub const fn amount_wei(&self) -> u128 {
+        self.amount as u128 * 1_000_000_000
+    }
+}
+
+/// A block to execute.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Block {
+    /// The block environment built from the header.
+    pub env: BlockEnv,
+    /// Hash of the parent block, stored in the history storage contract since Prague.
+    pub parent_hash: B256,
+    /// The parent beacon block root of the header, required since Cancun.
+    pub parent_beacon_block_root: Option<B256>,
+    /// Ommers of the block, rewarded before the merge.
+    pub ommers: Vec<Ommer>,
+    /// Withdrawals of the block, required since Shanghai.
+    pub withdrawals: Option<Vec<Withdrawal>>,
+    /// The transactions of the block.
+    pub transactions: Vec<TxEnv>,
+}
+
+/// Receipt of a transaction executed in a block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Receipt {
+    /// Type of the transaction.
+    pub tx_type: TransactionType,
+    /// Whether the transaction succeeded.
+    pub success: bool,
+    /// State root after the transaction, held instead of the status before Byzantium.
+    ///
+    /// Only set when the executor has an
+    /// [intermediate state root](BlockExecutor::with_intermediate_state_root) function.
+    pub state_root: Option<B256>,
+    /// Gas used by this transaction and all the previous ones of the block.
+    pub cumulative_gas_used: u64,
+    /// Logs emitted by the transaction.
+    pub logs: Vec<Log>,
+}
+
+impl Receipt {
+    /// Returns the bloom filter of the logs.
+    pub fn logs_bloom(&self) -> Bloom {
+        let mut bloom = Bloom::ZERO;
+        for log in &self.logs {
+            bloom.accrue_log(log);
+        }
+        bloom
+    }
+
+    /// Encodes the receipt as in the receipts trie (EIP-2718).
+    ///
+    /// Typed receipts are prefixed by the transaction type. The state root is encoded instead
+    /// of the status when it is set.
+    pub fn encode_2718(&self, out: &mut Vec<u8>) {
+        use alloy_rlp::Encodable;
+
+        let tx_type = match self.tx_type {
+            TransactionType::Legacy | TransactionType::Custom => None,
+            TransactionType::Eip2930 => Some(1),
+            TransactionType::Eip1559 => Some(2),
+            TransactionType::Eip4844 => Some(3),
+            TransactionType::Eip7702 => Some(4),
+        };
+        out.extend(tx_type);
+
+        let bloom = self.logs_bloom();
+        let status_length = match &self.state_root {
+            Some(state_root) => state_root.length(),
+            None => self.success.length(),
+        };
+        let payload_length = status_length
+            + self.cumulative_gas_used.length()
+            + bloom.length()
+            + alloy_rlp::list_length(&self.logs);
+        alloy_rlp::Header {
+            list: true,
+            payload_length,
+        }
+        .encode(out);
+        match &self.state_root {
+            Some(state_root) => state_root.encode(out),
+            None => self.success.encode(out),
+        }
+        self.cumulative_gas_used.encode(out);
+        bloom.encode(out);
+        alloy_rlp::encode_list(&self.logs, out);
+    }
+}
+
+/// Result of the execution of a block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockExecutionOutput {
+    /// Receipts of the transactions, in block order.
+    pub receipts: Vec<Receipt>,
+    /// Gas used by the block.
+    pub gas_used: u64,
+    /// Deposit (EIP-6110), withdrawal (EIP-7002) and consolidation (EIP-7251) requests, empty
+    /// before Prague.
+    ///
+    /// Each request is prefixed by its type (EIP-7685). The deposits are read from the logs of
+    /// the deposit contract, the other requests are the outputs of their contracts.
+    pub requests: Vec<Bytes>,
+    /// State changes of the block, with the reverts.
+    pub bundle: BundleState,
+}
+
+impl BlockExecutionOutput {
+    /// Returns the receipts root of the block.
+    pub fn receipts_root(&self) -> B256 {
+        ordered_trie_root(self.receipts.iter().map(|receipt| {
+            let mut out = Vec::new();
+            receipt.encode_2718(&mut out);
+            out
+        }))
+    }
+
+    /// Returns the bloom filter of the logs of the block.
+    pub fn logs_bloom(&self) -> Bloom {
+        let mut bloom = Bloom::ZERO;
+        for receipt in &self.receipts {
+            bloom.accrue_bloom(&receipt.logs_bloom());
+        }
+        bloom
+    }
+
+    /// Returns the requests hash of the block (EIP-7685), None before Prague.
+    ///
+    /// The requests without data are not part of the hash.
+    pub fn requests_hash(&self) -> Option<B256> {
+        if self.requests.is_empty() {
+            return None;
+        }
+        let mut hasher = Sha256::new();
+        for request in self.requests.iter().filter(|request| request.len() > 1) {
+            hasher.update(Sha256::digest(request));
+        }
+        Some(B256::new(hasher.finalize().into()))
+    }
+}
+
+/// Error of the execution of a block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BlockExecutionError<DBError> {
+    /// The parent beacon block root is required since Cancun.
+    MissingParentBeaconBlockRoot,
+    /// The withdrawals are required since Shanghai.
+    MissingWithdrawals,
+    /// The gas limit of a transaction is above the gas left in the block.
+    TransactionGasLimitMoreThanAvailableBlockGas {
+        /// Index of the transaction in the block.
+        index: usize,
+        /// Gas limit of the transaction.
+        transaction_gas_limit: u64,
+        /// Gas left in the block.
+        block_available_gas: u64,
+    },
+    /// A transaction is invalid or the database failed.
+    Transaction {
+        /// Index of the transaction in the block.
+        index: usize,
+        /// The error of the transaction.
+        error: EVMError<DBError, InvalidTransaction>,
+    },
+    /// A request system contract (EIP-7002, EIP-7251) has no code.
+    MissingSystemContract(Address),
+    /// A deposit contract log is not a valid deposit event (EIP-6110).
+    InvalidDepositEvent,
+    /// A system call failed.
+    SystemCall {
+        /// The called contract.
+        address: Address,
+        /// The error of the call.
+        error: EVMError<DBError, InvalidTransaction>,
+    },
+    /// The database failed while applying the rewards or withdrawals.
+    Database(DBError),
+}
+
+#[cfg(feature = "std")]
+impl<DBError: std::error::Error + 'static> std::error::Error for BlockExecutionError<DBError> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Transaction { error, .. } | Self::SystemCall { error, .. } => Some(error),
+            Self::Database(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl<DBError: fmt::Display> fmt::Display for BlockExecutionError<DBError> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingParentBeaconBlockRoot => f.write_str("missing parent beacon block root"),
+            Self::MissingWithdrawals => f.write_str("missing withdrawals"),
+            Self::TransactionGasLimitMoreThanAvailableBlockGas {
+                index,
+                transaction_gas_limit,
+                block_available_gas,
+            } => write!(
+                f,
+                "transaction {index} gas limit {transaction_gas_limit} is more than the \
+                 available block gas {block_available_gas}"
+            ),
+            Self::Transaction { index, error } => write!(f, "transaction {index}: {error}"),
+            Self::MissingSystemContract(address) => {
+                write!(f, "system contract {address} has no code")
+            }
+            Self::InvalidDepositEvent => f.write_str("invalid deposit event"),
+            Self::SystemCall { address, error } => {
+                write!(f, "system call to {address} failed: {error}")
+            }
+            Self::Database(error) => write!(f, "database error: {error}"),
+        }
+    }
+}
+
+/// Executes Ethereum blocks on top of a database.
+///
+/// The changes of the executed blocks are kept in a [State], so consecutive blocks can be
+/// executed before flushing them to the database.
+#[derive(Debug)]
+pub struct BlockExecutor<DB> {
+    cfg: CfgEnv,
+    deposit_contract: Address,
+    intermediate_state_root: Option<fn(&State<DB>) -> B256>,
+    state: State<DB>,
+}
+
+impl<DB: Database> BlockExecutor<DB> {
+    /// Creates an executor on top of `db`, with the default configuration.
+    pub fn new(db: DB) -> Self {
+        Self {
+            cfg: CfgEnv::default(),
+            deposit_contract: MAINNET_DEPOSIT_CONTRACT_ADDRESS,
+            intermediate_state_root: None,
+            state: State::builder()
+                .with_database(db)
+                .with_bundle_update()
+                .build(),
+        }
+    }
+
+    /// Creates an executor on top of `state`, with the default configuration.
+    ///
+    /// The state needs bundle updates enabled for the block outputs to hold their changes.
+    pub fn from_state(state: State<DB>) -> Self {
+        Self {
+            cfg: CfgEnv::default(),
+            deposit_contract: MAINNET_DEPOSIT_CONTRACT_ADDRESS,
+            intermediate_state_root: None,
+            state,
+        }
+    }
+
+    /// Sets the configuration, like the chain ID, used to execute the blocks.
+    pub fn with_cfg(mut self, cfg: CfgEnv) -> Self {
+        self.cfg = cfg;
+        self
+    }
+
+    /// Returns the configuration.
+    pub fn cfg(&self) -> &CfgEnv {
+        &self.cfg
+    }
+
+    /// Sets the deposit contract whose logs are the deposit requests, the mainnet one by
+    /// default.
+    pub fn with_deposit_contract(mut self, deposit_contract: Address) -> Self {
+        self.deposit_contract = deposit_contract;
+        self
+    }
+
+    /// Sets the function computing the state root after each transaction, held by the receipts
+    /// before Byzantium.
+    ///
+    /// The state root can't be computed from the [State] alone as it only caches the loaded
+    /// accounts. Without this function, the receipts before Byzantium have no state root.
+    pub fn with_intermediate_state_root(mut self, state_root: fn(&State<DB>) -> B256) -> Self {
+        self.intermediate_state_root = Some(state_root);
+        self
+    }
+
+    /// Returns the state with the changes of the executed blocks.
+    pub fn state(&self) -> &State<DB> {
+        &self.state
+    }
+
+    /// Returns the state with the changes of the executed blocks.
+    pub fn state_mut(&mut self) -> &mut State<DB> {
+        &mut self.state
+    }
+
+    /// Consumes the executor and returns the state.
+    pub fn into_state(self) -> State<DB> {
+        self.state
+    }
+
+    /// Executes the block with the rules of `spec_id`, and returns the receipts and the state
+    /// changes of the block.
+    ///
+    /// The beacon root (EIP-4788) and block hash (EIP-2935) system calls are made before the
+    /// transactions, the request system calls (EIP-7002, EIP-7251) after them. The block is
+    /// invalid if a request contract has no code. The block and ommer rewards, or the
+    /// withdrawals after the merge, are applied last.
+    ///
+    /// On error, the state holds the changes of the transactions executed before the error
+    /// and the executor should be discarded.
+    pub fn execute_block(
+        &mut self,
+        spec_id: SpecId,
+        block: &Block,
+    ) -> Result<BlockExecutionOutput, BlockExecutionError<DB::Error>> {
+        self.state
+            .set_state_clear_flag(SpecId::enabled(spec_id, SpecId::SPURIOUS_DRAGON));
+
+        let withdrawals = match &block.withdrawals {
+            Some(withdrawals) => withdrawals.as_slice(),
+            None if SpecId::enabled(spec_id, SpecId::SHANGHAI) => {
+                return Err(BlockExecutionError::MissingWithdrawals)
+            }
+            None => &[],
+        };
+
+        let mut evm = Evm::<EthereumWiring<&mut State<DB>, ()>>::builder()
+            .with_db(&mut self.state)
+            .with_default_ext_ctx()
+            .with_spec_id(spec_id)
+            .modify_cfg_env(|cfg| *cfg = self.cfg.clone())
+            .with_block_env(block.env.clone())
+            .build();
+
+        // There is no parent in the genesis block.
+        if !block.env.number.is_zero() {
+            if SpecId::enabled(spec_id, SpecId::CANCUN) {
+                let parent_beacon_block_root = block
+                    .parent_beacon_block_root
+                    .ok_or(BlockExecutionError::MissingParentBeaconBlockRoot)?;
+                system_call(&mut evm, BEACON_ROOTS_ADDRESS, parent_beacon_block_root)?;
+            }
+            if SpecId::enabled(spec_id, SpecId::PRAGUE) {
+                system_call(&mut evm, HISTORY_STORAGE_ADDRESS, block.parent_hash)?;
+            }
+        }
+
+        // Receipts before Byzantium hold the state root instead of the status.
+        let intermediate_state_root = self
+            .intermediate_state_root
+            .filter(|_| !SpecId::enabled(spec_id, SpecId::BYZANTIUM));
+        let receipts = execute_transactions(&mut evm, block, intermediate_state_root)?;
+
+        let mut requests = Vec::new();
+        if SpecId::enabled(spec_id, SpecId::PRAGUE) {
+            requests.push(deposit_requests(self.deposit_contract, &receipts)?);
+            for (address, request_type) in [
+                (
+                    WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS,
+                    WITHDRAWAL_REQUEST_TYPE,
+                ),
+                (
+                    CONSOLIDATION_REQUEST_PREDEPLOY_ADDRESS,
+                    CONSOLIDATION_REQUEST_TYPE,
+                ),
+            ] {
+                let has_code = evm
+                    .db_mut()
+                    .basic(address)
+                    .map_err(BlockExecutionError::Database)?
+                    .is_some_and(|info| !info.is_empty_code_hash());
+                if !has_code {
+                    return Err(BlockExecutionError::MissingSystemContract(address));
+                }
+                let output = system_call(&mut evm, address, Bytes::new())?;
+                let mut request = Vec::with_capacity(1 + output.len());
+                request.push(request_type);
+                request.extend_from_slice(&output);
+                requests.push(request.into());
+            }
+        }
+        drop(evm);
+
+        let mut balance_increments = Vec::new();
+        if let Some(reward) = block_reward(spec_id) {
+            let number: u64 = block.env.number.try_into().unwrap_or(u64::MAX);
+            let mut beneficiary_reward = reward;
+            for ommer in &block.ommers {
+                beneficiary_reward += reward / 32;
+                let distance = number.saturating_sub(ommer.number);
+                let ommer_reward = reward * 8u128.saturating_sub(distance as u128) / 8;
+                balance_increments.push((ommer.beneficiary, ommer_reward));
+            }
+            balance_increments.push((block.env.coinbase, beneficiary_reward));
+        }
+        balance_increments.extend(
+            withdrawals
+                .iter()
+                .filter(|withdrawal| withdrawal.amount != 0)
+                .map(|withdrawal| (withdrawal.address, withdrawal.amount_wei())),
+        );
+        self.state
+            .increment_balances(balance_increments)
+            .map_err(BlockExecutionError::Database)?;
+
+        self.state.merge_transitions(BundleRetention::Reverts);
+        Ok(BlockExecutionOutput {
+            gas_used: receipts
+                .last()
+                .map(|receipt| receipt.cumulative_gas_used)
+                .unwrap_or_default(),
+            receipts,
+            requests,
+            bundle: self.state.take_bundle(),
+        })
+    }
+}
+
+/// Executes and commits the transactions of the block.
+fn execute_transactions<DB: Database>(
+    evm: &mut Evm<'_, EthereumWiring<&mut State<DB>, ()>>,
+    block: &Block,
+    intermediate_state_root: Option<fn(&State<DB>) -> B256>,
+) -> Result<Vec<Receipt>, BlockExecutionError<DB::Error>> {
+    let block_gas_limit: u64 = block.env.gas_limit.try_into().unwrap_or(u64::MAX);
+    let mut cumulative_gas_used = 0u64;
+    let mut receipts = Vec::with_capacity(block.transactions.len());
+    for (index, tx) in block.transactions.iter().enumerate() {
+        let block_available_gas = block_gas_limit - cumulative_gas_used;
+        if tx.gas_limit > block_available_gas {
+            return Err(
+                BlockExecutionError::TransactionGasLimitMoreThanAvailableBlockGas {
+                    index,
+                    transaction_gas_limit: tx.gas_limit,
+                    block_available_gas,
+                },
+            );
+        }
+
+        *evm.tx_mut() = tx.clone();
+        let result = evm
+            .transact_commit()
+            .map_err(|error| BlockExecutionError::Transaction { index, error })?;
+
+        cumulative_gas_used += result.gas_used();
+        receipts.push(Receipt {
+            tx_type: tx.tx_type,
+            success: result.is_success(),
+            state_root: intermediate_state_root.map(|state_root| state_root(evm.db())),
+            cumulative_gas_used,
+            logs: result.into_logs(),
+        });
+    }
+    Ok(receipts)
+}
+
+/// Layout of the `DepositEvent` data: the offset and the size of the pubkey, withdrawal
+/// credentials, amount, signature and index byte arrays.
+const DEPOSIT_EVENT_LAYOUT: [(usize, usize); 5] =
+    [(160, 48), (256, 32), (320, 8), (384, 96), (512, 8)];
+
+/// Length of the `DepositEvent` data.
+const DEPOSIT_EVENT_LENGTH: usize = 576;
+
+/// Returns the deposit requests (EIP-6110) read from the logs of the deposit contract.
+///
+/// Each deposit is the concatenation of the byte arrays of its `DepositEvent`.
+fn deposit_requests<DBError>(
+    deposit_contract: Address,
+    receipts: &[Receipt],
+) -> Result<Bytes, BlockExecutionError<DBError>> {
+    let mut request = Vec::from([DEPOSIT_REQUEST_TYPE]);
+    let logs = receipts
+        .iter()
+        .flat_map(|receipt| &receipt.logs)
+        .filter(|log| {
+            log.address == deposit_contract
+                && log.topics().first() == Some(&DEPOSIT_EVENT_SIGNATURE_HASH)
+        });
+    for log in logs {
+        let data = &log.data.data;
+        if data.len() != DEPOSIT_EVENT_LENGTH {
+            return Err(BlockExecutionError::InvalidDepositEvent);
+        }
+        let word = |offset: usize| U256::from_be_slice(&data[offset..offset + 32]);
+        for (index, (offset, size)) in DEPOSIT_EVENT_LAYOUT.into_iter().enumerate() {
+            if word(index * 32) != U256::from(offset) || word(offset) != U256::from(size) {
+                return Err(BlockExecutionError::InvalidDepositEvent);
+            }
+            request.extend_from_slice(&data[offset + 32..offset + 32 + size]);
+        }
+    }
+    Ok(request.into())
+}
+
+/// Makes a system call and commits its changes, failing if the call is not successful.
+fn system_call<DB: Database>(
+    evm: &mut Evm<'_, EthereumWiring<&mut State<DB>, ()>>,
+    address: Address,
+    data: impl Into<Bytes>,
+) -> Result<Bytes, BlockExecutionError<DB::Error>> {
+    let result = evm
+        .system_call_commit(address, data.into())
+        .map_err(|error| BlockExecutionError::SystemCall { address, error })?;
+    match result {
+        ExecutionResult::Success { output, .. } => Ok(output.into_data()),
+        result => Err(BlockExecutionError::SystemCall {
+            address,
+            error: EVMError::Custom(format!("unsuccessful call: {result:?}")),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_eips::eip2718::Encodable2718;
+    use bytecode::{
+        opcode::{CALLDATACOPY, CALLDATASIZE, LOG1, MSTORE8, PUSH1, PUSH32, RETURN, STOP},
+        Bytecode,
+    };
+    use database::InMemoryDB;
+    use primitives::{address, b256, bytes, keccak256, TxKind};
+    use state::AccountInfo;
+
+    const CALLER: Address = address!("0000000000000000000000000000000000000a11");
+    const CONTRACT: Address = address!("0000000000000000000000000000000000000c0d");
+    const COINBASE: Address = address!("0000000000000000000000000000000000000c0c");
+    const ETHER: u128 = 1_000_000_000_000_000_000;
+
+    fn executor() -> BlockExecutor<InMemoryDB> {
+        // PUSH1 0x2a PUSH1 0 PUSH1 0 LOG1 STOP
+        let code = Bytecode::new_legacy([PUSH1, 0x2a, PUSH1, 0, PUSH1, 0, LOG1, STOP].into());
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(
+            CALLER,
+            AccountInfo {
+                balance: U256::from(ETHER),
+                ..Default::default()
+            },
+        );
+        db.insert_account_info(
+            CONTRACT,
+            AccountInfo {
+                code_hash: code.hash_slow(),
+                code: Some(code),
+                ..Default::default()
+            },
+        );
+        BlockExecutor::new(db)
+    }
+
+    /// Returns an executor with `code` as the request contracts.
+    fn prague_executor(code: Bytecode) -> BlockExecutor<InMemoryDB> {
+        let mut executor = executor();
+        for address in [
+            WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS,
+            CONSOLIDATION_REQUEST_PREDEPLOY_ADDRESS,
+        ] {
+            executor.state_mut().insert_account(
+                address,
+                AccountInfo {
+                    code_hash: code.hash_slow(),
+                    code: Some(code.clone()),
+                    ..Default::default()
+                },
+            );
+        }
+        executor
+    }
+
+    fn block(number: u64, transactions: Vec<TxEnv>) -> Block {
+        Block {
+            env: BlockEnv {
+                number: U256::from(number),
+                coinbase: COINBASE,
+                gas_limit: U256::from(30_000_000),
+                ..Default::default()
+            },
+            transactions,
+            ..Default::default()
+        }
+    }
+
+    fn tx(nonce: u64) -> TxEnv {
+        TxEnv {
+            tx_type: TransactionType::Eip1559,
+            caller: CALLER,
+            gas_limit: 100_000,
+            transact_to: TxKind::Call(CONTRACT),
+            nonce,
+            ..Default::default()
+        }
+    }
+
+    fn balance(output: &BlockExecutionOutput, address: Address) -> Option<U256> {
+        output
+            .bundle
+            .account(&address)
+            .and_then(|account| account.info.as_ref())
+            .map(|info| info.balance)
+    }
+
+    #[test]
+    fn block_rewards() {
+        assert_eq!(block_reward(SpecId::FRONTIER), Some(5 * ETHER));
+        assert_eq!(block_reward(SpecId::BYZANTIUM), Some(3 * ETHER));
+        assert_eq!(block_reward(SpecId::LONDON), Some(2 * ETHER));
+        assert_eq!(block_reward(SpecId::MERGE), None);
+
+        let ommer = address!("00000000000000000000000000000000000000aa");
+        let mut block = block(10, vec![]);
+        block.ommers = vec![Ommer {
+            number: 9,
+            beneficiary: ommer,
+        }];
+        let output = executor().execute_block(SpecId::LONDON, &block).unwrap();
+        assert_eq!(
+            balance(&output, COINBASE),
+            Some(U256::from(2 * ETHER + 2 * ETHER / 32))
+        );
+        assert_eq!(balance(&output, ommer), Some(U256::from(2 * ETHER * 7 / 8)));
+
+        let output = executor().execute_block(SpecId::MERGE, &block).unwrap();
+        assert_eq!(balance(&output, COINBASE), None);
+    }
+
+    #[test]
+    fn withdrawals() {
+        let recipient = address!("00000000000000000000000000000000000000bb");
+        let empty = address!("00000000000000000000000000000000000000cc");
+        let mut block = block(1, vec![]);
+        assert_eq!(
+            executor().execute_block(SpecId::SHANGHAI, &block),
+            Err(BlockExecutionError::MissingWithdrawals)
+        );
+
+        block.withdrawals = Some(vec![
+            Withdrawal {
+                address: recipient,
+                amount: 3,
+                ..Default::default()
+            },
+            Withdrawal {
+                index: 1,
+                address: empty,
+                amount: 0,
+                ..Default::default()
+            },
+        ]);
+        let output = executor().execute_block(SpecId::SHANGHAI, &block).unwrap();
+        assert_eq!(
+            balance(&output, recipient),
+            Some(U256::from(3_000_000_000u64))
+        );
+        assert!(output.bundle.account(&empty).is_none());
+    }
+
+    #[test]
+    fn receipts() {
+        let output = executor()
+            .execute_block(SpecId::LONDON, &block(1, vec![tx(0), tx(1)]))
+            .unwrap();
+        assert_eq!(output.receipts.len(), 2);
+        assert_eq!(output.receipts[1].cumulative_gas_used, output.gas_used);
+        assert_eq!(output.gas_used, 2 * output.receipts[0].cumulative_gas_used);
+        assert_eq!(output.receipts[0].logs.len(), 1);
+        assert_eq!(output.logs_bloom(), output.receipts[0].logs_bloom());
+
+        for receipt in &output.receipts {
+            let reference = alloy_consensus::ReceiptEnvelope::Eip1559(
+                alloy_consensus::Receipt {
+                    status: receipt.success.into(),
+                    cumulative_gas_used: receipt.cumulative_gas_used as u128,
+                    logs: receipt.logs.clone(),
+                }
+                .with_bloom(),
+            );
+            let mut encoded = Vec::new();
+            receipt.encode_2718(&mut encoded);
+            assert_eq!(encoded, reference.encoded_2718());
+        }
+        // Computed with the `triehash` crate.
+        assert_eq!(
+            output.receipts_root(),
+            b256!("a82e9d544710c17c9eb2ff6b4e0209428f5aa020512b1a918edd121e0f778d5c")
+        );
+
+        let output = executor()
+            .execute_block(SpecId::LONDON, &block(1, vec![]))
+            .unwrap();
+        assert_eq!(
+            output.receipts_root(),
+            b256!("56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421")
+        );
+    }
+
+    #[test]
+    fn intermediate_state_roots() {
+        let legacy = |nonce| TxEnv {
+            tx_type: TransactionType::Legacy,
+            ..tx(nonce)
+        };
+        let block = block(1, vec![legacy(0), legacy(1)]);
+
+        let output = executor().execute_block(SpecId::HOMESTEAD, &block).unwrap();
+        assert!(output
+            .receipts
+            .iter()
+            .all(|receipt| receipt.state_root.is_none()));
+
+        // Stands for the state root after each transaction.
+        let caller_nonce = |state: &State<InMemoryDB>| {
+            B256::with_last_byte(
+                state.cache.accounts[&CALLER]
+                    .account
+                    .as_ref()
+                    .unwrap()
+                    .info
+                    .nonce as u8,
+            )
+        };
+        let output = executor()
+            .with_intermediate_state_root(caller_nonce)
+            .execute_block(SpecId::HOMESTEAD, &block)
+            .unwrap();
+        for (nonce, receipt) in output.receipts.iter().enumerate() {
+            let state_root = B256::with_last_byte(nonce as u8 + 1);
+            assert_eq!(receipt.state_root, Some(state_root));
+
+            let reference = alloy_consensus::ReceiptEnvelope::Legacy(
+                alloy_consensus::Receipt {
+                    status: alloy_consensus::Eip658Value::PostState(state_root),
+                    cumulative_gas_used: receipt.cumulative_gas_used as u128,
+                    logs: receipt.logs.clone(),
+                }
+                .with_bloom(),
+            );
+            let mut encoded = Vec::new();
+            receipt.encode_2718(&mut encoded);
+            // The list header of alloy miscounts the length of the state root by one byte.
+            let mut payload = encoded.as_slice();
+            let header = alloy_rlp::Header::decode(&mut payload).unwrap();
+            assert_eq!(header.payload_length, payload.len());
+            assert_eq!(payload, &reference.encoded_2718()[3..]);
+        }
+
+        // Receipts hold the status since Byzantium.
+        let output = executor()
+            .with_intermediate_state_root(caller_nonce)
+            .execute_block(SpecId::BYZANTIUM, &block)
+            .unwrap();
+        assert!(output
+            .receipts
+            .iter()
+            .all(|receipt| receipt.state_root.is_none()));
+    }
+
+    #[test]
+    fn transaction_gas_limit_more_than_available_block_gas() {
+        let mut block = block(1, vec![tx(0), tx(1)]);
+        block.env.gas_limit = U256::from(120_000);
+        let error = executor()
+            .execute_block(SpecId::LONDON, &block)
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            BlockExecutionError::TransactionGasLimitMoreThanAvailableBlockGas {
+                index: 1,
+                transaction_gas_limit: 100_000,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn prague_system_calls() {
+        let mut block = block(1, vec![]);
+        block.withdrawals = Some(vec![]);
+        block.env.set_blob_excess_gas_and_price(0);
+        assert_eq!(
+            executor().execute_block(SpecId::PRAGUE, &block),
+            Err(BlockExecutionError::MissingParentBeaconBlockRoot)
+        );
+
+        block.parent_beacon_block_root = Some(b256!(
+            "1111111111111111111111111111111111111111111111111111111111111111"
+        ));
+        assert_eq!(
+            executor().execute_block(SpecId::PRAGUE, &block),
+            Err(BlockExecutionError::MissingSystemContract(
+                WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS
+            ))
+        );
+
+        // Without requests, the hash is the hash of nothing.
+        let output = prague_executor(Bytecode::new_legacy([STOP].into()))
+            .execute_block(SpecId::PRAGUE, &block)
+            .unwrap();
+        assert_eq!(
+            output.requests,
+            vec![bytes!("00"), bytes!("01"), bytes!("02")]
+        );
+        assert_eq!(
+            output.requests_hash(),
+            Some(b256!(
+                "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+            ))
+        );
+
+        // PUSH1 0x2a PUSH1 0 MSTORE8 PUSH1 1 PUSH1 0 RETURN
+        let code = Bytecode::new_legacy(
+            [PUSH1, 0x2a, PUSH1, 0, MSTORE8, PUSH1, 1, PUSH1, 0, RETURN].into(),
+        );
+        let output = prague_executor(code)
+            .execute_block(SpecId::PRAGUE, &block)
+            .unwrap();
+        assert_eq!(
+            output.requests,
+            vec![bytes!("00"), bytes!("012a"), bytes!("022a")]
+        );
+        let mut hasher = Sha256::new();
+        hasher.update(Sha256::digest([1, 0x2a]));
+        hasher.update(Sha256::digest([2, 0x2a]));
+        assert_eq!(
+            output.requests_hash(),
+            Some(B256::new(hasher.finalize().into()))
+        );
+        assert_eq!(
+            executor()
+                .execute_block(SpecId::CANCUN, &block)
+                .unwrap()
+                .requests_hash(),
+            None
+        );
+    }
+
+    #[test]
+    fn deposit_requests() {
+        assert_eq!(
+            DEPOSIT_EVENT_SIGNATURE_HASH,
+            keccak256("DepositEvent(bytes,bytes,bytes,bytes,bytes)")
+        );
+
+        // Logs the calldata as a deposit event.
+        let mut code = vec![CALLDATASIZE, PUSH1, 0, PUSH1, 0, CALLDATACOPY, PUSH32];
+        code.extend_from_slice(DEPOSIT_EVENT_SIGNATURE_HASH.as_slice());
+        code.extend([CALLDATASIZE, PUSH1, 0, LOG1, STOP]);
+        let code = Bytecode::new_legacy(code.into());
+
+        let mut event = Vec::new();
+        for (offset, _) in DEPOSIT_EVENT_LAYOUT {
+            event.extend(U256::from(offset).to_be_bytes::<32>());
+        }
+        let mut expected = vec![DEPOSIT_REQUEST_TYPE];
+        for (index, (_, size)) in DEPOSIT_EVENT_LAYOUT.into_iter().enumerate() {
+            let field = vec![index as u8 + 1; size];
+            event.extend(U256::from(size).to_be_bytes::<32>());
+            event.extend(&field);
+            event.resize(event.len().next_multiple_of(32), 0);
+            expected.extend(field);
+        }
+        assert_eq!(event.len(), DEPOSIT_EVENT_LENGTH);
+        assert_eq!(expected.len(), 1 + 192);
+
+        let mut block = block(1, vec![]);
+        block.withdrawals = Some(vec![]);
+        block.parent_beacon_block_root = Some(B256::ZERO);
+        block.env.set_blob_excess_gas_and_price(0);
+        let deposit = |data: Vec<u8>| TxEnv {
+            transact_to: TxKind::Call(MAINNET_DEPOSIT_CONTRACT_ADDRESS),
+            data: data.into(),
+            ..tx(0)
+        };
+        let executor = || {
+            let mut executor = prague_executor(Bytecode::new_legacy([STOP].into()));
+            executor.state_mut().insert_account(
+                MAINNET_DEPOSIT_CONTRACT_ADDRESS,
+                AccountInfo {
+                    code_hash: code.hash_slow(),
+                    code: Some(code.clone()),
+                    ..Default::default()
+                },
+            );
+            executor
+        };
+
+        block.transactions = vec![deposit(event.clone())];
+        let output = executor().execute_block(SpecId::PRAGUE, &block).unwrap();
+        assert_eq!(output.requests[0], Bytes::from(expected));
+
+        event.pop();
+        block.transactions = vec![deposit(event)];
+        assert_eq!(
+            executor().execute_block(SpecId::PRAGUE, &block),
+            Err(BlockExecutionError::InvalidDepositEvent)
+        );
+    }
+}
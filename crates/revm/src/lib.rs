@@ -20,11 +20,14 @@ pub use wiring;
 // Define modules.
 mod builder;
 mod context;
+mod estimate_gas;
 #[cfg(any(test, feature = "test-utils"))]
 pub mod test_utils;
 
 mod evm;
 mod evm_wiring;
+#[cfg(feature = "executor")]
+pub mod executor;
 mod frame;
 pub mod handler;
 mod journaled_state;
@@ -38,6 +41,7 @@ pub use context::{
     ContextWithEvmWiring, EvmContext, InnerEvmContext,
 };
 pub use database_interface::{Database, DatabaseCommit, DatabaseRef};
+pub use estimate_gas::{estimate_gas, EstimateGasError, EstimateGasErrorForChain};
 pub use evm::{Evm, CALL_STACK_LIMIT};
 pub use evm_wiring::EvmWiring;
 pub use frame::{CallFrame, CreateFrame, Frame, FrameData, FrameOrResult, FrameResult};
@@ -1,2 +1,35 @@
 #[doc(hidden)]
 pub use crate::context::evm_context::test_utils::*;
+
+use bytecode::{opcode::*, Bytecode};
+use primitives::{address, bytes, Address};
+use state::AccountInfo;
+use std::{vec, vec::Vec};
+
+/// Sender of the test transactions.
+pub const CALLER: Address = address!("1000000000000000000000000000000000000000");
+
+/// Contract called by the test transactions.
+pub const CALLER_A: Address = address!("00000000000000000000000000000000000000aa");
+
+/// Creates an account with the given code.
+pub fn account(code: Vec<u8>) -> AccountInfo {
+    let code = Bytecode::new_raw(code.into());
+    AccountInfo {
+        code_hash: code.hash_slow(),
+        code: Some(code),
+        ..Default::default()
+    }
+}
+
+/// Returns the code reverting with `Error("no")`.
+pub fn revert_with_no() -> Vec<u8> {
+    let mut code = vec![PUSH32];
+    code.extend(bytes!(
+        "08c379a000000000000000000000000000000000000000000000000000000000"
+    ));
+    code.extend([PUSH1, 0, MSTORE, PUSH1, 0x20, PUSH1, 0x04, MSTORE, PUSH1, 2]);
+    code.extend([PUSH1, 0x24, MSTORE, PUSH2, 0x6e, 0x6f, PUSH1, 0xf0, SHL]);
+    code.extend([PUSH1, 0x44, MSTORE, PUSH1, 0x64, PUSH1, 0, REVERT]);
+    code
+}
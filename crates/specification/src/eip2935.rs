@@ -0,0 +1,9 @@
+//! EIP-2935: Serve historical block hashes from state
+
+use primitives::{address, Address};
+
+/// The address of the history storage contract.
+pub const HISTORY_STORAGE_ADDRESS: Address = address!("0000F90827F1C53a10cb7A02335B175320002935");
+
+/// The number of block hashes kept by the history storage contract.
+pub const HISTORY_SERVE_WINDOW: u64 = 8191;
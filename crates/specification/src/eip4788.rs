@@ -0,0 +1,15 @@
+//! EIP-4788: Beacon block root in the EVM
+
+use primitives::{address, Address};
+
+/// The caller of the system calls made before the transactions of a block.
+pub const SYSTEM_ADDRESS: Address = address!("fffffffffffffffffffffffffffffffffffffffe");
+
+/// The gas limit of a system call.
+pub const SYSTEM_CALL_GAS_LIMIT: u64 = 30_000_000;
+
+/// The address of the beacon roots contract.
+pub const BEACON_ROOTS_ADDRESS: Address = address!("000F3df6D732807Ef1319fB7B8bB8522d0Beac02");
+
+/// The number of beacon roots kept by the beacon roots contract.
+pub const HISTORY_BUFFER_LENGTH: u64 = 8191;
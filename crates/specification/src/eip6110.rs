@@ -0,0 +1,14 @@
+//! EIP-6110: Supply validator deposits on chain
+
+use primitives::{address, b256, Address, B256};
+
+/// The address of the deposit contract of the Ethereum mainnet.
+pub const MAINNET_DEPOSIT_CONTRACT_ADDRESS: Address =
+    address!("00000000219ab540356cbb839cbe05303d7705fa");
+
+/// The topic of the `DepositEvent(bytes,bytes,bytes,bytes,bytes)` log of the deposit contract.
+pub const DEPOSIT_EVENT_SIGNATURE_HASH: B256 =
+    b256!("649bbc62d0e31342afea4e5cd82d4049e7e1ee912fc0889aa790803be39038c5");
+
+/// The EIP-7685 type of the deposit requests.
+pub const DEPOSIT_REQUEST_TYPE: u8 = 0x00;
@@ -0,0 +1,10 @@
+//! EIP-7002: Execution layer triggerable withdrawals
+
+use primitives::{address, Address};
+
+/// The address of the withdrawal request contract.
+pub const WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS: Address =
+    address!("00000961Ef480Eb55e80D19ad83579A64c007002");
+
+/// The EIP-7685 type of the withdrawal requests.
+pub const WITHDRAWAL_REQUEST_TYPE: u8 = 0x01;
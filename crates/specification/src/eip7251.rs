@@ -0,0 +1,10 @@
+//! EIP-7251: Increase the MAX_EFFECTIVE_BALANCE
+
+use primitives::{address, Address};
+
+/// The address of the consolidation request contract.
+pub const CONSOLIDATION_REQUEST_PREDEPLOY_ADDRESS: Address =
+    address!("0000BBdDc7CE488642fb579F8B00f3a590007251");
+
+/// The EIP-7685 type of the consolidation requests.
+pub const CONSOLIDATION_REQUEST_TYPE: u8 = 0x02;
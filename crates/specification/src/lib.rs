@@ -9,6 +9,11 @@ pub mod constants;
 pub mod eip170;
 pub mod eip2;
 pub mod eip2930;
+pub mod eip2935;
+pub mod eip4788;
 pub mod eip4844;
+pub mod eip6110;
+pub mod eip7002;
+pub mod eip7251;
 pub mod eip7702;
 pub mod hardfork;
@@ -0,0 +1,222 @@
+use revm::{
+    primitives::{
+        alloy_primitives::{Bloom, B64},
+        Address, Bytes, B256, U256,
+    },
+    specification::eip2930::AccessList,
+    transaction::TransactionType,
+};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{deserializer::deserialize_maybe_empty, AccountInfo, SpecName, TestAuthorization};
+
+/// The top level blockchain test suite.
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+pub struct BlockchainTestSuite(pub BTreeMap<String, BlockchainTestUnit>);
+
+/// A single blockchain test: a chain of blocks applied on top of a genesis state.
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockchainTestUnit {
+    /// Test info is optional
+    #[serde(default, rename = "_info")]
+    pub info: Option<serde_json::Value>,
+
+    pub network: SpecName,
+    pub genesis_block_header: TestBlockHeader,
+    #[serde(default, rename = "genesisRLP")]
+    pub genesis_rlp: Option<Bytes>,
+    pub pre: HashMap<Address, AccountInfo>,
+    pub blocks: Vec<TestBlock>,
+    /// Post state, absent when the test only has the post state hash.
+    #[serde(default)]
+    pub post_state: Option<HashMap<Address, AccountInfo>>,
+    #[serde(default)]
+    pub post_state_hash: Option<B256>,
+    #[serde(rename = "lastblockhash")]
+    pub last_block_hash: B256,
+    #[serde(default)]
+    pub seal_engine: Option<String>,
+}
+
+/// A block of a blockchain test.
+///
+/// Blocks that are expected to be rejected have their RLP and the expected exception, and
+/// their content in `rlp_decoded` when the RLP can be decoded.
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestBlock {
+    #[serde(default)]
+    pub block_header: Option<TestBlockHeader>,
+    #[serde(default)]
+    pub transactions: Vec<TestTransaction>,
+    #[serde(default)]
+    pub uncle_headers: Vec<TestBlockHeader>,
+    #[serde(default)]
+    pub withdrawals: Option<Vec<TestWithdrawal>>,
+    #[serde(default)]
+    pub expect_exception: Option<String>,
+    /// RLP of the block, absent in `rlp_decoded`.
+    #[serde(default)]
+    pub rlp: Bytes,
+    #[serde(default, rename = "rlp_decoded")]
+    pub rlp_decoded: Option<Box<TestBlock>>,
+}
+
+impl TestBlock {
+    /// Returns the block with its header, from the decoded RLP if the block has no header.
+    ///
+    /// Returns `None` if the header is unknown.
+    pub fn decoded(&self) -> Option<(&TestBlock, &TestBlockHeader)> {
+        match (&self.block_header, &self.rlp_decoded) {
+            (Some(header), _) => Some((self, header)),
+            (None, Some(decoded)) => Some((decoded, decoded.block_header.as_ref()?)),
+            (None, None) => None,
+        }
+    }
+}
+
+/// Block header.
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestBlockHeader {
+    pub parent_hash: B256,
+    pub uncle_hash: B256,
+    pub coinbase: Address,
+    pub state_root: B256,
+    pub transactions_trie: B256,
+    pub receipt_trie: B256,
+    pub bloom: Bloom,
+    pub difficulty: U256,
+    pub number: U256,
+    pub gas_limit: U256,
+    pub gas_used: U256,
+    pub timestamp: U256,
+    pub extra_data: Bytes,
+    pub mix_hash: B256,
+    pub nonce: B64,
+    pub hash: B256,
+
+    pub base_fee_per_gas: Option<U256>,
+    pub withdrawals_root: Option<B256>,
+    pub blob_gas_used: Option<U256>,
+    pub excess_blob_gas: Option<U256>,
+    pub parent_beacon_block_root: Option<B256>,
+    pub requests_hash: Option<B256>,
+}
+
+/// Signed transaction of a block.
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestTransaction {
+    /// Transaction type, absent for legacy transactions.
+    #[serde(default, rename = "type")]
+    pub ty: Option<U256>,
+    pub chain_id: Option<U256>,
+    pub nonce: U256,
+    pub gas_price: Option<U256>,
+    pub max_priority_fee_per_gas: Option<U256>,
+    pub max_fee_per_gas: Option<U256>,
+    pub gas_limit: U256,
+    #[serde(default, deserialize_with = "deserialize_maybe_empty")]
+    pub to: Option<Address>,
+    pub value: U256,
+    pub data: Bytes,
+    pub access_list: Option<AccessList>,
+    pub max_fee_per_blob_gas: Option<U256>,
+    #[serde(default)]
+    pub blob_versioned_hashes: Vec<B256>,
+    pub authorization_list: Option<Vec<TestAuthorization>>,
+    pub v: U256,
+    pub r: U256,
+    pub s: U256,
+    /// The recovered signer, if sender is not present it needs to be recovered from the
+    /// signature.
+    #[serde(default)]
+    pub sender: Option<Address>,
+}
+
+impl TestTransaction {
+    /// Returns the transaction type.
+    ///
+    /// Returns `None` if the type is unknown.
+    pub fn tx_type(&self) -> Option<TransactionType> {
+        let Some(ty) = self.ty else {
+            return Some(TransactionType::Legacy);
+        };
+        match u8::try_from(ty).ok()? {
+            0 => Some(TransactionType::Legacy),
+            1 => Some(TransactionType::Eip2930),
+            2 => Some(TransactionType::Eip1559),
+            3 => Some(TransactionType::Eip4844),
+            4 => Some(TransactionType::Eip7702),
+            _ => None,
+        }
+    }
+}
+
+/// Withdrawal of a block.
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestWithdrawal {
+    pub index: U256,
+    pub validator_index: U256,
+    pub address: Address,
+    /// Amount in Gwei.
+    pub amount: U256,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize_blockchain_test() {
+        let suite: BlockchainTestSuite = serde_json::from_str(include_str!(
+            "../../../tests/blockchain_suite/valid_and_invalid_blocks.json"
+        ))
+        .unwrap();
+
+        let unit = &suite.0["shanghai_withdrawal"];
+        assert_eq!(unit.network, SpecName::Shanghai);
+        assert_eq!(unit.blocks.len(), 2);
+
+        let valid = &unit.blocks[0];
+        let (block, header) = valid.decoded().unwrap();
+        assert!(core::ptr::eq(block, valid));
+        assert_eq!(header.number, U256::from(1));
+        assert_eq!(header.base_fee_per_gas, Some(U256::from(7)));
+        assert!(header.withdrawals_root.is_some());
+        let tx_types: Vec<_> = block.transactions.iter().map(|tx| tx.tx_type()).collect();
+        assert_eq!(
+            tx_types,
+            [
+                Some(TransactionType::Legacy),
+                Some(TransactionType::Eip1559)
+            ]
+        );
+        assert_eq!(block.withdrawals.as_ref().unwrap().len(), 1);
+
+        // The rejected block only has its content in `rlp_decoded`.
+        let invalid = &unit.blocks[1];
+        assert!(invalid.block_header.is_none());
+        assert!(invalid.expect_exception.is_some());
+        let (block, header) = invalid.decoded().unwrap();
+        assert!(block.rlp.is_empty());
+        assert_eq!(header.number, U256::from(2));
+        assert_eq!(block.transactions.len(), 1);
+
+        let unit = &suite.0["frontier_legacy_receipts"];
+        assert_eq!(unit.network, SpecName::Frontier);
+        assert!(unit.blocks[0].withdrawals.is_none());
+        assert_eq!(
+            unit.blocks[0]
+                .block_header
+                .as_ref()
+                .unwrap()
+                .base_fee_per_gas,
+            None
+        );
+    }
+}
@@ -4,9 +4,11 @@
 //! specifically tailored for use with REVM.
 //!
 //! It includes structures for representing account information, environment settings,
-//! test cases, and transaction data used in Ethereum state tests.
+//! test cases, and transaction data used in Ethereum state tests, as well as the blocks,
+//! genesis and post state of Ethereum blockchain tests.
 
 mod account_info;
+mod blockchain_test;
 mod deserializer;
 mod env;
 mod spec;
@@ -17,6 +19,7 @@ mod test_unit;
 mod transaction;
 
 pub use account_info::*;
+pub use blockchain_test::*;
 pub use deserializer::*;
 pub use env::*;
 pub use spec::*;
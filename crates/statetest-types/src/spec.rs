@@ -23,8 +23,11 @@ pub enum SpecName {
     London,
     Paris,
     Merge,
+    ParisToShanghaiAtTime15k,
     Shanghai,
+    ShanghaiToCancunAtTime15k,
     Cancun,
+    CancunToPragueAtTime15k,
     Prague,
     Osaka, // SKIPPED
     #[serde(other)]
@@ -47,9 +50,9 @@ impl SpecName {
             Self::Berlin => SpecId::BERLIN,
             Self::London | Self::BerlinToLondonAt5 => SpecId::LONDON,
             Self::Paris | Self::Merge => SpecId::MERGE,
-            Self::Shanghai => SpecId::SHANGHAI,
-            Self::Cancun => SpecId::CANCUN,
-            Self::Prague => SpecId::PRAGUE,
+            Self::Shanghai | Self::ParisToShanghaiAtTime15k => SpecId::SHANGHAI,
+            Self::Cancun | Self::ShanghaiToCancunAtTime15k => SpecId::CANCUN,
+            Self::Prague | Self::CancunToPragueAtTime15k => SpecId::PRAGUE,
             Self::ByzantiumToConstantinopleAt5 | Self::Constantinople => {
                 panic!("Overridden with PETERSBURG")
             }
@@ -57,4 +60,23 @@ impl SpecName {
             Self::Unknown => panic!("Unknown spec"),
         }
     }
+
+    /// Convert to the spec id of a block.
+    ///
+    /// Transition specs of blockchain tests switch to the next spec at block 5 or at
+    /// timestamp 15000, the other specs are the same for all blocks.
+    pub fn to_spec_id_at(&self, number: u64, timestamp: u64) -> SpecId {
+        let before = match self {
+            Self::FrontierToHomesteadAt5 if number < 5 => Self::Frontier,
+            Self::HomesteadToDaoAt5 | Self::HomesteadToEIP150At5 if number < 5 => Self::Homestead,
+            Self::EIP158ToByzantiumAt5 if number < 5 => Self::EIP158,
+            Self::ByzantiumToConstantinopleFixAt5 if number < 5 => Self::Byzantium,
+            Self::BerlinToLondonAt5 if number < 5 => Self::Berlin,
+            Self::ParisToShanghaiAtTime15k if timestamp < 15_000 => Self::Paris,
+            Self::ShanghaiToCancunAtTime15k if timestamp < 15_000 => Self::Shanghai,
+            Self::CancunToPragueAtTime15k if timestamp < 15_000 => Self::Cancun,
+            _ => return self.to_spec_id(),
+        };
+        before.to_spec_id()
+    }
 }